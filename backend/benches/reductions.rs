@@ -0,0 +1,68 @@
+// Sequential versus pooled reductions over the compute module; run with `cargo bench`.
+// The crate only builds a binary, so the modules under test are compiled in directly; their
+// unit tests come along without a harness to run them
+#![allow(dead_code, unused_imports)]
+
+#[path = "../src/binning.rs"]
+mod binning;
+#[path = "../src/compute.rs"]
+mod compute;
+#[path = "../src/quantiles.rs"]
+mod quantiles;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use binning::{compute_histogram, HistogramOptions};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+const VALUES: usize = 5_000_000;
+const RUNS: usize = 7;
+
+fn values(n: usize) -> Vec<f64> {
+    (0..n).map(|i| ((i as f64 * 0.618_034).fract() - 0.5) * 1e3 + (i % 97) as f64).collect()
+}
+
+fn pool(threads: usize) -> ThreadPool {
+    ThreadPoolBuilder::new().num_threads(threads).build().expect("benchmark thread pool")
+}
+
+// Median of several timed runs after one warm-up run
+fn median_time<T: Send>(pool: &ThreadPool, job: &(dyn Fn() -> T + Sync)) -> Duration {
+    pool.install(|| black_box(job()));
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            pool.install(|| black_box(job()));
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    times[RUNS / 2]
+}
+
+fn compare<T: PartialEq + std::fmt::Debug + Send>(label: &str, sequential: &ThreadPool, parallel: &ThreadPool, job: &(dyn Fn() -> T + Sync)) {
+    // Chunked reductions merge in a fixed order, so thread count must not change the answer
+    assert_eq!(sequential.install(job), parallel.install(job), "{} differs between pools", label);
+
+    let single = median_time(sequential, job);
+    let pooled = median_time(parallel, job);
+    println!(
+        "{:<20} 1 thread {:>10.2?}   {} threads {:>10.2?}   speedup {:.2}x",
+        label, single, parallel.current_num_threads(), pooled, single.as_secs_f64() / pooled.as_secs_f64()
+    );
+}
+
+fn main() {
+    let data = values(VALUES);
+    let sequential = pool(1);
+    let parallel = pool(std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+    let options = HistogramOptions::default();
+
+    println!("{} values, median of {} runs", VALUES, RUNS);
+    compare("mean/std/min_max", &sequential, &parallel, &|| compute::moments(&data));
+    compare("sum", &sequential, &parallel, &|| {
+        compute::map_reduce_chunks(&data, |chunk| chunk.iter().sum::<f64>(), |a, b| a + b)
+    });
+    compare("histogram", &sequential, &parallel, &|| compute_histogram(&data, &options).expect("histogram").histogram);
+}
@@ -2,14 +2,27 @@ use anyhow::{Result, anyhow};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
 use tracing::info;
 
+use crate::binning::{self, BinPosition, BinningStrategy};
+use crate::casting::{self, CastConfig};
+use crate::compute::run_blocking;
+use crate::data_processor::rows_to_dataframe;
+use crate::export::{self, ExportSummary, ExportTarget};
+use crate::fuzzy_matching::{BlockedIndex, FuzzyCandidate, FuzzyMatchConfig};
+use crate::row_filter::FilterCondition;
+use crate::row_sort::{self, SortKey};
+
 // Advanced formula request structures
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AdvancedFormulaRequest {
     pub formula_type: String,
+    #[serde(default)]
     pub data: Vec<HashMap<String, Value>>,
+    #[serde(default)]
+    pub dataset_id: Option<String>,
     pub parameters: FormulaParameters,
     pub output_config: OutputConfig,
 }
@@ -25,6 +38,61 @@ pub struct FormulaParameters {
     pub lookup_key: Option<String>,
     pub return_column: Option<String>,
     pub optional_params: Vec<String>,
+    pub fuzzy_config: Option<FuzzyMatchConfig>,
+    pub rate_table: Option<Vec<HashMap<String, Value>>>,
+    pub conversion_config: Option<ConversionConfig>,
+    pub bucket_config: Option<BucketConfig>,
+    pub cast_config: Option<CastConfig>,
+    pub filter_condition: Option<FilterCondition>,
+    pub sort_keys: Option<Vec<SortKey>>,
+}
+
+// Column names used to read a CONVERT rate table
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ConversionConfig {
+    pub unit_column: String,
+    pub rate_column: String,
+    pub date_column: String,
+    pub target_unit: Option<String>,
+}
+
+impl Default for ConversionConfig {
+    fn default() -> Self {
+        Self {
+            unit_column: "currency".to_string(),
+            rate_column: "rate".to_string(),
+            date_column: "date".to_string(),
+            target_unit: None,
+        }
+    }
+}
+
+// Bin definition for BUCKET; values outside custom edges use the below/above labels or stay null
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct BucketConfig {
+    pub strategy: BinningStrategy,
+    pub bins: usize,
+    pub edges: Option<Vec<f64>>,
+    pub labels: Option<Vec<String>>,
+    pub below_label: Option<String>,
+    pub above_label: Option<String>,
+    pub null_label: Option<String>,
+}
+
+impl Default for BucketConfig {
+    fn default() -> Self {
+        Self {
+            strategy: BinningStrategy::EqualWidth,
+            bins: 5,
+            edges: None,
+            labels: None,
+            below_label: None,
+            above_label: None,
+            null_label: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -32,6 +100,8 @@ pub struct OutputConfig {
     pub output_column: String,
     pub include_metadata: bool,
     pub sample_size: Option<usize>,
+    #[serde(default)]
+    pub export: Option<ExportTarget>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -41,6 +111,11 @@ pub struct FormulaResult {
     pub metadata: HashMap<String, Value>,
     pub processing_time_ms: u64,
     pub formula_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<ExportSummary>,
+    // Rows left without a result or removed; reported even when metadata is not requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -123,6 +198,110 @@ impl AdvancedFormulaProcessor {
                 "Find employee department using employee ID".to_string(),
             ],
         });
+        
+        // Catalogue parameters name request fields: dotted paths reach into a nested config and [] into
+        // each list entry. As with VLOOKUP's default_value, other plain names are flags passed in optional_params
+        
+        // FUZZY_LOOKUP - Approximate matching against reference tables
+        self.supported_formulas.insert("FUZZY_LOOKUP".to_string(), FormulaInfo {
+            name: "FUZZY_LOOKUP".to_string(),
+            description: "Finds the closest matching row in a reference table and returns its value with a similarity score".to_string(),
+            complexity: "Advanced".to_string(),
+            required_params: vec!["input_columns".to_string(), "lookup_table".to_string(), "lookup_key".to_string(), "return_column".to_string()],
+            optional_params: vec!["fuzzy_config.metric".to_string(), "fuzzy_config.threshold".to_string(), "fuzzy_config.blocking_columns".to_string(), "fuzzy_config.blocking_prefix".to_string(), "fuzzy_config.case_sensitive".to_string(), "default_value".to_string()],
+            examples: vec![
+                "Match 'Acme Corp.' to vendor master entry 'ACME Corporation'".to_string(),
+                "Find customer ID by name using Jaro-Winkler with threshold 0.9".to_string(),
+                "Match product names within the same Category block".to_string(),
+            ],
+        });
+        
+        // FUZZY_DEDUPE - Cluster near-duplicate rows
+        self.supported_formulas.insert("FUZZY_DEDUPE".to_string(), FormulaInfo {
+            name: "FUZZY_DEDUPE".to_string(),
+            description: "Groups near-duplicate rows into clusters using a similarity metric and threshold".to_string(),
+            complexity: "Advanced".to_string(),
+            required_params: vec!["input_columns".to_string()],
+            optional_params: vec!["fuzzy_config.metric".to_string(), "fuzzy_config.threshold".to_string(), "fuzzy_config.blocking_columns".to_string(), "fuzzy_config.blocking_prefix".to_string(), "fuzzy_config.case_sensitive".to_string(), "drop_duplicates".to_string()],
+            examples: vec![
+                "Flag duplicate customers whose names differ only by spelling".to_string(),
+                "Deduplicate vendors by name within each Country".to_string(),
+                "Keep one row per fuzzy-matched company name".to_string(),
+            ],
+        });
+        
+        // CONVERT - Currency and unit normalization
+        self.supported_formulas.insert("CONVERT".to_string(), FormulaInfo {
+            name: "CONVERT".to_string(),
+            description: "Converts amounts to a common currency or unit using a local rate table, with per-date rates; input_columns are amount, unit and an optional date".to_string(),
+            complexity: "Intermediate".to_string(),
+            required_params: vec!["input_columns".to_string(), "rate_table".to_string()],
+            optional_params: vec!["conversion_config.target_unit".to_string(), "conversion_config.unit_column".to_string(), "conversion_config.rate_column".to_string(), "conversion_config.date_column".to_string()],
+            examples: vec![
+                "Convert Sales from Currency to USD using daily FX rates".to_string(),
+                "Normalize weights from lb and oz to kg".to_string(),
+                "Convert invoice amounts using the most recent earlier rate".to_string(),
+            ],
+        });
+        
+        // BUCKET - Numeric binning into labelled segments
+        self.supported_formulas.insert("BUCKET".to_string(), FormulaInfo {
+            name: "BUCKET".to_string(),
+            description: "Assigns each row a bucket label for the first input column using equal-width, quantile, or custom-edge bins".to_string(),
+            complexity: "Intermediate".to_string(),
+            required_params: vec!["input_columns".to_string()],
+            optional_params: vec!["bucket_config.strategy".to_string(), "bucket_config.bins".to_string(), "bucket_config.edges".to_string(), "bucket_config.labels".to_string(), "bucket_config.below_label".to_string(), "bucket_config.above_label".to_string(), "bucket_config.null_label".to_string()],
+            examples: vec![
+                "Segment customers into Low/Medium/High spend bands".to_string(),
+                "Split order values into quartiles".to_string(),
+                "Bucket ages with edges 0, 18, 35, 65, 120".to_string(),
+            ],
+        });
+        
+        // CAST - Typed conversion of text columns
+        self.supported_formulas.insert("CAST".to_string(), FormulaInfo {
+            name: "CAST".to_string(),
+            description: "Converts text columns to int, float, decimal, bool, date or datetime using locale, currency, percent and format rules".to_string(),
+            complexity: "Intermediate".to_string(),
+            required_params: vec!["input_columns".to_string(), "cast_config.target".to_string()],
+            optional_params: [
+                "locale", "decimal_separator", "thousands_separator", "currency_symbols", "percent_as_fraction", "formats",
+                "true_values", "false_values", "null_values", "scale", "on_error", "failure_sample",
+            ].iter().map(|field| format!("cast_config.{}", field)).collect(),
+            examples: vec![
+                "Cast '$1,200' and '(350)' in Amount to float".to_string(),
+                "Read German prices like '1.234,50 €' as decimals with scale 2".to_string(),
+                "Parse Order Date written as 01/03/2024 day first, nulling rows that do not match".to_string(),
+            ],
+        });
+        
+        // FILTER - Row selection with compound conditions
+        self.supported_formulas.insert("FILTER".to_string(), FormulaInfo {
+            name: "FILTER".to_string(),
+            description: "Keeps rows matching an AND/OR/NOT tree of column predicates".to_string(),
+            complexity: "Intermediate".to_string(),
+            required_params: vec!["filter_condition".to_string()],
+            optional_params: vec![],
+            examples: vec![
+                "Keep rows where Region in ('North', 'East') AND Amount between 100 and 500".to_string(),
+                "Keep rows where Email matches a regex OR Phone is not null".to_string(),
+                "Drop rows where Status is null".to_string(),
+            ],
+        });
+        
+        // SORT - Multi-key stable ordering
+        self.supported_formulas.insert("SORT".to_string(), FormulaInfo {
+            name: "SORT".to_string(),
+            description: "Sorts rows by several columns with per-column direction, null placement and natural ordering; without sort_keys, input_columns are sorted ascending".to_string(),
+            complexity: "Intermediate".to_string(),
+            required_params: vec!["sort_keys[].column".to_string()],
+            optional_params: vec!["sort_keys[].direction".to_string(), "sort_keys[].nulls".to_string(), "sort_keys[].case_insensitive".to_string(), "sort_keys[].natural".to_string(), "input_columns".to_string()],
+            examples: vec![
+                "Sort by Region ascending then Revenue descending".to_string(),
+                "Sort SKUs naturally so item2 comes before item10".to_string(),
+                "Sort by Close Date with empty dates first".to_string(),
+            ],
+        });
     }
     
     pub async fn process_advanced_formula(&self, request: AdvancedFormulaRequest) -> Result<FormulaResult> {
@@ -131,22 +310,45 @@ impl AdvancedFormulaProcessor {
         
         info!("Processing advanced formula: {} with {} rows", formula_type, request.data.len());
         
-        let result = match request.formula_type.to_uppercase().as_str() {
-            "SUMIFS" => self.process_sumifs(request).await?,
-            "PIVOT" => self.process_pivot(request).await?,
-            "TEXT_JOIN" => self.process_text_join(request).await?,
-            "VLOOKUP" => self.process_vlookup(request).await?,
+        let include_metadata = request.output_config.include_metadata;
+        let export_target = request.output_config.export.clone();
+        
+        let (result, metadata) = match request.formula_type.to_uppercase().as_str() {
+            "SUMIFS" => (self.process_sumifs(request).await?, HashMap::new()),
+            "PIVOT" => (self.process_pivot(request).await?, HashMap::new()),
+            "TEXT_JOIN" => (self.process_text_join(request).await?, HashMap::new()),
+            "VLOOKUP" => (self.process_vlookup(request).await?, HashMap::new()),
+            "FUZZY_LOOKUP" => self.process_fuzzy_lookup(request).await?,
+            "FUZZY_DEDUPE" => self.process_fuzzy_dedupe(request).await?,
+            "CONVERT" => self.process_convert(request).await?,
+            "BUCKET" => self.process_bucket(request).await?,
+            "CAST" => self.process_cast(request).await?,
+            "FILTER" => self.process_filter(request).await?,
+            "SORT" => self.process_sort(request).await?,
             _ => return Err(anyhow!("Unsupported formula type: {}", formula_type)),
         };
         
+        let warnings = formula_warnings(&formula_type.to_uppercase(), &metadata);
+        
+        let export = match export_target {
+            Some(target) => {
+                // The file is written on the blocking pool, as /export does
+                let mut df = rows_to_dataframe(&result, None)?;
+                Some(run_blocking(move || export::export_dataframe(&mut df, Path::new(&target.path), &target.options)).await?)
+            }
+            None => None,
+        };
+        
         let processing_time = start_time.elapsed().as_millis() as u64;
         
         Ok(FormulaResult {
             status: "success".to_string(),
             data: result,
-            metadata: HashMap::new(),
+            metadata: if include_metadata { metadata } else { HashMap::new() },
             processing_time_ms: processing_time,
             formula_type,
+            export,
+            warnings,
         })
     }
     
@@ -429,6 +631,439 @@ impl AdvancedFormulaProcessor {
         Ok(result_data)
     }
     
+    // FUZZY_LOOKUP Implementation - Approximate Reference Matching
+    async fn process_fuzzy_lookup(&self, request: AdvancedFormulaRequest) -> Result<(Vec<HashMap<String, Value>>, HashMap<String, Value>)> {
+        let data = request.data;
+        let config = request.parameters.fuzzy_config.clone().unwrap_or_default();
+        
+        let lookup_table = request.parameters.lookup_table
+            .as_ref()
+            .ok_or_else(|| anyhow!("FUZZY_LOOKUP requires a lookup table"))?;
+        
+        let lookup_key = request.parameters.lookup_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("FUZZY_LOOKUP requires a lookup key column"))?;
+        
+        let return_col = request.parameters.return_column
+            .as_ref()
+            .ok_or_else(|| anyhow!("FUZZY_LOOKUP requires a return column"))?;
+        
+        let match_col = request.parameters.input_columns.first()
+            .ok_or_else(|| anyhow!("FUZZY_LOOKUP requires a lookup value column"))?;
+        
+        let output_col = request.output_config.output_column.clone();
+        let score_col = format!("{}_score", output_col);
+        let matched_col = format!("{}_matched_key", output_col);
+        
+        let default_value = request.parameters.optional_params.iter()
+            .find_map(|p| p.strip_prefix("default_value:"))
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null);
+        
+        // Index the reference table by block key
+        let mut index = BlockedIndex::default();
+        for (i, row) in lookup_table.iter().enumerate() {
+            if let Some(key_value) = row.get(lookup_key).filter(|v| !v.is_null()) {
+                let normalized = config.normalize(&value_to_text(key_value));
+                let block_key = config.block_key(&normalized, &blocking_values(row, &config.blocking_columns));
+                index.insert(block_key, FuzzyCandidate { index: i, normalized });
+            }
+        }
+        
+        let mut matched_rows = 0usize;
+        let mut unmatched_rows = 0usize;
+        let mut comparisons = 0usize;
+        let mut score_total = 0.0;
+        let mut result_data = Vec::with_capacity(data.len());
+        
+        for row in data {
+            let mut result_row = row.clone();
+            
+            let found = row.get(match_col).filter(|v| !v.is_null()).and_then(|value| {
+                let probe = config.normalize(&value_to_text(value));
+                let block_key = config.block_key(&probe, &blocking_values(&row, &config.blocking_columns));
+                let (found, compared) = index.best_match(&config, &block_key, &probe);
+                comparisons += compared;
+                found
+            });
+            
+            match found {
+                Some(fuzzy_match) => {
+                    let reference_row = &lookup_table[fuzzy_match.index];
+                    result_row.insert(output_col.clone(), reference_row.get(return_col).cloned().unwrap_or(Value::Null));
+                    result_row.insert(matched_col.clone(), reference_row.get(lookup_key).cloned().unwrap_or(Value::Null));
+                    result_row.insert(score_col.clone(), json_number(fuzzy_match.score));
+                    score_total += fuzzy_match.score;
+                    matched_rows += 1;
+                }
+                None => {
+                    result_row.insert(output_col.clone(), default_value.clone());
+                    result_row.insert(matched_col.clone(), Value::Null);
+                    result_row.insert(score_col.clone(), Value::Null);
+                    unmatched_rows += 1;
+                }
+            }
+            
+            result_data.push(result_row);
+        }
+        
+        let mut metadata = fuzzy_metadata(&config, index.block_count(), comparisons);
+        metadata.insert("matched_rows".to_string(), Value::from(matched_rows));
+        metadata.insert("unmatched_rows".to_string(), Value::from(unmatched_rows));
+        metadata.insert("average_score".to_string(), if matched_rows > 0 {
+            json_number(score_total / matched_rows as f64)
+        } else {
+            Value::Null
+        });
+        
+        Ok((result_data, metadata))
+    }
+    
+    // FUZZY_DEDUPE Implementation - Near-Duplicate Clustering
+    async fn process_fuzzy_dedupe(&self, request: AdvancedFormulaRequest) -> Result<(Vec<HashMap<String, Value>>, HashMap<String, Value>)> {
+        let data = request.data;
+        let config = request.parameters.fuzzy_config.clone().unwrap_or_default();
+        let match_cols = &request.parameters.input_columns;
+        let drop_duplicates = request.parameters.optional_params.iter().any(|p| p == "drop_duplicates");
+        
+        let output_col = request.output_config.output_column.clone();
+        let score_col = format!("{}_score", output_col);
+        let duplicate_col = format!("{}_duplicate_of", output_col);
+        
+        // Each new cluster's first row acts as its representative; later rows in the
+        // same block join the most similar representative at or above the threshold
+        let mut representatives = BlockedIndex::default();
+        let mut cluster_of_row: HashMap<usize, usize> = HashMap::new();
+        let mut cluster_count = 0usize;
+        let mut duplicate_rows = 0usize;
+        let mut comparisons = 0usize;
+        let mut result_data = Vec::with_capacity(data.len());
+        
+        for (i, row) in data.into_iter().enumerate() {
+            let text = match_cols.iter()
+                .filter_map(|col| row.get(col).filter(|v| !v.is_null()))
+                .map(value_to_text)
+                .collect::<Vec<_>>()
+                .join(" ");
+            let normalized = config.normalize(&text);
+            let block_key = config.block_key(&normalized, &blocking_values(&row, &config.blocking_columns));
+            
+            let (found, compared) = if normalized.is_empty() {
+                (None, 0)
+            } else {
+                representatives.best_match(&config, &block_key, &normalized)
+            };
+            comparisons += compared;
+            
+            let mut result_row = row;
+            match found {
+                Some(fuzzy_match) => {
+                    let cluster_id = cluster_of_row[&fuzzy_match.index];
+                    duplicate_rows += 1;
+                    if drop_duplicates {
+                        continue;
+                    }
+                    result_row.insert(output_col.clone(), Value::from(cluster_id));
+                    result_row.insert(score_col.clone(), json_number(fuzzy_match.score));
+                    result_row.insert(duplicate_col.clone(), Value::from(fuzzy_match.index));
+                }
+                None => {
+                    let cluster_id = cluster_count;
+                    cluster_count += 1;
+                    cluster_of_row.insert(i, cluster_id);
+                    if !normalized.is_empty() {
+                        representatives.insert(block_key, FuzzyCandidate { index: i, normalized });
+                    }
+                    result_row.insert(output_col.clone(), Value::from(cluster_id));
+                    result_row.insert(score_col.clone(), json_number(1.0));
+                    result_row.insert(duplicate_col.clone(), Value::Null);
+                }
+            }
+            
+            result_data.push(result_row);
+        }
+        
+        let mut metadata = fuzzy_metadata(&config, representatives.block_count(), comparisons);
+        metadata.insert("clusters".to_string(), Value::from(cluster_count));
+        metadata.insert("duplicate_rows".to_string(), Value::from(duplicate_rows));
+        metadata.insert("duplicates_dropped".to_string(), Value::Bool(drop_duplicates));
+        
+        Ok((result_data, metadata))
+    }
+    
+    // CONVERT Implementation - Currency and Unit Normalization
+    async fn process_convert(&self, request: AdvancedFormulaRequest) -> Result<(Vec<HashMap<String, Value>>, HashMap<String, Value>)> {
+        let data = request.data;
+        let config = request.parameters.conversion_config.clone().unwrap_or_default();
+        
+        let rate_table = request.parameters.rate_table
+            .as_ref()
+            .ok_or_else(|| anyhow!("CONVERT requires a rate table"))?;
+        
+        let amount_col = request.parameters.input_columns.first()
+            .ok_or_else(|| anyhow!("CONVERT requires an amount column"))?;
+        
+        let unit_col = request.parameters.input_columns.get(1)
+            .ok_or_else(|| anyhow!("CONVERT requires a currency or unit column"))?;
+        
+        let date_col = request.parameters.input_columns.get(2);
+        
+        // Rates per unit: undated rows act as the fallback, dated rows are kept in date order
+        let mut undated_rates: HashMap<String, f64> = HashMap::new();
+        let mut dated_rates: HashMap<String, BTreeMap<chrono::NaiveDate, f64>> = HashMap::new();
+        
+        for (i, row) in rate_table.iter().enumerate() {
+            let unit = row.get(&config.unit_column)
+                .map(|v| value_to_text(v).trim().to_uppercase())
+                .filter(|u| !u.is_empty())
+                .ok_or_else(|| anyhow!("Rate table row {} has no '{}' value", i, config.unit_column))?;
+            
+            let rate = row.get(&config.rate_column)
+                .and_then(value_to_f64)
+                .filter(|r| r.is_finite())
+                .ok_or_else(|| anyhow!("Rate table row {} has no numeric '{}' value", i, config.rate_column))?;
+            
+            match row.get(&config.date_column).filter(|v| !v.is_null()) {
+                Some(date_value) => {
+                    let date = parse_date(date_value)
+                        .ok_or_else(|| anyhow!("Rate table row {} has an invalid date: {}", i, date_value))?;
+                    dated_rates.entry(unit).or_default().insert(date, rate);
+                }
+                None => {
+                    undated_rates.insert(unit, rate);
+                }
+            }
+        }
+        
+        let target_unit = config.target_unit.as_ref().map(|u| u.trim().to_uppercase());
+        let output_col = request.output_config.output_column.clone();
+        let rate_col = format!("{}_rate", output_col);
+        
+        let mut converted_rows = 0usize;
+        let mut missing_rate_rows = Vec::new();
+        let mut missing_units = HashSet::new();
+        let mut invalid_amount_rows = Vec::new();
+        let mut result_data = Vec::with_capacity(data.len());
+        
+        for (i, row) in data.into_iter().enumerate() {
+            let mut result_row = row;
+            
+            let unit = result_row.get(unit_col)
+                .map(|v| value_to_text(v).trim().to_uppercase())
+                .unwrap_or_default();
+            let row_date = date_col.and_then(|col| result_row.get(col)).and_then(parse_date);
+            
+            let rate = if target_unit.as_deref() == Some(unit.as_str()) {
+                Some(1.0)
+            } else {
+                dated_rates.get(&unit)
+                    .and_then(|rates| row_date.and_then(|date| rates.range(..=date).next_back()))
+                    .map(|(_, &rate)| rate)
+                    .or_else(|| undated_rates.get(&unit).copied())
+            };
+            
+            let amount = result_row.get(amount_col).and_then(value_to_f64);
+            
+            match (amount, rate) {
+                (Some(amount), Some(rate)) => {
+                    result_row.insert(output_col.clone(), json_number(amount * rate));
+                    result_row.insert(rate_col.clone(), json_number(rate));
+                    converted_rows += 1;
+                }
+                (_, None) => {
+                    result_row.insert(output_col.clone(), Value::Null);
+                    result_row.insert(rate_col.clone(), Value::Null);
+                    missing_rate_rows.push(i);
+                    missing_units.insert(unit);
+                }
+                (None, Some(rate)) => {
+                    result_row.insert(output_col.clone(), Value::Null);
+                    result_row.insert(rate_col.clone(), json_number(rate));
+                    invalid_amount_rows.push(i);
+                }
+            }
+            
+            result_data.push(result_row);
+        }
+        
+        let mut missing_units: Vec<String> = missing_units.into_iter().collect();
+        missing_units.sort();
+        
+        let mut metadata = HashMap::new();
+        metadata.insert("converted_rows".to_string(), Value::from(converted_rows));
+        metadata.insert("missing_rate_count".to_string(), Value::from(missing_rate_rows.len()));
+        metadata.insert("missing_rate_rows".to_string(), serde_json::json!(missing_rate_rows));
+        metadata.insert("missing_units".to_string(), serde_json::json!(missing_units));
+        metadata.insert("invalid_amount_rows".to_string(), serde_json::json!(invalid_amount_rows));
+        if let Some(target) = target_unit {
+            metadata.insert("target_unit".to_string(), Value::String(target));
+        }
+        
+        Ok((result_data, metadata))
+    }
+    
+    // BUCKET Implementation - Labelled Binning
+    async fn process_bucket(&self, request: AdvancedFormulaRequest) -> Result<(Vec<HashMap<String, Value>>, HashMap<String, Value>)> {
+        let data = request.data;
+        let config = request.parameters.bucket_config.clone().unwrap_or_default();
+        
+        let value_col = request.parameters.input_columns.first()
+            .ok_or_else(|| anyhow!("BUCKET requires a value column"))?;
+        
+        let values: Vec<Option<f64>> = data.iter()
+            .map(|row| row.get(value_col).and_then(value_to_f64).filter(|v| v.is_finite()))
+            .collect();
+        let present: Vec<f64> = values.iter().flatten().copied().collect();
+        
+        // Tied or constant data merges zero-width bins away; `requested` maps each remaining bin back
+        // to the requested bin it came from so labels written for every requested bin still apply
+        let (edges, requested) = match config.strategy {
+            BinningStrategy::Custom => {
+                let edges = config.edges.clone()
+                    .ok_or_else(|| anyhow!("BUCKET with custom strategy requires edges"))?;
+                binning::validate_edges(&edges)?;
+                let requested = (0..edges.len() - 1).collect();
+                (edges, requested)
+            }
+            _ if present.is_empty() => (Vec::new(), Vec::new()),
+            BinningStrategy::EqualWidth => binning::merge_edges(&binning::equal_width_edges(&present, config.bins)?),
+            BinningStrategy::Quantile => binning::merge_edges(&binning::quantile_cut_points(&present, config.bins)?),
+        };
+        
+        let bin_count = requested.len();
+        let requested_count = match config.strategy {
+            BinningStrategy::Custom => bin_count,
+            _ => config.bins,
+        };
+        let labels = match &config.labels {
+            Some(labels) if labels.len() == bin_count => labels.clone(),
+            Some(labels) if edges.is_empty() => labels.clone(),
+            Some(labels) if labels.len() == requested_count => requested.iter().map(|&i| labels[i].clone()).collect(),
+            Some(labels) => {
+                return Err(anyhow!("BUCKET expected {} labels but got {}", requested_count, labels.len()));
+            }
+            None => binning::default_labels(&edges),
+        };
+        
+        let output_col = request.output_config.output_column.clone();
+        let index_col = format!("{}_index", output_col);
+        let label_value = |label: &Option<String>| label.clone().map(Value::String).unwrap_or(Value::Null);
+        
+        let mut counts = vec![0usize; bin_count];
+        let mut null_count = 0usize;
+        let mut below_count = 0usize;
+        let mut above_count = 0usize;
+        let mut result_data = Vec::with_capacity(data.len());
+        
+        for (row, value) in data.into_iter().zip(values) {
+            let mut result_row = row;
+            
+            let (label, index) = match value.map(|v| binning::bin_position(&edges, v)) {
+                None => {
+                    null_count += 1;
+                    (label_value(&config.null_label), Value::Null)
+                }
+                Some(BinPosition::Below) => {
+                    below_count += 1;
+                    (label_value(&config.below_label), Value::Null)
+                }
+                Some(BinPosition::Above) => {
+                    above_count += 1;
+                    (label_value(&config.above_label), Value::Null)
+                }
+                Some(BinPosition::Bin(i)) => {
+                    counts[i] += 1;
+                    (Value::String(labels[i].clone()), Value::from(i))
+                }
+            };
+            
+            result_row.insert(output_col.clone(), label);
+            result_row.insert(index_col.clone(), index);
+            result_data.push(result_row);
+        }
+        
+        let mut metadata = HashMap::new();
+        metadata.insert("edges".to_string(), serde_json::json!(edges));
+        metadata.insert("labels".to_string(), serde_json::json!(labels));
+        metadata.insert("bucket_counts".to_string(), serde_json::json!(counts));
+        metadata.insert("requested_bins".to_string(), Value::from(requested_count));
+        metadata.insert("merged_bins".to_string(), Value::from(requested_count.saturating_sub(bin_count)));
+        metadata.insert("null_count".to_string(), Value::from(null_count));
+        metadata.insert("below_range_count".to_string(), Value::from(below_count));
+        metadata.insert("above_range_count".to_string(), Value::from(above_count));
+        
+        Ok((result_data, metadata))
+    }
+    
+    // CAST Implementation - Typed Column Conversion
+    async fn process_cast(&self, request: AdvancedFormulaRequest) -> Result<(Vec<HashMap<String, Value>>, HashMap<String, Value>)> {
+        let mut data = request.data;
+        let config = request.parameters.cast_config.clone().unwrap_or_default();
+        let columns = &request.parameters.input_columns;
+        
+        if columns.is_empty() {
+            return Err(anyhow!("CAST requires at least one input column"));
+        }
+        
+        // A single column may be written to the output column; several columns are converted in place
+        let output_col = &request.output_config.output_column;
+        let mut reports = Vec::with_capacity(columns.len());
+        for column in columns {
+            let target_col = if columns.len() == 1 && !output_col.is_empty() { output_col } else { column };
+            reports.push(casting::cast_rows(&mut data, column, target_col, &config)?);
+        }
+        
+        let mut metadata = HashMap::new();
+        metadata.insert("target".to_string(), Value::from(config.target.as_str()));
+        metadata.insert("failed_values".to_string(), Value::from(reports.iter().map(|r| r.failed).sum::<usize>()));
+        metadata.insert("cast_reports".to_string(), serde_json::to_value(&reports)?);
+        
+        Ok((data, metadata))
+    }
+    
+    // FILTER Implementation - Compound Row Selection
+    async fn process_filter(&self, request: AdvancedFormulaRequest) -> Result<(Vec<HashMap<String, Value>>, HashMap<String, Value>)> {
+        let condition = request.parameters.filter_condition
+            .as_ref()
+            .ok_or_else(|| anyhow!("FILTER requires a filter condition"))?
+            .compile()?;
+        
+        let input_rows = request.data.len();
+        let result_data: Vec<HashMap<String, Value>> = request.data.into_iter()
+            .filter(|row| condition.matches(row))
+            .collect();
+        
+        let mut metadata = HashMap::new();
+        metadata.insert("input_rows".to_string(), Value::from(input_rows));
+        metadata.insert("kept_rows".to_string(), Value::from(result_data.len()));
+        metadata.insert("dropped_rows".to_string(), Value::from(input_rows - result_data.len()));
+        
+        Ok((result_data, metadata))
+    }
+    
+    // SORT Implementation - Multi-Key Stable Ordering
+    async fn process_sort(&self, request: AdvancedFormulaRequest) -> Result<(Vec<HashMap<String, Value>>, HashMap<String, Value>)> {
+        let mut data = request.data;
+        
+        // Plain input columns sort ascending with nulls last when no explicit keys are given
+        let keys = match request.parameters.sort_keys {
+            Some(keys) if !keys.is_empty() => keys,
+            _ => request.parameters.input_columns.iter().map(|col| SortKey::ascending(col)).collect(),
+        };
+        
+        if keys.is_empty() {
+            return Err(anyhow!("SORT requires at least one sort key"));
+        }
+        
+        row_sort::sort_rows(&mut data, &keys);
+        
+        let mut metadata = HashMap::new();
+        metadata.insert("sort_keys".to_string(), serde_json::to_value(&keys)?);
+        metadata.insert("row_count".to_string(), Value::from(data.len()));
+        
+        Ok((data, metadata))
+    }
+    
     // Helper functions
     fn json_to_dataframe(&self, _data: &[HashMap<String, Value>]) -> Result<DataFrame> {
         // Simplified implementation - return empty DataFrame for now
@@ -459,8 +1094,9 @@ impl AdvancedFormulaProcessor {
             None => return Err(anyhow!("Unsupported formula: {}", request.formula_type)),
         };
         
-        // Basic validation - ensure we have input columns
-        if request.parameters.input_columns.is_empty() {
+        // Basic validation - ensure we have input columns (FILTER and SORT may name theirs in their own parameters)
+        let has_sort_keys = request.parameters.sort_keys.as_ref().is_some_and(|keys| !keys.is_empty());
+        if request.parameters.input_columns.is_empty() && formula_name != "FILTER" && !(formula_name == "SORT" && has_sort_keys) {
             return Err(anyhow!("At least one input column is required"));
         }
         
@@ -489,9 +1125,445 @@ impl AdvancedFormulaProcessor {
                     return Err(anyhow!("VLOOKUP requires lookup_table, lookup_key, and return_column"));
                 }
             },
+            "CONVERT" => {
+                if request.parameters.input_columns.len() < 2 {
+                    return Err(anyhow!("CONVERT requires amount and currency/unit input columns"));
+                }
+                if request.parameters.rate_table.is_none() {
+                    return Err(anyhow!("CONVERT requires a rate_table"));
+                }
+            },
+            "CAST" => {
+                if request.parameters.input_columns.is_empty() {
+                    return Err(anyhow!("CAST requires at least one input column"));
+                }
+                request.parameters.cast_config.clone().unwrap_or_default().compile()?;
+            },
+            "FILTER" => {
+                let condition = request.parameters.filter_condition.as_ref()
+                    .ok_or_else(|| anyhow!("FILTER requires a filter_condition"))?;
+                condition.compile()?;
+            },
+            "BUCKET" => {
+                let config = request.parameters.bucket_config.clone().unwrap_or_default();
+                if config.strategy == BinningStrategy::Custom {
+                    let edges = config.edges.as_ref()
+                        .ok_or_else(|| anyhow!("BUCKET with custom strategy requires edges"))?;
+                    binning::validate_edges(edges)?;
+                } else if config.bins == 0 {
+                    return Err(anyhow!("BUCKET requires at least one bin"));
+                }
+            },
+            "FUZZY_LOOKUP" | "FUZZY_DEDUPE" => {
+                if formula_name == "FUZZY_LOOKUP" && (request.parameters.lookup_table.is_none() || request.parameters.lookup_key.is_none() || request.parameters.return_column.is_none()) {
+                    return Err(anyhow!("FUZZY_LOOKUP requires lookup_table, lookup_key, and return_column"));
+                }
+                if let Some(config) = &request.parameters.fuzzy_config {
+                    if !(0.0..=1.0).contains(&config.threshold) {
+                        return Err(anyhow!("Fuzzy match threshold must be between 0 and 1"));
+                    }
+                    if config.blocking_prefix == Some(0) {
+                        return Err(anyhow!("Fuzzy match blocking_prefix must be at least 1"));
+                    }
+                }
+            },
             _ => {}
         }
         
         Ok(())
     }
 }
+
+// Diagnostics from the formula metadata that mean rows were left without a result or removed
+fn formula_warnings(formula_name: &str, metadata: &HashMap<String, Value>) -> Vec<String> {
+    let count = |key: &str| metadata.get(key).and_then(Value::as_u64).unwrap_or(0);
+    let list = |key: &str| metadata.get(key).and_then(Value::as_array).map(|values| {
+        values.iter().map(value_to_text).collect::<Vec<_>>().join(", ")
+    }).unwrap_or_default();
+    let mut warnings = Vec::new();
+    
+    match formula_name {
+        "CONVERT" => {
+            if count("missing_rate_count") > 0 {
+                warnings.push(format!("{} rows have no rate on or before their date and were left null (units: {})", count("missing_rate_count"), list("missing_units")));
+            }
+            let invalid = metadata.get("invalid_amount_rows").and_then(Value::as_array).map_or(0, |rows| rows.len());
+            if invalid > 0 {
+                warnings.push(format!("{} rows have an amount that is not a number and were left null", invalid));
+            }
+        }
+        "FUZZY_LOOKUP" if count("unmatched_rows") > 0 => {
+            warnings.push(format!("{} rows found no match above the threshold and received the default value", count("unmatched_rows")));
+        }
+        "FUZZY_DEDUPE" if count("duplicate_rows") > 0 && metadata.get("duplicates_dropped") == Some(&Value::Bool(true)) => {
+            warnings.push(format!("{} duplicate rows were dropped", count("duplicate_rows")));
+        }
+        "CAST" if count("failed_values") > 0 => {
+            warnings.push(format!("{} values could not be cast to {} and were set to null", count("failed_values"), metadata.get("target").map(value_to_text).unwrap_or_default()));
+        }
+        "BUCKET" if count("below_range_count") + count("above_range_count") > 0 => {
+            warnings.push(format!("{} values fell outside the bucket edges", count("below_range_count") + count("above_range_count")));
+        }
+        _ => {}
+    }
+    
+    warnings
+}
+
+pub(crate) fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => String::new(),
+        _ => value.to_string(),
+    }
+}
+
+pub(crate) fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+// Accepts plain dates (2024-03-01) and RFC 3339 timestamps
+fn parse_date(value: &Value) -> Option<chrono::NaiveDate> {
+    let text = value.as_str()?.trim();
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()
+        .or_else(|| chrono::DateTime::parse_from_rfc3339(text).ok().map(|dt| dt.date_naive()))
+        .or_else(|| chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").ok().map(|dt| dt.date()))
+}
+
+fn json_number(value: f64) -> Value {
+    serde_json::Number::from_f64(value).map(Value::Number).unwrap_or(Value::Null)
+}
+
+fn blocking_values(row: &HashMap<String, Value>, blocking_columns: &[String]) -> Vec<String> {
+    blocking_columns.iter()
+        .map(|col| row.get(col).map(value_to_text).unwrap_or_default().trim().to_lowercase())
+        .collect()
+}
+
+fn fuzzy_metadata(config: &FuzzyMatchConfig, block_count: usize, comparisons: usize) -> HashMap<String, Value> {
+    let mut metadata = HashMap::new();
+    metadata.insert("metric".to_string(), Value::String(config.metric.as_str().to_string()));
+    metadata.insert("threshold".to_string(), json_number(config.threshold));
+    metadata.insert("blocks".to_string(), Value::from(block_count));
+    metadata.insert("comparisons".to_string(), Value::from(comparisons));
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn build_request(value: Value) -> AdvancedFormulaRequest {
+        serde_json::from_value(value).unwrap()
+    }
+    
+    #[test]
+    fn test_catalogue_parameters_name_request_fields() {
+        let processor = AdvancedFormulaProcessor::new();
+        let parameters = serde_json::to_value(build_request(serde_json::json!({
+            "formula_type": "FILTER",
+            "parameters": {"input_columns": [], "optional_params": []},
+            "output_config": {"output_column": "out", "include_metadata": false}
+        })).parameters).unwrap();
+        let flags = ["default_value", "drop_duplicates"];
+        
+        for name in ["FUZZY_LOOKUP", "FUZZY_DEDUPE", "CONVERT", "BUCKET", "CAST", "FILTER", "SORT"] {
+            let info = processor.get_formula_info(name).unwrap();
+            for param in info.required_params.iter().chain(&info.optional_params) {
+                let field = param.split(['.', '[']).next().unwrap();
+                assert!(parameters.get(field).is_some() || flags.contains(&param.as_str()), "{} advertises '{}', which is not a request field", name, param);
+            }
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_fuzzy_lookup_returns_scores() {
+        let processor = AdvancedFormulaProcessor::new();
+        let request = build_request(serde_json::json!({
+            "formula_type": "FUZZY_LOOKUP",
+            "data": [
+                {"vendor": "Acme Corp."},
+                {"vendor": "Globex"},
+                {"vendor": "Initech"}
+            ],
+            "parameters": {
+                "input_columns": ["vendor"],
+                "lookup_table": [
+                    {"name": "ACME Corp", "vendor_id": "V1"},
+                    {"name": "Globex Corporation", "vendor_id": "V2"}
+                ],
+                "lookup_key": "name",
+                "return_column": "vendor_id",
+                "optional_params": [],
+                "fuzzy_config": {"metric": "jaro_winkler", "threshold": 0.85}
+            },
+            "output_config": {"output_column": "vendor_id", "include_metadata": true}
+        }));
+        
+        assert!(processor.validate_formula_request(&request).is_ok());
+        let result = processor.process_advanced_formula(request).await.unwrap();
+        
+        assert_eq!(result.data[0]["vendor_id"], "V1");
+        assert!(result.data[0]["vendor_id_score"].as_f64().unwrap() >= 0.85);
+        assert_eq!(result.data[2]["vendor_id"], Value::Null);
+        assert_eq!(result.metadata["matched_rows"], 2);
+        assert_eq!(result.metadata["unmatched_rows"], 1);
+        assert_eq!(result.warnings, vec!["1 rows found no match above the threshold and received the default value"]);
+    }
+    
+    #[tokio::test]
+    async fn test_fuzzy_dedupe_clusters_within_blocks() {
+        let processor = AdvancedFormulaProcessor::new();
+        let request = build_request(serde_json::json!({
+            "formula_type": "FUZZY_DEDUPE",
+            "data": [
+                {"name": "Acme Corporation", "country": "US"},
+                {"name": "ACME Corporation Inc", "country": "US"},
+                {"name": "Acme Corporation", "country": "DE"}
+            ],
+            "parameters": {
+                "input_columns": ["name"],
+                "optional_params": ["drop_duplicates"],
+                "fuzzy_config": {"metric": "token_set", "threshold": 0.8, "blocking_columns": ["country"]}
+            },
+            "output_config": {"output_column": "cluster", "include_metadata": true}
+        }));
+        
+        let result = processor.process_advanced_formula(request).await.unwrap();
+        
+        assert_eq!(result.data.len(), 2);
+        assert_eq!(result.metadata["clusters"], 2);
+        assert_eq!(result.metadata["duplicate_rows"], 1);
+        assert_eq!(result.warnings, vec!["1 duplicate rows were dropped"]);
+    }
+    
+    #[tokio::test]
+    async fn test_convert_uses_nearest_earlier_rate() {
+        let processor = AdvancedFormulaProcessor::new();
+        let request = build_request(serde_json::json!({
+            "formula_type": "CONVERT",
+            "data": [
+                {"amount": 100.0, "currency": "EUR", "date": "2024-03-05"},
+                {"amount": 50.0, "currency": "usd", "date": "2024-03-05"},
+                {"amount": 10.0, "currency": "GBP", "date": "2024-03-05"},
+                {"amount": 20.0, "currency": "EUR", "date": "2024-02-01"}
+            ],
+            "parameters": {
+                "input_columns": ["amount", "currency", "date"],
+                "optional_params": [],
+                "rate_table": [
+                    {"currency": "EUR", "rate": 1.10, "date": "2024-03-01"},
+                    {"currency": "EUR", "rate": 1.20, "date": "2024-03-10"}
+                ],
+                "conversion_config": {"target_unit": "USD"}
+            },
+            "output_config": {"output_column": "amount_usd", "include_metadata": true}
+        }));
+        
+        assert!(processor.validate_formula_request(&request).is_ok());
+        let mut quiet = request.clone();
+        quiet.output_config.include_metadata = false;
+        let result = processor.process_advanced_formula(request).await.unwrap();
+        
+        assert!((result.data[0]["amount_usd"].as_f64().unwrap() - 110.0).abs() < 1e-9);
+        assert_eq!(result.data[1]["amount_usd"], 50.0);
+        assert_eq!(result.data[2]["amount_usd"], Value::Null);
+        assert_eq!(result.data[3]["amount_usd"], Value::Null);
+        assert_eq!(result.metadata["missing_rate_rows"], serde_json::json!([2, 3]));
+        assert_eq!(result.metadata["missing_units"], serde_json::json!(["EUR", "GBP"]));
+        assert_eq!(result.warnings, vec!["2 rows have no rate on or before their date and were left null (units: EUR, GBP)"]);
+        
+        // Missing rates are still reported when metadata is not requested
+        let result = processor.process_advanced_formula(quiet).await.unwrap();
+        assert!(result.metadata.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_bucket_custom_edges_with_labels() {
+        let processor = AdvancedFormulaProcessor::new();
+        let request = build_request(serde_json::json!({
+            "formula_type": "BUCKET",
+            "data": [
+                {"spend": 50.0},
+                {"spend": 250.0},
+                {"spend": 5000.0},
+                {"spend": null},
+                {"spend": -10.0}
+            ],
+            "parameters": {
+                "input_columns": ["spend"],
+                "optional_params": [],
+                "bucket_config": {
+                    "strategy": "custom",
+                    "edges": [0.0, 100.0, 1000.0],
+                    "labels": ["Low", "Medium"],
+                    "above_label": "High",
+                    "null_label": "Unknown"
+                }
+            },
+            "output_config": {"output_column": "band", "include_metadata": true}
+        }));
+        
+        assert!(processor.validate_formula_request(&request).is_ok());
+        let result = processor.process_advanced_formula(request).await.unwrap();
+        
+        let bands: Vec<Value> = result.data.iter().map(|row| row["band"].clone()).collect();
+        assert_eq!(bands, vec![
+            Value::from("Low"), Value::from("Medium"), Value::from("High"), Value::from("Unknown"), Value::Null,
+        ]);
+        assert_eq!(result.metadata["bucket_counts"], serde_json::json!([1, 1]));
+        assert_eq!(result.metadata["below_range_count"], 1);
+        assert_eq!(result.warnings, vec!["2 values fell outside the bucket edges"]);
+    }
+    
+    #[tokio::test]
+    async fn test_cast_nulls_failures_and_reports_sample() {
+        let processor = AdvancedFormulaProcessor::new();
+        let request = build_request(serde_json::json!({
+            "formula_type": "CAST",
+            "data": [
+                {"price": "1.234,50 €"},
+                {"price": "12,5%"},
+                {"price": "n/v"},
+                {"price": null}
+            ],
+            "parameters": {
+                "input_columns": ["price"],
+                "optional_params": [],
+                "cast_config": {"target": "float", "locale": "de"}
+            },
+            "output_config": {"output_column": "price_value", "include_metadata": true}
+        }));
+        
+        assert!(processor.validate_formula_request(&request).is_ok());
+        let result = processor.process_advanced_formula(request).await.unwrap();
+        
+        let values: Vec<Value> = result.data.iter().map(|row| row["price_value"].clone()).collect();
+        assert_eq!(values, vec![Value::from(1234.5), Value::from(0.125), Value::Null, Value::Null]);
+        assert_eq!(result.data[2]["price"], "n/v");
+        assert_eq!(result.metadata["failed_values"], 1);
+        assert_eq!(result.warnings, vec!["1 values could not be cast to float and were set to null"]);
+        let report = &result.metadata["cast_reports"][0];
+        assert_eq!((report["converted"].clone(), report["null_inputs"].clone()), (Value::from(2), Value::from(1)));
+        assert_eq!(report["failures"][0]["row"], 2);
+        assert_eq!(report["failures"][0]["value"], "n/v");
+        
+        let strict = build_request(serde_json::json!({
+            "formula_type": "CAST",
+            "data": [{"qty": "7"}, {"qty": "seven"}],
+            "parameters": {
+                "input_columns": ["qty"],
+                "optional_params": [],
+                "cast_config": {"target": "int", "on_error": "error"}
+            },
+            "output_config": {"output_column": "", "include_metadata": false}
+        }));
+        assert!(processor.process_advanced_formula(strict).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_bucket_labels_survive_tied_quantiles() {
+        let processor = AdvancedFormulaProcessor::new();
+        let request = build_request(serde_json::json!({
+            "formula_type": "BUCKET",
+            "data": [{"score": 1}, {"score": 1}, {"score": 1}, {"score": 1}, {"score": 2}, {"score": 9}],
+            "parameters": {
+                "input_columns": ["score"],
+                "optional_params": [],
+                "bucket_config": {"strategy": "quantile", "bins": 4, "labels": ["Q1", "Q2", "Q3", "Q4"]}
+            },
+            "output_config": {"output_column": "quartile", "include_metadata": true}
+        }));
+        
+        let result = processor.process_advanced_formula(request).await.unwrap();
+        
+        // The first three quartile edges are all 1, so Q1 and Q2 have zero width and are merged away
+        assert_eq!(result.metadata["labels"], serde_json::json!(["Q3", "Q4"]));
+        assert_eq!(result.metadata["requested_bins"], 4);
+        assert_eq!(result.metadata["merged_bins"], 2);
+        let quartiles: Vec<Value> = result.data.iter().map(|row| row["quartile"].clone()).collect();
+        assert_eq!(quartiles, ["Q3", "Q3", "Q3", "Q3", "Q4", "Q4"].map(Value::from).to_vec());
+        
+        let constant = build_request(serde_json::json!({
+            "formula_type": "BUCKET",
+            "data": [{"score": 5}, {"score": 5}],
+            "parameters": {
+                "input_columns": ["score"],
+                "optional_params": [],
+                "bucket_config": {"bins": 3, "labels": ["Low", "Mid", "High"]}
+            },
+            "output_config": {"output_column": "band", "include_metadata": true}
+        }));
+        let result = processor.process_advanced_formula(constant).await.unwrap();
+        assert_eq!(result.data[0]["band"], "Low");
+        assert_eq!(result.metadata["merged_bins"], 2);
+    }
+    
+    #[tokio::test]
+    async fn test_filter_reports_dropped_rows() {
+        let processor = AdvancedFormulaProcessor::new();
+        let request = build_request(serde_json::json!({
+            "formula_type": "FILTER",
+            "data": [
+                {"region": "North", "amount": 120},
+                {"region": "South", "amount": 80},
+                {"region": "North", "amount": 40}
+            ],
+            "parameters": {
+                "input_columns": [],
+                "optional_params": [],
+                "filter_condition": {"and": [
+                    {"predicate": {"column": "region", "op": "eq", "value": "north", "case_sensitive": false}},
+                    {"predicate": {"column": "amount", "op": "gte", "value": 100}}
+                ]}
+            },
+            "output_config": {"output_column": "unused", "include_metadata": true}
+        }));
+        
+        assert!(processor.validate_formula_request(&request).is_ok());
+        let result = processor.process_advanced_formula(request).await.unwrap();
+        
+        assert_eq!(result.data.len(), 1);
+        assert_eq!(result.data[0]["amount"], 120);
+        assert_eq!(result.metadata["dropped_rows"], 2);
+        // Dropping rows is what a filter is for, so it is not a warning
+        assert!(result.warnings.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_sort_multiple_keys() {
+        let processor = AdvancedFormulaProcessor::new();
+        let request = build_request(serde_json::json!({
+            "formula_type": "SORT",
+            "data": [
+                {"sku": "item10", "region": "North"},
+                {"sku": "item2", "region": "North"},
+                {"sku": "item1", "region": null},
+                {"sku": "item3", "region": "East"}
+            ],
+            "parameters": {
+                "input_columns": [],
+                "optional_params": [],
+                "sort_keys": [
+                    {"column": "region", "direction": "desc"},
+                    {"column": "sku", "natural": true}
+                ]
+            },
+            "output_config": {"output_column": "unused", "include_metadata": false}
+        }));
+        
+        assert!(processor.validate_formula_request(&request).is_ok());
+        let result = processor.process_advanced_formula(request).await.unwrap();
+        
+        let skus: Vec<&str> = result.data.iter().map(|row| row["sku"].as_str().unwrap()).collect();
+        assert_eq!(skus, vec!["item2", "item10", "item3", "item1"]);
+        assert!(result.metadata.is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+use anyhow::{Result, anyhow};
+use ndarray::{Array1, Array2, Axis};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::data_processor::finite_or_null;
+use crate::linalg;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrelationMethod {
+    #[default]
+    Pearson,
+    Spearman,
+    Kendall,
+}
+
+// Numeric columns as an (rows x columns) matrix; rows with a null or NaN in any selected column are dropped
+pub struct NumericMatrix {
+    pub columns: Vec<String>,
+    pub data: Array2<f64>,
+    pub dropped_rows: usize,
+}
+
+pub fn numeric_matrix(df: &DataFrame, columns: Option<&[String]>) -> Result<NumericMatrix> {
+    let names: Vec<String> = match columns {
+        Some(columns) => columns.to_vec(),
+        None => df.get_columns().iter()
+            .filter(|c| c.dtype().is_primitive_numeric())
+            .map(|c| c.name().to_string())
+            .collect(),
+    };
+    if names.is_empty() {
+        return Err(anyhow!("No numeric columns to analyse"));
+    }
+
+    let mut values: Vec<Vec<Option<f64>>> = Vec::with_capacity(names.len());
+    for name in &names {
+        let column = df.column(name).map_err(|_| anyhow!("Column '{}' not found", name))?;
+        if !column.dtype().is_primitive_numeric() {
+            return Err(anyhow!("Column '{}' has type {} and is not numeric", name, column.dtype()));
+        }
+        values.push(column.cast(&DataType::Float64)?.f64()?.into_iter().collect());
+    }
+
+    let complete: Vec<usize> = (0..df.height())
+        .filter(|&row| values.iter().all(|col| col[row].is_some_and(|v| !v.is_nan())))
+        .collect();
+
+    let mut data = Array2::<f64>::zeros((complete.len(), names.len()));
+    for (i, &row) in complete.iter().enumerate() {
+        for (j, col) in values.iter().enumerate() {
+            data[[i, j]] = col[row].unwrap_or(f64::NAN);
+        }
+    }
+
+    Ok(NumericMatrix {
+        columns: names,
+        data,
+        dropped_rows: df.height() - complete.len(),
+    })
+}
+
+// Sample covariance (n - 1 denominator)
+pub fn covariance_matrix(data: &Array2<f64>) -> Result<Array2<f64>> {
+    let n = data.nrows();
+    if n < 2 {
+        return Err(anyhow!("Covariance needs at least 2 complete rows, got {}", n));
+    }
+    let means = data.mean_axis(Axis(0)).ok_or_else(|| anyhow!("Cannot average empty data"))?;
+    let centered = data - &means;
+    Ok(centered.t().dot(&centered) / (n - 1) as f64)
+}
+
+// Constant columns have no defined correlation and come back as NaN
+pub fn correlation_matrix(data: &Array2<f64>, method: CorrelationMethod) -> Result<Array2<f64>> {
+    match method {
+        CorrelationMethod::Pearson => pearson(data),
+        CorrelationMethod::Spearman => {
+            let mut ranked = data.clone();
+            for mut column in ranked.columns_mut() {
+                let ranks = average_ranks(&column.to_vec());
+                column.assign(&Array1::from(ranks));
+            }
+            pearson(&ranked)
+        }
+        CorrelationMethod::Kendall => {
+            let k = data.ncols();
+            if data.nrows() < 2 {
+                return Err(anyhow!("Correlation needs at least 2 complete rows, got {}", data.nrows()));
+            }
+            let mut matrix = Array2::<f64>::eye(k);
+            for i in 0..k {
+                for j in (i + 1)..k {
+                    let tau = kendall_tau_b(&data.column(i).to_vec(), &data.column(j).to_vec());
+                    matrix[[i, j]] = tau;
+                    matrix[[j, i]] = tau;
+                }
+                if data.column(i).iter().all(|&v| v == data[[0, i]]) {
+                    matrix[[i, i]] = f64::NAN;
+                }
+            }
+            Ok(matrix)
+        }
+    }
+}
+
+fn pearson(data: &Array2<f64>) -> Result<Array2<f64>> {
+    let covariance = covariance_matrix(data)?;
+    let std: Vec<f64> = covariance.diag().iter().map(|v| v.sqrt()).collect();
+    let k = data.ncols();
+    let mut matrix = Array2::<f64>::zeros((k, k));
+    for i in 0..k {
+        for j in 0..k {
+            let denominator = std[i] * std[j];
+            matrix[[i, j]] = if denominator > 0.0 {
+                (covariance[[i, j]] / denominator).clamp(-1.0, 1.0)
+            } else {
+                f64::NAN
+            };
+        }
+    }
+    Ok(matrix)
+}
+
+// 1-based ranks with ties sharing their average rank
+pub fn average_ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &index in &order[start..end] {
+            ranks[index] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+// Tau-b, which corrects for ties in either variable; O(n^2) pairwise comparison
+fn kendall_tau_b(x: &[f64], y: &[f64]) -> f64 {
+    let (mut concordance, mut x_pairs, mut y_pairs) = (0.0, 0.0, 0.0);
+    for i in 0..x.len() {
+        for j in (i + 1)..x.len() {
+            let dx = sign(x[i] - x[j]);
+            let dy = sign(y[i] - y[j]);
+            concordance += dx * dy;
+            x_pairs += dx * dx;
+            y_pairs += dy * dy;
+        }
+    }
+    let denominator = (x_pairs * y_pairs).sqrt();
+    if denominator > 0.0 { concordance / denominator } else { f64::NAN }
+}
+
+fn sign(value: f64) -> f64 {
+    if value > 0.0 { 1.0 } else if value < 0.0 { -1.0 } else { 0.0 }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegressionCoefficient {
+    pub name: String,
+    pub estimate: f64,
+    pub std_error: f64,
+    pub t_statistic: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegressionResult {
+    pub target: String,
+    pub coefficients: Vec<RegressionCoefficient>,
+    pub r_squared: f64,
+    pub adjusted_r_squared: f64,
+    pub residual_std_error: f64,
+    pub observations: usize,
+    pub degrees_of_freedom: usize,
+    pub residuals: Option<Vec<f64>>,
+}
+
+// Ordinary least squares with an intercept; one feature gives simple linear regression
+pub fn linear_regression(target: &str, y: &Array1<f64>, features: &[String], x: &Array2<f64>, include_residuals: bool) -> Result<RegressionResult> {
+    let n = y.len();
+    let p = features.len() + 1;
+    if n <= p {
+        return Err(anyhow!("Regression with {} coefficients needs more than {} complete rows, got {}", p, p, n));
+    }
+
+    let mut design = Array2::<f64>::ones((n, p));
+    design.slice_mut(ndarray::s![.., 1..]).assign(x);
+
+    let xtx = design.t().dot(&design);
+    let xtx_inv = linalg::inverse(&xtx)
+        .map_err(|_| anyhow!("Features are perfectly collinear (or constant); remove redundant columns"))?;
+    let beta = xtx_inv.dot(&design.t().dot(y));
+
+    let fitted = design.dot(&beta);
+    let residuals = y - &fitted;
+    let rss = residuals.dot(&residuals);
+    let mean_y = y.mean().unwrap_or(0.0);
+    let tss = y.iter().map(|v| (v - mean_y).powi(2)).sum::<f64>();
+
+    let degrees_of_freedom = n - p;
+    let sigma_squared = rss / degrees_of_freedom as f64;
+    let r_squared = if tss > 0.0 { 1.0 - rss / tss } else { f64::NAN };
+    let adjusted_r_squared = 1.0 - (1.0 - r_squared) * (n - 1) as f64 / degrees_of_freedom as f64;
+
+    let names = std::iter::once("intercept".to_string()).chain(features.iter().cloned());
+    let coefficients = names.enumerate()
+        .map(|(i, name)| {
+            let std_error = (sigma_squared * xtx_inv[[i, i]]).sqrt();
+            RegressionCoefficient {
+                name,
+                estimate: beta[i],
+                std_error,
+                t_statistic: if std_error > 0.0 { beta[i] / std_error } else { f64::NAN },
+            }
+        })
+        .collect();
+
+    Ok(RegressionResult {
+        target: target.to_string(),
+        coefficients,
+        r_squared,
+        adjusted_r_squared,
+        residual_std_error: sigma_squared.sqrt(),
+        observations: n,
+        degrees_of_freedom,
+        residuals: include_residuals.then(|| residuals.to_vec()),
+    })
+}
+
+pub fn matrix_to_json(columns: &[String], matrix: &Array2<f64>) -> Value {
+    let rows: Vec<Vec<Value>> = matrix.rows().into_iter()
+        .map(|row| row.iter().map(|&v| finite_or_null(v)).collect())
+        .collect();
+    serde_json::json!({ "columns": columns, "matrix": rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_correlation_methods() {
+        let data = array![[1.0, 2.0, 10.0], [2.0, 4.0, 8.0], [3.0, 6.5, 9.0], [4.0, 100.0, 1.0]];
+
+        let pearson = correlation_matrix(&data, CorrelationMethod::Pearson).unwrap();
+        assert!((pearson[[0, 0]] - 1.0).abs() < 1e-12);
+        assert!(pearson[[0, 1]] < 0.9);
+
+        let spearman = correlation_matrix(&data, CorrelationMethod::Spearman).unwrap();
+        assert!((spearman[[0, 1]] - 1.0).abs() < 1e-12);
+
+        let kendall = correlation_matrix(&data, CorrelationMethod::Kendall).unwrap();
+        assert!((kendall[[0, 1]] - 1.0).abs() < 1e-12);
+        assert!((kendall[[0, 2]] - (-2.0 / 3.0)).abs() < 1e-12);
+        assert_eq!(average_ranks(&[3.0, 1.0, 3.0]), vec![2.5, 1.0, 2.5]);
+    }
+
+    #[test]
+    fn test_multiple_regression_recovers_coefficients() {
+        let x = array![[1.0, 0.0], [2.0, 1.0], [3.0, 0.0], [4.0, 1.0], [5.0, 0.0], [6.0, 1.0]];
+        let y: Array1<f64> = x.rows().into_iter().map(|r| 1.0 + 2.0 * r[0] - 3.0 * r[1]).collect();
+        let features = vec!["price".to_string(), "promo".to_string()];
+
+        let result = linear_regression("sales", &y, &features, &x, true).unwrap();
+        let estimates: Vec<f64> = result.coefficients.iter().map(|c| c.estimate).collect();
+        assert!((estimates[0] - 1.0).abs() < 1e-9);
+        assert!((estimates[1] - 2.0).abs() < 1e-9);
+        assert!((estimates[2] + 3.0).abs() < 1e-9);
+        assert!((result.r_squared - 1.0).abs() < 1e-12);
+        assert_eq!(result.degrees_of_freedom, 3);
+
+        let collinear = array![[1.0, 2.0], [2.0, 4.0], [3.0, 6.0], [4.0, 8.0]];
+        let y = array![1.0, 2.0, 3.0, 4.0];
+        assert!(linear_regression("y", &y, &features, &collinear, false).is_err());
+    }
+}
@@ -0,0 +1,448 @@
+use anyhow::{Result, anyhow};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::compute::map_reduce_chunks;
+use crate::quantiles::{quantile_sorted, QuantileMethod};
+
+// Where a value falls relative to a set of bin edges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinPosition {
+    Below,
+    Bin(usize),
+    Above,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BinningStrategy {
+    #[default]
+    EqualWidth,
+    Quantile,
+    Custom,
+}
+
+// Evenly spaced edges between the minimum and maximum finite value
+pub fn equal_width_edges(values: &[f64], bins: usize) -> Result<Vec<f64>> {
+    if bins == 0 {
+        return Err(anyhow!("Number of bins must be at least 1"));
+    }
+
+    let (min, max) = finite_range(values)
+        .ok_or_else(|| anyhow!("Cannot compute bin edges without finite values"))?;
+
+    // All-equal data collapses into a single bin instead of a zero width
+    if min == max {
+        return Ok(vec![min, max]);
+    }
+
+    let width = (max - min) / bins as f64;
+    let mut edges: Vec<f64> = (0..bins).map(|i| min + i as f64 * width).collect();
+    edges.push(max);
+    Ok(edges)
+}
+
+// The bins + 1 quantile cut points at evenly spaced quantiles, repeats included; merge_edges drops the repeats
+pub fn quantile_cut_points(values: &[f64], bins: usize) -> Result<Vec<f64>> {
+    if bins == 0 {
+        return Err(anyhow!("Number of bins must be at least 1"));
+    }
+
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if sorted.is_empty() {
+        return Err(anyhow!("Cannot compute bin edges without finite values"));
+    }
+    sorted.sort_by(f64::total_cmp);
+
+    Ok((0..=bins).map(|i| quantile_sorted(&sorted, i as f64 / bins as f64, QuantileMethod::Linear)).collect())
+}
+
+// Drops zero-width bins from non-decreasing edges. Also returns, for each remaining bin, the index of
+// the requested bin it came from; when every bin has zero width the first one is kept
+pub fn merge_edges(points: &[f64]) -> (Vec<f64>, Vec<usize>) {
+    let mut edges: Vec<f64> = points.first().copied().into_iter().collect();
+    let mut kept = Vec::with_capacity(points.len().saturating_sub(1));
+    for (i, &point) in points.iter().enumerate().skip(1) {
+        if edges.last().is_some_and(|&last| point > last) {
+            edges.push(point);
+            kept.push(i - 1);
+        }
+    }
+
+    if edges.len() == 1 {
+        edges.push(edges[0]);
+        kept.push(0);
+    }
+    (edges, kept)
+}
+
+pub fn validate_edges(edges: &[f64]) -> Result<()> {
+    if edges.len() < 2 {
+        return Err(anyhow!("At least two bin edges are required"));
+    }
+    if edges.iter().any(|e| !e.is_finite()) {
+        return Err(anyhow!("Bin edges must be finite numbers"));
+    }
+    if edges.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(anyhow!("Bin edges must be strictly increasing"));
+    }
+    Ok(())
+}
+
+// Bins are half-open [a, b) except the last one, which also includes its right edge
+pub fn bin_position(edges: &[f64], value: f64) -> BinPosition {
+    let first = edges[0];
+    let last = edges[edges.len() - 1];
+
+    if value < first {
+        return BinPosition::Below;
+    }
+    if value > last {
+        return BinPosition::Above;
+    }
+
+    let bin_count = (edges.len() - 1).max(1);
+    let index = edges.partition_point(|&edge| edge <= value).saturating_sub(1);
+    BinPosition::Bin(index.min(bin_count - 1))
+}
+
+pub fn default_labels(edges: &[f64]) -> Vec<String> {
+    let bin_count = (edges.len() - 1).max(1);
+    (0..bin_count)
+        .map(|i| {
+            let closing = if i == bin_count - 1 { ']' } else { ')' };
+            format!("[{}, {}{}", format_edge(edges[i]), format_edge(edges[(i + 1).min(edges.len() - 1)]), closing)
+        })
+        .collect()
+}
+
+pub fn format_edge(edge: f64) -> String {
+    let rounded = format!("{:.4}", edge);
+    let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" { "0".to_string() } else { trimmed.to_string() }
+}
+
+// Automatic bin-count rules for histograms; auto picks the finer of Sturges and Freedman-Diaconis
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BinRule {
+    Sturges,
+    Scott,
+    #[serde(alias = "fd")]
+    FreedmanDiaconis,
+    Auto,
+}
+
+impl BinRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinRule::Sturges => "sturges",
+            BinRule::Scott => "scott",
+            BinRule::FreedmanDiaconis => "freedman_diaconis",
+            BinRule::Auto => "auto",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum HistogramBins {
+    Count(usize),
+    Rule(BinRule),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct HistogramOptions {
+    pub bins: HistogramBins,
+    pub edges: Option<Vec<f64>>,
+    pub log_scale: bool,
+    pub density: bool,
+    pub cumulative: bool,
+}
+
+impl Default for HistogramOptions {
+    fn default() -> Self {
+        Self {
+            bins: HistogramBins::Count(10),
+            edges: None,
+            log_scale: false,
+            density: false,
+            cumulative: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Histogram {
+    pub histogram: Vec<usize>,
+    pub bin_edges: Vec<f64>,
+    // Shared width of equal-width bins; null for log-scale or custom edges
+    pub bin_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub density: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cumulative: Option<Vec<usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cumulative_density: Option<Vec<f64>>,
+    pub bin_rule: Option<String>,
+    pub count: usize,
+    pub non_finite_count: usize,
+    pub outside_count: usize,
+    pub warnings: Vec<String>,
+}
+
+// Upper bound for rule-selected bin counts, which heavy outliers can otherwise blow up
+const MAX_AUTO_BINS: usize = 10_000;
+
+pub fn rule_bin_count(sorted: &[f64], rule: BinRule) -> usize {
+    let n = sorted.len() as f64;
+    let range = sorted[sorted.len() - 1] - sorted[0];
+    let sturges = (n.log2().ceil() as usize + 1).max(1);
+    if range <= 0.0 {
+        return 1;
+    }
+
+    let from_width = |width: f64| {
+        if width > 0.0 && width.is_finite() {
+            Some(((range / width).ceil() as usize).clamp(1, MAX_AUTO_BINS))
+        } else {
+            None
+        }
+    };
+    let scott = || {
+        let mean = sorted.iter().sum::<f64>() / n;
+        let std = (sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+        from_width(3.49 * std * n.powf(-1.0 / 3.0))
+    };
+    let freedman_diaconis = || {
+        let iqr = quantile_sorted(sorted, 0.75, QuantileMethod::Linear) - quantile_sorted(sorted, 0.25, QuantileMethod::Linear);
+        from_width(2.0 * iqr * n.powf(-1.0 / 3.0))
+    };
+
+    // Rules whose width collapses to zero (e.g. IQR of mostly repeated values) fall back to Sturges
+    match rule {
+        BinRule::Sturges => sturges,
+        BinRule::Scott => scott().unwrap_or(sturges),
+        BinRule::FreedmanDiaconis => freedman_diaconis().unwrap_or(sturges),
+        BinRule::Auto => freedman_diaconis().map_or(sturges, |fd| fd.max(sturges)),
+    }
+}
+
+// Geometrically spaced edges; both bounds must be positive
+pub fn log_edges(min: f64, max: f64, bins: usize) -> Result<Vec<f64>> {
+    if bins == 0 {
+        return Err(anyhow!("Number of bins must be at least 1"));
+    }
+    if min <= 0.0 || max <= 0.0 {
+        return Err(anyhow!("Log-scale bins need positive bounds, got [{}, {}]", min, max));
+    }
+
+    let (log_min, log_max) = (min.log10(), max.log10());
+    let step = (log_max - log_min) / bins as f64;
+    let mut edges: Vec<f64> = (0..bins).map(|i| 10f64.powf(log_min + i as f64 * step)).collect();
+    edges.push(max);
+    Ok(edges)
+}
+
+// Never fails on degenerate data: empty input yields no bins, constant input one bin around the value,
+// and non-finite values (or non-positive ones on a log scale) are counted and left out
+pub fn compute_histogram(values: &[f64], options: &HistogramOptions) -> Result<Histogram> {
+    let mut warnings = Vec::new();
+    let finite: Vec<f64> = values.par_iter().copied().filter(|v| v.is_finite()).collect();
+    let non_finite_count = values.len() - finite.len();
+
+    let mut sorted: Vec<f64> = if options.log_scale && options.edges.is_none() {
+        finite.iter().copied().filter(|v| *v > 0.0).collect()
+    } else {
+        finite.clone()
+    };
+    if sorted.len() < finite.len() {
+        warnings.push(format!("{} non-positive values left out of the log-scale bins", finite.len() - sorted.len()));
+    }
+    sorted.par_sort_unstable_by(f64::total_cmp);
+
+    let mut bin_rule = None;
+    let (edges, bin_width) = match &options.edges {
+        Some(edges) => {
+            validate_edges(edges)?;
+            (edges.clone(), None)
+        }
+        None if sorted.is_empty() => {
+            warnings.push("No values to bin".to_string());
+            (Vec::new(), None)
+        }
+        None => {
+            let bins = match options.bins {
+                HistogramBins::Count(0) => {
+                    warnings.push("bins = 0 is not valid; using the auto rule".to_string());
+                    bin_rule = Some(BinRule::Auto);
+                    rule_bin_count(&sorted, BinRule::Auto)
+                }
+                HistogramBins::Count(bins) => bins,
+                HistogramBins::Rule(rule) => {
+                    bin_rule = Some(rule);
+                    rule_bin_count(&sorted, rule)
+                }
+            };
+
+            let (mut min, mut max) = (sorted[0], sorted[sorted.len() - 1]);
+            if min == max {
+                // Same convention as numpy: a unit-wide range centred on the value (a decade on log scale)
+                if options.log_scale {
+                    (min, max) = (min / 10f64.sqrt(), max * 10f64.sqrt());
+                } else {
+                    (min, max) = (min - 0.5, max + 0.5);
+                }
+            }
+
+            if options.log_scale {
+                (log_edges(min, max, bins)?, None)
+            } else {
+                let width = (max - min) / bins as f64;
+                let mut edges: Vec<f64> = (0..bins).map(|i| min + i as f64 * width).collect();
+                edges.push(max);
+                (edges, Some(width))
+            }
+        }
+    };
+
+    let bin_count = edges.len().saturating_sub(1);
+    let (counts, outside_count) = if bin_count == 0 {
+        (Vec::new(), 0)
+    } else {
+        map_reduce_chunks(
+            &finite,
+            |chunk| {
+                let (mut counts, mut outside) = (vec![0usize; bin_count], 0);
+                for &value in chunk {
+                    match bin_position(&edges, value) {
+                        BinPosition::Bin(index) => counts[index] += 1,
+                        _ => outside += 1,
+                    }
+                }
+                (counts, outside)
+            },
+            |(mut counts, outside), (other, other_outside)| {
+                counts.iter_mut().zip(other).for_each(|(c, o)| *c += o);
+                (counts, outside + other_outside)
+            },
+        )
+    };
+    let count: usize = counts.iter().sum();
+
+    let density = options.density.then(|| {
+        counts.iter()
+            .zip(edges.windows(2))
+            .map(|(&c, w)| if count == 0 { 0.0 } else { c as f64 / (count as f64 * (w[1] - w[0])) })
+            .collect()
+    });
+
+    let cumulative: Option<Vec<usize>> = options.cumulative.then(|| {
+        counts.iter().scan(0, |total, &c| { *total += c; Some(*total) }).collect()
+    });
+    let cumulative_density = match (&cumulative, options.density) {
+        (Some(cumulative), true) => Some(cumulative.iter()
+            .map(|&c| if count == 0 { 0.0 } else { c as f64 / count as f64 })
+            .collect()),
+        _ => None,
+    };
+
+    Ok(Histogram {
+        histogram: counts,
+        bin_edges: edges,
+        bin_width,
+        density,
+        cumulative,
+        cumulative_density,
+        bin_rule: bin_rule.map(|r| r.as_str().to_string()),
+        count,
+        non_finite_count,
+        outside_count,
+        warnings,
+    })
+}
+
+fn finite_range(values: &[f64]) -> Option<(f64, f64)> {
+    values.iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .fold(None, |range, v| match range {
+            None => Some((v, v)),
+            Some((min, max)) => Some((min.min(v), max.max(v))),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_width_edges_handle_constant_data() {
+        assert_eq!(equal_width_edges(&[2.0, 2.0, 2.0], 4).unwrap(), vec![2.0, 2.0]);
+        assert_eq!(equal_width_edges(&[0.0, 10.0], 2).unwrap(), vec![0.0, 5.0, 10.0]);
+        assert!(equal_width_edges(&[1.0], 0).is_err());
+    }
+
+    #[test]
+    fn test_quantile_edges_merge_duplicates() {
+        let points = quantile_cut_points(&[1.0, 1.0, 1.0, 1.0, 5.0], 4).unwrap();
+        assert_eq!(merge_edges(&points).0, vec![1.0, 5.0]);
+
+        // The surviving bin is the last requested one, [q3, q4]
+        assert_eq!(merge_edges(&[1.0, 1.0, 1.0, 1.0, 5.0]), (vec![1.0, 5.0], vec![3]));
+        assert_eq!(merge_edges(&[0.0, 1.0, 1.0, 2.0]), (vec![0.0, 1.0, 2.0], vec![0, 2]));
+        assert_eq!(merge_edges(&[2.0, 2.0]), (vec![2.0, 2.0], vec![0]));
+    }
+
+    #[test]
+    fn test_bin_position() {
+        let edges = [0.0, 10.0, 20.0];
+        assert_eq!(bin_position(&edges, -1.0), BinPosition::Below);
+        assert_eq!(bin_position(&edges, 0.0), BinPosition::Bin(0));
+        assert_eq!(bin_position(&edges, 10.0), BinPosition::Bin(1));
+        assert_eq!(bin_position(&edges, 20.0), BinPosition::Bin(1));
+        assert_eq!(bin_position(&edges, 20.5), BinPosition::Above);
+        assert_eq!(default_labels(&edges), vec!["[0, 10)", "[10, 20]"]);
+    }
+
+    #[test]
+    fn test_histogram_rules_and_degenerate_inputs() {
+        let values: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let options = HistogramOptions { bins: HistogramBins::Rule(BinRule::Sturges), ..Default::default() };
+        let histogram = compute_histogram(&values, &options).unwrap();
+        assert_eq!(histogram.histogram.len(), 8);
+        assert_eq!(histogram.count, 100);
+
+        let constant = compute_histogram(&[3.0, 3.0, f64::NAN], &HistogramOptions::default()).unwrap();
+        assert_eq!(constant.bin_edges.first(), Some(&2.5));
+        assert_eq!(constant.count, 2);
+        assert_eq!(constant.non_finite_count, 1);
+
+        let options = HistogramOptions { bins: HistogramBins::Count(0), ..Default::default() };
+        assert!(!compute_histogram(&values, &options).unwrap().warnings.is_empty());
+        assert!(compute_histogram(&[], &HistogramOptions::default()).unwrap().histogram.is_empty());
+    }
+
+    #[test]
+    fn test_histogram_edges_log_density_cumulative() {
+        let options = HistogramOptions {
+            edges: Some(vec![0.0, 1.0, 3.0]),
+            density: true,
+            cumulative: true,
+            ..Default::default()
+        };
+        let histogram = compute_histogram(&[0.5, 1.5, 2.5, 9.0], &options).unwrap();
+        assert_eq!(histogram.histogram, vec![1, 2]);
+        assert_eq!(histogram.outside_count, 1);
+        assert_eq!(histogram.density, Some(vec![1.0 / 3.0, 1.0 / 3.0]));
+        assert_eq!(histogram.cumulative, Some(vec![1, 3]));
+        assert_eq!(histogram.cumulative_density, Some(vec![1.0 / 3.0, 1.0]));
+
+        let options = HistogramOptions { bins: HistogramBins::Count(2), log_scale: true, ..Default::default() };
+        let histogram = compute_histogram(&[-1.0, 1.0, 10.0, 100.0], &options).unwrap();
+        assert!((histogram.bin_edges[1] - 10.0).abs() < 1e-9);
+        assert_eq!(histogram.histogram, vec![1, 2]);
+        assert_eq!(histogram.outside_count, 1);
+    }
+}
@@ -0,0 +1,570 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+// Failures kept per column when the config does not say otherwise
+const DEFAULT_FAILURE_SAMPLE: usize = 10;
+
+// Mantissas are held in an i128, which fits any 38-digit number
+const MAX_DIGITS: usize = 38;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CastTarget {
+    Int,
+    Float,
+    Decimal,
+    Bool,
+    Date,
+    Datetime,
+}
+
+impl CastTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CastTarget::Int => "int",
+            CastTarget::Float => "float",
+            CastTarget::Decimal => "decimal",
+            CastTarget::Bool => "bool",
+            CastTarget::Date => "date",
+            CastTarget::Datetime => "datetime",
+        }
+    }
+}
+
+// What happens to a value that cannot be converted: it becomes null and is reported, or the whole cast fails
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CastErrorPolicy {
+    Null,
+    Error,
+}
+
+// Conversion rules for CAST. The locale picks the decimal and thousands separators and whether
+// ambiguous dates such as 03/01/2024 are read day first; explicit separators override it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CastConfig {
+    pub target: CastTarget,
+    pub locale: String,
+    pub decimal_separator: Option<char>,
+    pub thousands_separator: Option<char>,
+    pub currency_symbols: Vec<String>,
+    // "12.5%" becomes 0.125 when set, 12.5 otherwise
+    pub percent_as_fraction: bool,
+    // chrono formats tried in order before the locale defaults, e.g. "%d %B %Y"
+    pub formats: Vec<String>,
+    pub true_values: Vec<String>,
+    pub false_values: Vec<String>,
+    // Extra text treated as missing; blank strings always are
+    pub null_values: Vec<String>,
+    // Digits after the point for decimal output, rounded half away from zero
+    pub scale: Option<u32>,
+    pub on_error: CastErrorPolicy,
+    pub failure_sample: usize,
+}
+
+impl Default for CastConfig {
+    fn default() -> Self {
+        Self {
+            target: CastTarget::Float,
+            locale: "en".to_string(),
+            decimal_separator: None,
+            thousands_separator: None,
+            currency_symbols: ["$", "€", "£", "¥", "₹", "₩", "CHF", "USD", "EUR", "GBP"].iter().map(|s| s.to_string()).collect(),
+            percent_as_fraction: true,
+            formats: Vec::new(),
+            true_values: ["true", "t", "yes", "y", "1", "on"].iter().map(|s| s.to_string()).collect(),
+            false_values: ["false", "f", "no", "n", "0", "off"].iter().map(|s| s.to_string()).collect(),
+            null_values: Vec::new(),
+            scale: None,
+            on_error: CastErrorPolicy::Null,
+            failure_sample: DEFAULT_FAILURE_SAMPLE,
+        }
+    }
+}
+
+impl CastConfig {
+    pub fn compile(&self) -> Result<CompiledCast> {
+        let (decimal, thousands, day_first) = locale_rules(&self.locale)?;
+        let decimal = self.decimal_separator.unwrap_or(decimal);
+        let thousands = match self.thousands_separator {
+            Some(separator) => vec![separator],
+            None => thousands.to_vec(),
+        };
+        if thousands.contains(&decimal) {
+            return Err(anyhow!("Decimal and thousands separators must differ, both are '{}'", decimal));
+        }
+        if decimal.is_ascii_digit() || thousands.iter().any(|c| c.is_ascii_digit()) {
+            return Err(anyhow!("Separators cannot be digits"));
+        }
+        if let Some(format) = self.formats.iter().find(|f| f.trim().is_empty()) {
+            return Err(anyhow!("Date format '{}' is empty", format));
+        }
+
+        // Longest first so "CHF" is not cut short by a shorter symbol it contains
+        let mut currency_symbols: Vec<String> = self.currency_symbols.iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        currency_symbols.sort_by_key(|s| std::cmp::Reverse(s.chars().count()));
+
+        let lowercase = |values: &[String]| values.iter().map(|v| v.trim().to_lowercase()).collect::<Vec<_>>();
+        let true_values = lowercase(&self.true_values);
+        let false_values = lowercase(&self.false_values);
+        if let Some(both) = true_values.iter().find(|v| false_values.contains(v)) {
+            return Err(anyhow!("'{}' is listed as both a true and a false value", both));
+        }
+
+        let mut date_formats = self.formats.clone();
+        date_formats.extend(default_date_formats(day_first).iter().map(|f| f.to_string()));
+
+        Ok(CompiledCast {
+            target: self.target,
+            decimal,
+            thousands,
+            currency_symbols,
+            percent_as_fraction: self.percent_as_fraction,
+            date_formats,
+            true_values,
+            false_values,
+            null_values: self.null_values.iter().map(|v| v.trim().to_string()).collect(),
+            scale: self.scale,
+        })
+    }
+}
+
+// (decimal separator, accepted thousands separators, day-first dates)
+fn locale_rules(locale: &str) -> Result<(char, &'static [char], bool)> {
+    const SPACES: &[char] = &[' ', '\u{a0}', '\u{202f}'];
+    let locale = locale.trim().to_lowercase().replace('_', "-");
+    let language = locale.split('-').next().unwrap_or_default();
+
+    Ok(match (locale.as_str(), language) {
+        ("de-ch" | "fr-ch" | "it-ch", _) => ('.', &['\'', '’'], true),
+        ("en" | "en-us" | "en-ca" | "en-ph", _) => ('.', &[','], false),
+        (_, "en" | "ga" | "he" | "ja" | "ko" | "zh" | "th" | "hi") => ('.', &[','], true),
+        (_, "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl") => (',', &['.'], true),
+        (_, "fr" | "ru" | "pl" | "sv" | "nb" | "no" | "fi" | "cs" | "sk" | "uk" | "hu" | "bg") => (',', SPACES, true),
+        _ => return Err(anyhow!("Unsupported locale '{}'; set decimal_separator and thousands_separator instead", locale)),
+    })
+}
+
+fn default_date_formats(day_first: bool) -> &'static [&'static str] {
+    const DAY_FIRST: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%d/%m/%Y", "%d.%m.%Y", "%d-%m-%Y", "%d %b %Y", "%d %B %Y", "%b %d, %Y", "%B %d, %Y"];
+    const MONTH_FIRST: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%m/%d/%Y", "%m-%d-%Y", "%d.%m.%Y", "%d %b %Y", "%d %B %Y", "%b %d, %Y", "%B %d, %Y"];
+    if day_first { DAY_FIRST } else { MONTH_FIRST }
+}
+
+// Time parts tried after each date format when reading datetimes
+const TIME_SUFFIXES: &[&str] = &[" %H:%M:%S%.f", "T%H:%M:%S%.f", " %H:%M", "T%H:%M", " %I:%M:%S %p", " %I:%M %p"];
+
+pub struct CompiledCast {
+    target: CastTarget,
+    decimal: char,
+    thousands: Vec<char>,
+    currency_symbols: Vec<String>,
+    percent_as_fraction: bool,
+    date_formats: Vec<String>,
+    true_values: Vec<String>,
+    false_values: Vec<String>,
+    null_values: Vec<String>,
+    scale: Option<u32>,
+}
+
+// Exact decimal number: mantissa × 10^-scale
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    fn rescale(self, scale: u32) -> std::result::Result<Self, String> {
+        if scale >= self.scale {
+            let factor = 10i128.checked_pow(scale - self.scale).ok_or("has too many digits")?;
+            let mantissa = self.mantissa.checked_mul(factor).ok_or("has too many digits")?;
+            return Ok(Decimal { mantissa, scale });
+        }
+        let factor = 10i128.pow(self.scale - scale);
+        let (quotient, remainder) = (self.mantissa / factor, self.mantissa % factor);
+        let rounded = if remainder.abs() * 2 >= factor { quotient + self.mantissa.signum() } else { quotient };
+        Ok(Decimal { mantissa: rounded, scale })
+    }
+
+    fn to_f64(self) -> f64 {
+        format!("{}e-{}", self.mantissa, self.scale).parse().unwrap_or(f64::NAN)
+    }
+
+    fn to_i64(self) -> std::result::Result<i64, String> {
+        let factor = 10i128.pow(self.scale);
+        if self.mantissa % factor != 0 {
+            return Err("has a fractional part".to_string());
+        }
+        i64::try_from(self.mantissa / factor).map_err(|_| "is out of range for int".to_string())
+    }
+
+    fn to_text(self) -> String {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        if self.scale == 0 {
+            return format!("{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = self.scale as usize + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        format!("{}{}.{}", sign, whole, fraction)
+    }
+}
+
+impl CompiledCast {
+    pub fn target(&self) -> CastTarget {
+        self.target
+    }
+
+    // Converts one cell. Missing values stay null; anything else either converts or yields the reason it did not
+    pub fn cast(&self, value: &Value) -> std::result::Result<Value, String> {
+        match value {
+            Value::Null => Ok(Value::Null),
+            Value::String(text) if self.is_missing(text) => Ok(Value::Null),
+            Value::String(text) => self.cast_text(text.trim()),
+            Value::Number(number) => self.cast_number(number),
+            Value::Bool(flag) => match self.target {
+                CastTarget::Bool => Ok(Value::Bool(*flag)),
+                CastTarget::Int => Ok(Value::from(i64::from(*flag))),
+                _ => Err(format!("cannot convert a boolean to {}", self.target.as_str())),
+            },
+            Value::Array(_) | Value::Object(_) => Err(format!("cannot convert a nested value to {}", self.target.as_str())),
+        }
+    }
+
+    pub fn is_missing(&self, text: &str) -> bool {
+        let text = text.trim();
+        text.is_empty() || self.null_values.iter().any(|v| v == text)
+    }
+
+    fn cast_number(&self, number: &serde_json::Number) -> std::result::Result<Value, String> {
+        match self.target {
+            CastTarget::Int => match number.as_i64() {
+                Some(n) => Ok(Value::from(n)),
+                None => Ok(Value::from(self.parse_decimal(&number.to_string(), '.')?.to_i64()?)),
+            },
+            CastTarget::Float => Ok(number.as_f64().map(Value::from).unwrap_or(Value::Null)),
+            CastTarget::Decimal => self.decimal_value(self.parse_decimal(&number.to_string(), '.')?),
+            CastTarget::Bool => match number.as_f64() {
+                Some(1.0) => Ok(Value::Bool(true)),
+                Some(0.0) => Ok(Value::Bool(false)),
+                _ => Err("is not 0 or 1".to_string()),
+            },
+            CastTarget::Date | CastTarget::Datetime => Err(format!("cannot convert a number to {}", self.target.as_str())),
+        }
+    }
+
+    fn cast_text(&self, text: &str) -> std::result::Result<Value, String> {
+        match self.target {
+            CastTarget::Int => Ok(Value::from(self.parse_decimal(text, self.decimal)?.to_i64()?)),
+            CastTarget::Float => {
+                let value = self.parse_decimal(text, self.decimal)?.to_f64();
+                serde_json::Number::from_f64(value).map(Value::Number).ok_or_else(|| "is out of range for float".to_string())
+            }
+            CastTarget::Decimal => self.decimal_value(self.parse_decimal(text, self.decimal)?),
+            CastTarget::Bool => {
+                let lowered = text.to_lowercase();
+                if self.true_values.contains(&lowered) {
+                    Ok(Value::Bool(true))
+                } else if self.false_values.contains(&lowered) {
+                    Ok(Value::Bool(false))
+                } else {
+                    Err("is not a recognised boolean".to_string())
+                }
+            }
+            CastTarget::Date => self.parse_datetime(text)
+                .map(|dt| Value::String(dt.date().format("%Y-%m-%d").to_string())),
+            CastTarget::Datetime => self.parse_datetime(text)
+                .map(|dt| Value::String(dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string())),
+        }
+    }
+
+    // Decimals are returned as text so no precision is lost on the way to the client
+    fn decimal_value(&self, decimal: Decimal) -> std::result::Result<Value, String> {
+        let decimal = match self.scale {
+            Some(scale) => decimal.rescale(scale)?,
+            None => decimal,
+        };
+        Ok(Value::String(decimal.to_text()))
+    }
+
+    // Reads "$1,200.50", "(1.200,50 €)", "12.5%", "-3e2" and the like into an exact decimal. JSON
+    // numbers are read with a '.' separator whatever the locale
+    fn parse_decimal(&self, text: &str, decimal_separator: char) -> std::result::Result<Decimal, String> {
+        let mut rest = text.trim();
+        let mut negative = false;
+
+        // Accounting style negatives: (1,200)
+        if let Some(inner) = rest.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+            negative = true;
+            rest = inner.trim();
+        }
+
+        // Sign and currency may come in either order: -$5, $-5, 5 €, €5
+        let mut percent = false;
+        let mut signed = false;
+        let mut currency = false;
+        loop {
+            let before = rest;
+            if !signed {
+                if let Some(r) = rest.strip_prefix('-').or_else(|| rest.strip_prefix('\u{2212}')) {
+                    (negative, signed, rest) = (!negative, true, r.trim_start());
+                } else if let Some(r) = rest.strip_prefix('+') {
+                    (signed, rest) = (true, r.trim_start());
+                }
+            }
+            if !currency {
+                for symbol in &self.currency_symbols {
+                    if let Some(r) = rest.strip_prefix(symbol.as_str()) {
+                        (currency, rest) = (true, r.trim_start());
+                        break;
+                    }
+                    if let Some(r) = rest.strip_suffix(symbol.as_str()) {
+                        (currency, rest) = (true, r.trim_end());
+                        break;
+                    }
+                }
+            }
+            if !percent {
+                if let Some(r) = rest.strip_suffix('%') {
+                    (percent, rest) = (true, r.trim_end());
+                }
+            }
+            if rest == before {
+                break;
+            }
+        }
+
+        let (number, exponent) = match rest.find(['e', 'E']) {
+            Some(at) => {
+                let exponent: i32 = rest[at + 1..].parse().map_err(|_| "is not a number".to_string())?;
+                (&rest[..at], exponent)
+            }
+            None => (rest, 0),
+        };
+
+        let (whole, fraction) = match number.split_once(decimal_separator) {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (number, ""),
+        };
+        if !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err("is not a number".to_string());
+        }
+        let whole = self.strip_grouping(whole)?;
+        if whole.is_empty() && fraction.is_empty() {
+            return Err("is not a number".to_string());
+        }
+
+        let digits = format!("{}{}", whole, fraction);
+        let significant = digits.trim_start_matches('0');
+        if significant.len() > MAX_DIGITS {
+            return Err("has too many digits".to_string());
+        }
+        let magnitude: i128 = if significant.is_empty() { 0 } else { significant.parse().map_err(|_| "is not a number".to_string())? };
+
+        let mut decimal = Decimal { mantissa: if negative { -magnitude } else { magnitude }, scale: fraction.len() as u32 };
+        let exponent = exponent - if percent && self.percent_as_fraction { 2 } else { 0 };
+        if exponent >= 0 {
+            let shift = exponent as u32;
+            if decimal.scale >= shift {
+                decimal.scale -= shift;
+            } else {
+                decimal = Decimal { scale: 0, ..decimal }.rescale(shift - decimal.scale)?;
+                decimal.scale = 0;
+            }
+        } else {
+            decimal.scale += exponent.unsigned_abs();
+            if decimal.scale > MAX_DIGITS as u32 {
+                return Err("has too many digits".to_string());
+            }
+        }
+        Ok(decimal)
+    }
+
+    // Thousands separators are only accepted between complete groups of three, so "12.5" is not
+    // silently read as 125 under a locale that groups with dots
+    fn strip_grouping<'a>(&self, whole: &'a str) -> std::result::Result<std::borrow::Cow<'a, str>, String> {
+        if whole.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(whole.into());
+        }
+        let groups: Vec<&str> = whole.split(|c: char| self.thousands.contains(&c)).collect();
+        let valid = groups.len() > 1
+            && groups.iter().all(|g| g.chars().all(|c| c.is_ascii_digit()))
+            && (1..=3).contains(&groups[0].len())
+            && groups[1..].iter().all(|g| g.len() == 3);
+        if valid {
+            Ok(groups.concat().into())
+        } else {
+            Err("is not a number".to_string())
+        }
+    }
+
+    fn parse_datetime(&self, text: &str) -> std::result::Result<NaiveDateTime, String> {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+            return Ok(dt.naive_utc());
+        }
+        for format in &self.date_formats {
+            for suffix in TIME_SUFFIXES {
+                if let Ok(dt) = NaiveDateTime::parse_from_str(text, &format!("{}{}", format, suffix)) {
+                    return Ok(dt);
+                }
+            }
+            // Custom formats may carry their own time fields
+            if let Ok(dt) = NaiveDateTime::parse_from_str(text, format) {
+                return Ok(dt);
+            }
+            if let Ok(date) = NaiveDate::parse_from_str(text, format) {
+                return Ok(date.and_hms_opt(0, 0, 0).expect("midnight is a valid time"));
+            }
+        }
+        Err(format!("does not match any {} format", self.target.as_str()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CastFailure {
+    pub row: usize,
+    pub value: Value,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CastReport {
+    pub column: String,
+    pub output_column: String,
+    pub target: CastTarget,
+    pub converted: usize,
+    pub null_inputs: usize,
+    pub failed: usize,
+    pub failures: Vec<CastFailure>,
+}
+
+// Casts `column` of every row into `output_column`, which may be the same column
+pub fn cast_rows(rows: &mut [HashMap<String, Value>], column: &str, output_column: &str, config: &CastConfig) -> Result<CastReport> {
+    let cast = config.compile()?;
+    let mut report = CastReport {
+        column: column.to_string(),
+        output_column: output_column.to_string(),
+        target: cast.target(),
+        converted: 0,
+        null_inputs: 0,
+        failed: 0,
+        failures: Vec::new(),
+    };
+
+    for (index, row) in rows.iter_mut().enumerate() {
+        let input = row.get(column).cloned().unwrap_or(Value::Null);
+        let output = match cast.cast(&input) {
+            Ok(Value::Null) => {
+                report.null_inputs += 1;
+                Value::Null
+            }
+            Ok(value) => {
+                report.converted += 1;
+                value
+            }
+            Err(reason) if config.on_error == CastErrorPolicy::Error => {
+                return Err(anyhow!("Cannot cast '{}' to {} in row {}: {} {}", column, cast.target().as_str(), index, input, reason));
+            }
+            Err(reason) => {
+                report.failed += 1;
+                if report.failures.len() < config.failure_sample {
+                    report.failures.push(CastFailure { row: index, value: input, reason });
+                }
+                Value::Null
+            }
+        };
+        row.insert(output_column.to_string(), output);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(value: Value) -> CastConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn cast_all(config: &CastConfig, values: &[Value]) -> Vec<std::result::Result<Value, String>> {
+        let cast = config.compile().unwrap();
+        values.iter().map(|v| cast.cast(v)).collect()
+    }
+
+    #[test]
+    fn test_numbers_with_locale_currency_and_percent() {
+        let en = config(serde_json::json!({"target": "float"}));
+        let results = cast_all(&en, &[
+            Value::from("$1,200.50"), Value::from("12.5%"), Value::from("(1,000)"), Value::from("-$5"),
+            Value::from("1.5e3"), Value::from("12,34"), Value::from("abc"), Value::from("  "),
+        ]);
+        assert_eq!(results[0], Ok(Value::from(1200.5)));
+        assert_eq!(results[1], Ok(Value::from(0.125)));
+        assert_eq!(results[2], Ok(Value::from(-1000.0)));
+        assert_eq!(results[3], Ok(Value::from(-5.0)));
+        assert_eq!(results[4], Ok(Value::from(1500.0)));
+        assert!(results[5].is_err() && results[6].is_err());
+        assert_eq!(results[7], Ok(Value::Null));
+
+        let de = config(serde_json::json!({"target": "decimal", "locale": "de-DE", "scale": 2}));
+        let results = cast_all(&de, &[Value::from("1.234,565 €"), Value::from("12.5"), Value::from(7)]);
+        assert_eq!(results[0], Ok(Value::from("1234.57")));
+        assert!(results[1].is_err());
+        assert_eq!(results[2], Ok(Value::from("7.00")));
+
+        let fr = config(serde_json::json!({"target": "int", "locale": "fr", "percent_as_fraction": false}));
+        let results = cast_all(&fr, &[Value::from("1\u{202f}200"), Value::from("45 %"), Value::from("2,5")]);
+        assert_eq!(results[0], Ok(Value::from(1200)));
+        assert_eq!(results[1], Ok(Value::from(45)));
+        assert_eq!(results[2], Err("has a fractional part".to_string()));
+    }
+
+    #[test]
+    fn test_bools_dates_and_datetimes() {
+        let flags = config(serde_json::json!({"target": "bool", "null_values": ["N/A"]}));
+        let results = cast_all(&flags, &[Value::from("Yes"), Value::from("off"), Value::from(1), Value::from("N/A"), Value::from("maybe")]);
+        assert_eq!(results[..4], [Ok(Value::Bool(true)), Ok(Value::Bool(false)), Ok(Value::Bool(true)), Ok(Value::Null)]);
+        assert!(results[4].is_err());
+
+        let us = config(serde_json::json!({"target": "date"}));
+        let gb = config(serde_json::json!({"target": "date", "locale": "en-GB"}));
+        assert_eq!(cast_all(&us, &[Value::from("03/01/2024")])[0], Ok(Value::from("2024-03-01")));
+        assert_eq!(cast_all(&gb, &[Value::from("03/01/2024")])[0], Ok(Value::from("2024-01-03")));
+        assert_eq!(cast_all(&us, &[Value::from("2024/03/01")])[0], Ok(Value::from("2024-03-01")));
+
+        let custom = config(serde_json::json!({"target": "datetime", "formats": ["%d|%m|%Y %Hh%M"]}));
+        let results = cast_all(&custom, &[
+            Value::from("01|03|2024 14h30"), Value::from("2024-03-01T10:00:00+02:00"), Value::from("2024-03-01 08:15"), Value::from("yesterday"),
+        ]);
+        assert_eq!(results[0], Ok(Value::from("2024-03-01T14:30:00")));
+        assert_eq!(results[1], Ok(Value::from("2024-03-01T08:00:00")));
+        assert_eq!(results[2], Ok(Value::from("2024-03-01T08:15:00")));
+        assert!(results[3].is_err());
+    }
+
+    #[test]
+    fn test_error_policy_and_failure_sample() {
+        let mut rows: Vec<HashMap<String, Value>> = ["1", "x", "3", "y", "", "z"].iter()
+            .map(|v| HashMap::from([("qty".to_string(), Value::from(*v))]))
+            .collect();
+
+        let lenient = config(serde_json::json!({"target": "int", "failure_sample": 2}));
+        let report = cast_rows(&mut rows.clone(), "qty", "qty", &lenient).unwrap();
+        assert_eq!((report.converted, report.null_inputs, report.failed), (2, 1, 3));
+        assert_eq!(report.failures.iter().map(|f| f.row).collect::<Vec<_>>(), vec![1, 3]);
+
+        let strict = config(serde_json::json!({"target": "int", "on_error": "error"}));
+        let error = cast_rows(&mut rows, "qty", "qty_int", &strict).unwrap_err().to_string();
+        assert!(error.contains("row 1"), "{}", error);
+        assert!(config(serde_json::json!({"locale": "xx"})).compile().is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{Result, anyhow};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
+use tokio::sync::{oneshot, Semaphore};
+use tracing::error;
+
+// Inputs up to this length are reduced on the calling thread; splitting them costs more than it saves
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+// Dedicated pool for CPU-bound work so large requests never run on the HTTP workers.
+// The semaphore bounds how many jobs can be queued or running at once; callers wait for a permit
+pub struct ComputePool {
+    pool: ThreadPool,
+    permits: Arc<Semaphore>,
+    threads: usize,
+}
+
+impl ComputePool {
+    pub fn new(threads: Option<usize>, max_jobs: Option<usize>) -> Result<Self> {
+        let threads = threads
+            .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+            .unwrap_or(1)
+            .max(1);
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("compute-{}", i))
+            .panic_handler(|_| error!("A compute job panicked"))
+            .build()
+            .map_err(|e| anyhow!("Failed to start compute pool: {}", e))?;
+
+        Ok(Self {
+            pool,
+            permits: Arc::new(Semaphore::new(max_jobs.unwrap_or(threads * 4).max(1))),
+            threads,
+        })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    // Runs the job on the pool and resolves once it finishes; parallel iterators inside the job use this pool
+    pub async fn run<T, F>(&self, job: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let _permit = self.permits.acquire().await.map_err(|_| anyhow!("Compute pool is shut down"))?;
+        let (sender, receiver) = oneshot::channel();
+        self.pool.spawn(move || {
+            let _ = sender.send(job());
+        });
+        receiver.await.map_err(|_| anyhow!("Compute job failed before producing a result"))
+    }
+}
+
+// Runs file I/O and whole-file parsing or encoding on the runtime's blocking thread pool, the one
+// actix's web::block uses, so a large file cannot hold up an HTTP worker or a compute thread
+pub async fn run_blocking<T, F>(job: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(job).await.map_err(|e| anyhow!("Background job failed: {}", e))?
+}
+
+// Maps fixed-size chunks in parallel and folds the partial results in chunk order, so the result
+// does not depend on how the work was scheduled
+pub fn map_reduce_chunks<T, M, R>(data: &[f64], map: M, reduce: R) -> T
+where
+    T: Send,
+    M: Fn(&[f64]) -> T + Sync + Send,
+    R: Fn(T, T) -> T,
+{
+    if data.len() <= CHUNK_SIZE {
+        return map(data);
+    }
+    let partials: Vec<T> = data.par_chunks(CHUNK_SIZE).map(map).collect();
+    partials.into_iter().reduce(reduce).expect("non-empty input has at least one chunk")
+}
+
+// Count, sum, mean, sum of squared deviations and extremes in one pass. Chunks are merged with
+// Chan's update, which is also more accurate than a single running sum over long inputs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Moments {
+    pub count: usize,
+    pub sum: f64,
+    pub mean: f64,
+    pub m2: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Moments {
+    fn of_chunk(values: &[f64]) -> Self {
+        let mut moments = Moments { count: 0, sum: 0.0, mean: 0.0, m2: 0.0, min: f64::INFINITY, max: f64::NEG_INFINITY };
+        for &value in values {
+            moments.count += 1;
+            moments.sum += value;
+            let delta = value - moments.mean;
+            moments.mean += delta / moments.count as f64;
+            moments.m2 += delta * (value - moments.mean);
+            moments.min = moments.min.min(value);
+            moments.max = moments.max.max(value);
+        }
+        moments
+    }
+
+    fn merge(self, other: Self) -> Self {
+        if self.count == 0 {
+            return other;
+        }
+        if other.count == 0 {
+            return self;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        Moments {
+            count,
+            sum: self.sum + other.sum,
+            mean: self.mean + delta * other.count as f64 / count as f64,
+            m2: self.m2 + other.m2 + delta * delta * (self.count * other.count) as f64 / count as f64,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    // Population variance, matching the existing std operation
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.m2 / self.count as f64 }
+    }
+}
+
+pub fn moments(data: &[f64]) -> Moments {
+    map_reduce_chunks(data, Moments::of_chunk, Moments::merge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(n: usize) -> Vec<f64> {
+        // Deterministic spread of magnitudes without pulling in a random generator
+        (0..n).map(|i| ((i as f64 * 0.618_034).fract() - 0.5) * 1e3 + (i % 97) as f64).collect()
+    }
+
+    #[tokio::test]
+    async fn test_parallel_moments_match_sequential() {
+        let pool = ComputePool::new(Some(4), Some(2)).unwrap();
+        let data = values(CHUNK_SIZE * 5 + 123);
+        let expected = Moments::of_chunk(&data);
+
+        let shared = data.clone();
+        let parallel = pool.run(move || moments(&shared)).await.unwrap();
+        assert_eq!((parallel.count, parallel.min, parallel.max), (expected.count, expected.min, expected.max));
+        assert!((parallel.mean - expected.mean).abs() < 1e-9);
+        assert!((parallel.variance() - expected.variance()).abs() / expected.variance() < 1e-12);
+        assert!((parallel.sum - data.iter().sum::<f64>()).abs() < 1e-6);
+
+        // Scheduling does not change the answer
+        assert_eq!(pool.pool.install(|| moments(&data)), parallel);
+        assert_eq!(moments(&[]).count, 0);
+    }
+}
@@ -1,199 +1,699 @@
 use anyhow::{Result, anyhow};
-use ndarray::{Array1, Array2};
+use ndarray::Array2;
 use polars::prelude::*;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use tracing::{info, warn};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::analytics::{self, CorrelationMethod};
+use crate::binning::{self, HistogramOptions};
+use crate::compute::{self, ComputePool};
+use crate::forecasting::{self, ForecastOptions};
+use crate::hypothesis::{self, HypothesisOptions};
+use crate::linalg;
+use crate::outliers::{self, OutlierOptions};
+use crate::profiling;
+use crate::quantiles::{self, quantile_sorted, NanPolicy, QuantileMethod, TDigest};
+use crate::registry::{DataOperation, FnOperation, OperationSchema, ParamSpec, ParamType};
+use crate::sampling::{self, SamplingOptions, SplitOptions};
+use crate::scaling::{self, ScalingOptions};
+use crate::timeseries::{self, TimeSeriesOptions};
+
+// Registered numeric operations applied to named table columns, optionally once per group
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ColumnOperations {
+    pub columns: Vec<String>,
+    pub operations: Vec<String>,
+    pub group_by: Vec<String>,
+    pub skip_nulls: bool,
+    pub parameters: Option<Value>,
+}
+
+impl Default for ColumnOperations {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            operations: Vec::new(),
+            group_by: Vec::new(),
+            skip_nulls: true,
+            parameters: None,
+        }
+    }
+}
 
 pub struct DataProcessor {
-    operations: HashMap<String, Box<dyn Fn(&[f64], Option<&Value>) -> Result<Value> + Send + Sync>>,
+    operations: HashMap<String, Arc<dyn DataOperation>>,
+    // Parameter schemas of the whole-table operations, which share one parameter object per request
+    table_schemas: HashMap<String, OperationSchema>,
+    compute: ComputePool,
 }
 
 impl DataProcessor {
-    pub async fn new() -> Self {
+    pub async fn new() -> Result<Self> {
         let mut processor = DataProcessor {
             operations: HashMap::new(),
+            table_schemas: HashMap::new(),
+            compute: ComputePool::new(None, None)?,
         };
         
         // Register built-in operations
         processor.register_operations();
+        processor.register_table_operations();
         
-        info!("Data processor initialized with {} operations", processor.operations.len());
-        processor
+        info!("Data processor initialized with {} operations and {} compute threads", processor.operations.len(), processor.compute.threads());
+        Ok(processor)
+    }
+    
+    fn register<F>(&mut self, schema: OperationSchema, function: F)
+    where
+        F: Fn(&[f64], Option<&Value>) -> Result<Value> + Send + Sync + 'static,
+    {
+        self.operations.insert(schema.name.clone(), Arc::new(FnOperation::new(schema, function)));
     }
     
     fn register_operations(&mut self) {
         // Statistical operations
-        self.operations.insert("mean".to_string(), Box::new(|data, _| {
-            let array = Array1::from_vec(data.to_vec());
-            let mean = array.mean().unwrap_or(0.0);
-            Ok(serde_json::json!({
-                "mean": mean,
-                "count": data.len()
-            }))
-        }));
-        
-        self.operations.insert("std".to_string(), Box::new(|data, _| {
-            let array = Array1::from_vec(data.to_vec());
-            let mean = array.mean().unwrap_or(0.0);
-            let variance = array.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64;
-            let std = variance.sqrt();
-            Ok(serde_json::json!({
-                "std": std,
-                "variance": variance,
-                "count": data.len()
-            }))
-        }));
-        
-        self.operations.insert("min_max".to_string(), Box::new(|data, _| {
-            let min = data.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-            let max = data.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-            Ok(serde_json::json!({
-                "min": min,
-                "max": max,
-                "range": max - min,
-                "count": data.len()
-            }))
-        }));
+        self.register(
+            OperationSchema::new("mean", "statistics", "Arithmetic mean"),
+            |data, _| {
+                let moments = compute::moments(data);
+                Ok(serde_json::json!({
+                    "mean": moments.sum / data.len() as f64,
+                    "count": data.len()
+                }))
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("std", "statistics", "Population standard deviation and variance"),
+            |data, _| {
+                let variance = compute::moments(data).variance();
+                let std = variance.sqrt();
+                Ok(serde_json::json!({
+                    "std": std,
+                    "variance": variance,
+                    "count": data.len()
+                }))
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("min_max", "statistics", "Minimum, maximum and range"),
+            |data, _| {
+                let moments = compute::moments(data);
+                let (min, max) = (moments.min, moments.max);
+                Ok(serde_json::json!({
+                    "min": min,
+                    "max": max,
+                    "range": max - min,
+                    "count": data.len()
+                }))
+            },
+        );
         
         // Mathematical operations
-        self.operations.insert("sum".to_string(), Box::new(|data, _| {
-            let sum: f64 = data.iter().sum();
-            Ok(serde_json::json!({
-                "sum": sum,
-                "count": data.len()
-            }))
-        }));
-        
-        self.operations.insert("product".to_string(), Box::new(|data, _| {
-            let product: f64 = data.iter().product();
-            Ok(serde_json::json!({
-                "product": product,
-                "count": data.len()
-            }))
-        }));
+        self.register(
+            OperationSchema::new("sum", "math", "Sum of all values"),
+            |data, _| {
+                let sum = compute::map_reduce_chunks(data, |chunk| chunk.iter().sum::<f64>(), |a, b| a + b);
+                Ok(serde_json::json!({
+                    "sum": sum,
+                    "count": data.len()
+                }))
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("product", "math", "Product of all values"),
+            |data, _| {
+                let product: f64 = data.iter().product();
+                Ok(serde_json::json!({
+                    "product": product,
+                    "count": data.len()
+                }))
+            },
+        );
         
         // Advanced operations
-        self.operations.insert("percentiles".to_string(), Box::new(|data, params| {
-            let mut sorted_data = data.to_vec();
-            sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        self.register(
+            OperationSchema::new("percentiles", "statistics", "Exact, weighted or approximate percentiles")
+                .param(ParamSpec::new("percentiles", ParamType::array(ParamType::Number), "Percentiles to compute, from 0 to 100")
+                    .default(serde_json::json!([25.0, 50.0, 75.0, 90.0, 95.0, 99.0]))
+                    .range(Some(0.0), Some(100.0)))
+                .param(ParamSpec::new("method", ParamType::String, "Interpolation between order statistics")
+                    .default("linear")
+                    .choices(&["linear", "lower", "higher", "midpoint", "nearest"])
+                    .aliases(&["type7", "hyndman_fan_7"]))
+                .param(ParamSpec::new("nan_policy", ParamType::String, "Handling of NaN values")
+                    .default("omit")
+                    .choices(&["omit", "propagate", "error"]))
+                .param(ParamSpec::new("weights", ParamType::array(ParamType::Number), "One non-negative weight per value (exact mode only)")
+                    .range(Some(0.0), None))
+                .param(ParamSpec::new("mode", ParamType::String, "Exact computation or a t-digest approximation")
+                    .default("exact")
+                    .choices(&["exact", "approximate"]))
+                .param(ParamSpec::new("compression", ParamType::Number, "t-digest compression for approximate mode")
+                    .default(100.0)
+                    .range(Some(1.0), None)),
+            |data, params| {
+                let percentiles = params.and_then(|p| p.get("percentiles"))
+                    .and_then(|p| p.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect::<Vec<f64>>())
+                    .unwrap_or_else(|| vec![25.0, 50.0, 75.0, 90.0, 95.0, 99.0]);
+                if let Some(bad) = percentiles.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+                    return Err(anyhow!("Percentiles must lie between 0 and 100, got {}", bad));
+                }
+                let qs: Vec<f64> = percentiles.iter().map(|p| p / 100.0).collect();
             
-            let percentiles = params.and_then(|p| p.get("percentiles"))
-                .and_then(|p| p.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect::<Vec<f64>>())
-                .unwrap_or_else(|| vec![25.0, 50.0, 75.0, 90.0, 95.0, 99.0]);
+                let method: QuantileMethod = parse_param(params, "method")?.unwrap_or_default();
+                let nan_policy: NanPolicy = parse_param(params, "nan_policy")?.unwrap_or_default();
+                let weights: Option<Vec<f64>> = parse_param(params, "weights")?;
+                let mode = params.and_then(|p| p.get("mode"))
+                    .and_then(|p| p.as_str())
+                    .unwrap_or("exact");
             
-            let mut results = HashMap::new();
-            for percentile in percentiles {
-                let index = (percentile / 100.0 * (sorted_data.len() - 1) as f64).round() as usize;
-                let index = index.min(sorted_data.len() - 1);
-                results.insert(format!("p{}", percentile), sorted_data[index]);
-            }
+                let values = match (mode, weights) {
+                    ("exact", None) => quantiles::quantiles(data, &qs, method, nan_policy)?,
+                    ("exact", Some(weights)) => quantiles::weighted_quantiles(data, &weights, &qs, nan_policy)?,
+                    ("approximate", None) => {
+                        if nan_policy == NanPolicy::Error && data.iter().any(|v| v.is_nan()) {
+                            return Err(anyhow!("Input contains NaN values"));
+                        }
+                        let compression = params.and_then(|p| p.get("compression"))
+                            .and_then(|p| p.as_f64())
+                            .unwrap_or(100.0);
+                        let mut digest = TDigest::new(compression);
+                        data.iter().for_each(|&v| digest.add(v));
+                        qs.iter().map(|&q| digest.quantile(q)).collect()
+                    }
+                    ("approximate", Some(_)) => return Err(anyhow!("Weighted percentiles are only available in exact mode")),
+                    (other, _) => return Err(anyhow!("Unknown percentile mode: {}", other)),
+                };
+            
+                let mut results = serde_json::Map::new();
+                for (percentile, value) in percentiles.iter().zip(values) {
+                    results.insert(format!("p{}", percentile), finite_or_null(value));
+                }
+                results.insert("method".to_string(), serde_json::to_value(method)?);
+                results.insert("mode".to_string(), Value::from(mode));
+                results.insert("count".to_string(), Value::from(data.len()));
             
-            Ok(serde_json::json!(results))
-        }));
+                Ok(Value::Object(results))
+            },
+        );
         
-        self.operations.insert("histogram".to_string(), Box::new(|data, params| {
-            let bins = params.and_then(|p| p.get("bins"))
-                .and_then(|p| p.as_u64())
-                .unwrap_or(10) as usize;
+        self.register(
+            OperationSchema::new("histogram", "distribution", "Bin counts with optional density and cumulative totals")
+                .param(ParamSpec::new("bins", ParamType::OneOf { options: vec![ParamType::Integer, ParamType::String] }, "Number of bins or a binning rule")
+                    .default(10)
+                    .range(Some(1.0), None)
+                    .choices(&["auto", "sturges", "scott", "freedman_diaconis"])
+                    .aliases(&["fd"]))
+                .param(ParamSpec::new("edges", ParamType::array(ParamType::Number), "Explicit increasing bin edges; overrides bins"))
+                .param(ParamSpec::new("log_scale", ParamType::Boolean, "Logarithmically spaced bins").default(false))
+                .param(ParamSpec::new("density", ParamType::Boolean, "Normalise counts to a probability density").default(false))
+                .param(ParamSpec::new("cumulative", ParamType::Boolean, "Include running totals").default(false)),
+            |data, params| {
+                let options: HistogramOptions = parse_options(params, "histogram")?;
             
-            let min = data.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-            let max = data.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-            let bin_width = (max - min) / bins as f64;
+                let histogram = binning::compute_histogram(data, &options)?;
+                Ok(serde_json::to_value(histogram)?)
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("outliers", "distribution", "Flags values far from the bulk of the data")
+                .param(ParamSpec::new("method", ParamType::String, "Detection method")
+                    .default("z_score")
+                    .choices(&["z_score", "modified_z_score", "iqr", "rolling"])
+                    .aliases(&["mad"]))
+                .param(ParamSpec::new("threshold", ParamType::Number, "Cut-off; defaults to 3 for z-scores, 3.5 for modified z-scores and 1.5 for IQR fences")
+                    .range(Some(0.0), None))
+                .param(ParamSpec::new("window", ParamType::Integer, "Rolling method: preceding values each point is compared against")
+                    .default(20)
+                    .range(Some(2.0), None)),
+            |data, params| {
+                let options = outlier_options(params)?;
+                Ok(serde_json::to_value(outliers::detect_outliers(data, &options)?)?)
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("forecast", "time_series", "Forecast with prediction bands and holdout accuracy")
+                .param(ParamSpec::new("method", ParamType::String, "Forecasting model")
+                    .default("simple_exponential")
+                    .choices(&["moving_average", "simple_exponential", "double_exponential", "triple_exponential", "linear_trend"])
+                    .aliases(&["ses", "simple_exponential_smoothing", "holt", "double_exponential_smoothing", "holt_winters", "triple_exponential_smoothing"]))
+                .param(ParamSpec::new("horizon", ParamType::Integer, "Number of future steps").default(10).range(Some(1.0), None))
+                .param(ParamSpec::new("window", ParamType::Integer, "Moving average window").default(3).range(Some(1.0), None))
+                .param(ParamSpec::new("alpha", ParamType::Number, "Level smoothing; chosen by grid search when absent").range(Some(0.0), Some(1.0)))
+                .param(ParamSpec::new("beta", ParamType::Number, "Trend smoothing; chosen by grid search when absent").range(Some(0.0), Some(1.0)))
+                .param(ParamSpec::new("gamma", ParamType::Number, "Seasonal smoothing; chosen by grid search when absent").range(Some(0.0), Some(1.0)))
+                .param(ParamSpec::new("season_length", ParamType::Integer, "Observations per season").range(Some(2.0), None))
+                .param(ParamSpec::new("seasonal", ParamType::String, "How seasonality combines with the level")
+                    .default("additive")
+                    .choices(&["additive", "multiplicative"]))
+                .param(ParamSpec::new("holdout", ParamType::Integer, "Trailing observations held back to measure accuracy"))
+                .param(ParamSpec::new("confidence", ParamType::Number, "Coverage of the prediction bands").default(0.95).range(Some(0.0), Some(1.0))),
+            |data, params| {
+                let options: ForecastOptions = parse_options(params, "forecast")?;
             
-            let mut histogram = vec![0; bins];
-            for &value in data {
-                let bin_index = ((value - min) / bin_width).floor() as usize;
-                let bin_index = bin_index.min(bins - 1);
-                histogram[bin_index] += 1;
-            }
+                Ok(serde_json::to_value(forecasting::forecast(data, &options)?)?)
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("scale", "transform", "Standard, min-max, robust, max-abs, power and log1p scaling")
+                .param(ParamSpec::new("method", ParamType::String, "Scaling method")
+                    .default("standard")
+                    .choices(&["standard", "min_max", "robust", "max_abs", "box_cox", "yeo_johnson", "log1p"])
+                    .aliases(&["z_score"]))
+                .param(ParamSpec::new("feature_range", ParamType::Array { items: Box::new(ParamType::Number), length: Some(2) }, "Target [low, high] for min-max scaling")
+                    .default(serde_json::json!([0.0, 1.0])))
+                .param(ParamSpec::new("lambda", ParamType::Number, "Fixed power-transform lambda; estimated when absent"))
+                .param(ParamSpec::new("fitted", ParamType::Object, "Parameters returned by an earlier call, reapplied without refitting")),
+            |data, params| {
+                let options: ScalingOptions = parse_options(params, "scaling")?;
             
-            let bin_edges: Vec<f64> = (0..=bins).map(|i| min + i as f64 * bin_width).collect();
+                Ok(serde_json::to_value(scaling::scale(data, &options)?)?)
+            },
+        );
+        
+        // Samples are packed consecutively and split by 'group_sizes'; chi-square takes a table with 'shape'
+        self.register(
+            OperationSchema::new("hypothesis_test", "statistics", "t, Welch, chi-square, Mann-Whitney U and one-way ANOVA tests")
+                .param(ParamSpec::new("test", ParamType::String, "Test to run")
+                    .default("one_sample_t")
+                    .choices(&["one_sample_t", "two_sample_t", "welch_t", "chi_square", "mann_whitney_u", "anova"]))
+                .param(ParamSpec::new("alternative", ParamType::String, "Alternative hypothesis")
+                    .default("two_sided")
+                    .choices(&["two_sided", "less", "greater"]))
+                .param(ParamSpec::new("mu", ParamType::Number, "Hypothesised mean for the one-sample t-test").default(0.0))
+                .param(ParamSpec::new("group_sizes", ParamType::array(ParamType::Integer), "Lengths of the consecutive samples packed into the data")
+                    .range(Some(0.0), None))
+                .param(ParamSpec::new("shape", ParamType::shape(), "[rows, cols] of the contingency table for the chi-square test")
+                    .range(Some(1.0), None)),
+            |data, params| {
+                let options: HypothesisOptions = parse_options(params, "hypothesis test")?;
             
-            Ok(serde_json::json!({
-                "histogram": histogram,
-                "bin_edges": bin_edges,
-                "bin_width": bin_width,
-                "count": data.len()
-            }))
-        }));
-        
-        // Matrix operations
-        self.operations.insert("matrix_multiply".to_string(), Box::new(|data, params| {
-            let matrix_size = params.and_then(|p| p.get("matrix_size"))
-                .and_then(|p| p.as_u64())
-                .unwrap_or(2) as usize;
+                Ok(serde_json::to_value(hypothesis::run_on_values(data, &options)?)?)
+            },
+        );
+        
+        // Sampled values are returned with their positions so the subset can be traced back
+        self.register(
+            OperationSchema::new("sample", "sampling", "Seeded random, systematic or reservoir sample")
+                .param(ParamSpec::new("method", ParamType::String, "Sampling method")
+                    .default("random")
+                    .choices(&["random", "systematic", "reservoir"]))
+                .param(ParamSpec::new("size", ParamType::Integer, "Number of values to draw").range(Some(0.0), None))
+                .param(ParamSpec::new("fraction", ParamType::Number, "Share of values to draw").range(Some(0.0), Some(1.0)))
+                .param(seed_param()),
+            |data, params| {
+                let options: SamplingOptions = parse_options(params, "sampling")?;
+                let sample = sampling::sample_indices(data.len(), &options, None)?;
+                let values: Vec<Value> = sample.indices.iter().map(|&i| finite_or_null(data[i])).collect();
             
-            if data.len() != matrix_size * matrix_size * 2 {
-                return Err(anyhow!("Data length must be 2 * matrix_size^2 for matrix multiplication"));
-            }
+                let mut result = serde_json::to_value(&sample)?;
+                result["values"] = Value::from(values);
+                result["sample_size"] = Value::from(sample.indices.len());
+                Ok(result)
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("train_test_split", "sampling", "Seeded train, test and validation partitions")
+                .param(ParamSpec::new("train", ParamType::Number, "Share of values for training").default(0.8).range(Some(0.0), Some(1.0)))
+                .param(ParamSpec::new("test", ParamType::Number, "Share of values for testing").default(0.2).range(Some(0.0), Some(1.0)))
+                .param(ParamSpec::new("validation", ParamType::Number, "Share of values for validation").default(0.0).range(Some(0.0), Some(1.0)))
+                .param(ParamSpec::new("shuffle", ParamType::Boolean, "Shuffle before partitioning; otherwise partitions are contiguous").default(true))
+                .param(seed_param()),
+            |data, params| {
+                let options: SplitOptions = parse_options(params, "split")?;
+                let split = sampling::split_indices(data.len(), &options, None)?;
+                let partition = |indices: &[usize]| serde_json::json!({
+                    "indices": indices,
+                    "values": indices.iter().map(|&i| finite_or_null(data[i])).collect::<Vec<_>>()
+                });
             
-            let split_point = matrix_size * matrix_size;
-            let matrix_a_data = &data[..split_point];
-            let matrix_b_data = &data[split_point..];
+                Ok(serde_json::json!({
+                    "seed": split.seed,
+                    "train": partition(&split.train),
+                    "test": partition(&split.test),
+                    "validation": partition(&split.validation)
+                }))
+            },
+        );
+        
+        // Matrix operations; inputs are flat row-major values with an explicit 'shape' of [rows, cols]
+        self.register(
+            OperationSchema::new("matrix_multiply", "linear_algebra", "Product of two matrices packed back to back")
+                .param(ParamSpec::new("shape_a", ParamType::shape(), "[rows, cols] of the first matrix").range(Some(1.0), None))
+                .param(ParamSpec::new("shape_b", ParamType::shape(), "[rows, cols] of the second matrix").range(Some(1.0), None))
+                .param(ParamSpec::new("matrix_size", ParamType::Integer, "Size of two square matrices, when shapes are not given")
+                    .default(2)
+                    .range(Some(1.0), None)),
+            |data, params| {
+                let (matrix_a, matrix_b) = match parse_param::<[usize; 2]>(params, "shape_a")? {
+                    Some([rows, cols]) => {
+                        let split_point = rows * cols;
+                        if data.len() <= split_point {
+                            return Err(anyhow!("Data holds {} values but matrix A alone needs {}", data.len(), split_point));
+                        }
+                        let [b_rows, b_cols] = parse_param::<[usize; 2]>(params, "shape_b")?
+                            .unwrap_or([cols, (data.len() - split_point) / cols]);
+                        if b_rows != cols {
+                            return Err(anyhow!("Cannot multiply {}x{} by {}x{}: inner dimensions differ", rows, cols, b_rows, b_cols));
+                        }
+                        (linalg::from_flat(&data[..split_point], rows, cols)?, linalg::from_flat(&data[split_point..], b_rows, b_cols)?)
+                    }
+                    // Two square matrices packed back to back, sized by 'matrix_size'
+                    None => {
+                        let matrix_size = params.and_then(|p| p.get("matrix_size"))
+                            .and_then(|p| p.as_u64())
+                            .unwrap_or(2) as usize;
+                    
+                        if data.len() != matrix_size * matrix_size * 2 {
+                            return Err(anyhow!("Data length must be 2 * matrix_size^2 for matrix multiplication"));
+                        }
+                    
+                        let split_point = matrix_size * matrix_size;
+                        (linalg::from_flat(&data[..split_point], matrix_size, matrix_size)?,
+                         linalg::from_flat(&data[split_point..], matrix_size, matrix_size)?)
+                    }
+                };
             
-            let matrix_a = Array2::from_shape_vec((matrix_size, matrix_size), matrix_a_data.to_vec())?;
-            let matrix_b = Array2::from_shape_vec((matrix_size, matrix_size), matrix_b_data.to_vec())?;
+                let result = matrix_a.dot(&matrix_b);
             
-            let result = matrix_a.dot(&matrix_b);
+                Ok(serde_json::json!({
+                    "result": matrix_values(&result),
+                    "dimensions": [result.nrows(), result.ncols()],
+                    "operation": "matrix_multiplication"
+                }))
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("matrix_transpose", "linear_algebra", "Transpose")
+                .param(matrix_shape_param()),
+            |data, params| {
+                let transposed = matrix_input(data, params)?.t().to_owned();
+                Ok(serde_json::json!({
+                    "result": matrix_values(&transposed),
+                    "dimensions": [transposed.nrows(), transposed.ncols()],
+                    "operation": "matrix_transpose"
+                }))
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("matrix_inverse", "linear_algebra", "Inverse of a well-conditioned square matrix")
+                .param(matrix_shape_param()),
+            |data, params| {
+                let matrix = matrix_input(data, params)?;
+                let condition_number = linalg::ensure_well_conditioned(&matrix)?;
+                let inverse = linalg::inverse(&matrix)?;
+                Ok(serde_json::json!({
+                    "result": matrix_values(&inverse),
+                    "dimensions": [inverse.nrows(), inverse.ncols()],
+                    "condition_number": condition_number,
+                    "operation": "matrix_inverse"
+                }))
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("matrix_determinant", "linear_algebra", "Determinant of a square matrix")
+                .param(matrix_shape_param()),
+            |data, params| {
+                let matrix = matrix_input(data, params)?;
+                Ok(serde_json::json!({
+                    "determinant": linalg::determinant(&matrix)?,
+                    "dimensions": [matrix.nrows(), matrix.ncols()],
+                    "operation": "matrix_determinant"
+                }))
+            },
+        );
+        
+        // Data holds the n x n coefficient matrix followed by the right-hand side, one or more columns of n rows
+        self.register(
+            OperationSchema::new("matrix_solve", "linear_algebra", "Solves A x = b for a coefficient matrix followed by its right-hand side")
+                .param(ParamSpec::new("shape", ParamType::shape(), "[rows, cols] of the coefficient matrix").required().range(Some(1.0), None)),
+            |data, params| {
+                let [rows, cols] = parse_param::<[usize; 2]>(params, "shape")?
+                    .ok_or_else(|| anyhow!("matrix_solve requires 'shape' for the coefficient matrix"))?;
+                let split_point = rows * cols;
+                if rows == 0 || data.len() <= split_point || !(data.len() - split_point).is_multiple_of(rows) {
+                    return Err(anyhow!("After the {}x{} coefficient matrix the data must hold a right-hand side with {} rows", rows, cols, rows));
+                }
+                let a = linalg::from_flat(&data[..split_point], rows, cols)?;
+                let b = linalg::from_flat(&data[split_point..], rows, (data.len() - split_point) / rows)?;
             
-            Ok(serde_json::json!({
-                "result": result.into_raw_vec_and_offset().0,
-                "dimensions": [matrix_size, matrix_size],
-                "operation": "matrix_multiplication"
-            }))
-        }));
+                let condition_number = linalg::ensure_well_conditioned(&a)?;
+                let solution = linalg::solve(&a, &b)?;
+                Ok(serde_json::json!({
+                    "solution": matrix_values(&solution),
+                    "dimensions": [solution.nrows(), solution.ncols()],
+                    "condition_number": condition_number,
+                    "operation": "matrix_solve"
+                }))
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("matrix_eigen", "linear_algebra", "Eigenvalues, with eigenvectors for symmetric matrices")
+                .param(matrix_shape_param()),
+            |data, params| {
+                let matrix = matrix_input(data, params)?;
+                let decomposition = linalg::eigen(&matrix)?;
+                let eigenvalues: Vec<Value> = decomposition.real.iter().zip(&decomposition.imaginary)
+                    .map(|(real, imag)| serde_json::json!({"real": real, "imag": imag}))
+                    .collect();
+                Ok(serde_json::json!({
+                    "eigenvalues": eigenvalues,
+                    "eigenvectors": decomposition.vectors.as_ref().map(matrix_values),
+                    "symmetric": decomposition.vectors.is_some(),
+                    "dimensions": [matrix.nrows(), matrix.ncols()],
+                    "operation": "matrix_eigen"
+                }))
+            },
+        );
+        
+        self.register(
+            OperationSchema::new("matrix_svd", "linear_algebra", "Thin singular value decomposition and rank")
+                .param(matrix_shape_param()),
+            |data, params| {
+                let matrix = matrix_input(data, params)?;
+                let decomposition = linalg::svd(&matrix)?;
+                let largest = decomposition.singular_values.first().copied().unwrap_or(0.0);
+                let tolerance = f64::EPSILON * matrix.nrows().max(matrix.ncols()) as f64 * largest;
+                let rank = decomposition.singular_values.iter().filter(|&&s| s > tolerance).count();
+                Ok(serde_json::json!({
+                    "u": matrix_values(&decomposition.u),
+                    "u_dimensions": [decomposition.u.nrows(), decomposition.u.ncols()],
+                    "singular_values": decomposition.singular_values,
+                    "vt": matrix_values(&decomposition.vt),
+                    "vt_dimensions": [decomposition.vt.nrows(), decomposition.vt.ncols()],
+                    "rank": rank,
+                    "operation": "matrix_svd"
+                }))
+            },
+        );
         
         // Custom operations
-        self.operations.insert("custom".to_string(), Box::new(|data, params| {
-            let operation = params.and_then(|p| p.get("operation"))
-                .and_then(|p| p.as_str())
-                .ok_or_else(|| anyhow!("Custom operation requires 'operation' parameter"))?;
+        self.register(
+            OperationSchema::new("custom", "transform", "Element-wise normalize, log or exponential transform")
+                .param(ParamSpec::new("operation", ParamType::String, "Transform to apply")
+                    .required()
+                    .choices(&["normalize", "log_transform", "exponential"])),
+            |data, params| {
+                let operation = params.and_then(|p| p.get("operation"))
+                    .and_then(|p| p.as_str())
+                    .ok_or_else(|| anyhow!("Custom operation requires 'operation' parameter"))?;
             
-            match operation {
-                "normalize" => {
-                    let mean = data.iter().sum::<f64>() / data.len() as f64;
-                    let std = (data.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64).sqrt();
-                    let normalized: Vec<f64> = data.iter().map(|&x| (x - mean) / std).collect();
+                match operation {
+                    "normalize" => {
+                        let mean = data.iter().sum::<f64>() / data.len() as f64;
+                        let std = (data.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64).sqrt();
+                        // Constant data has no spread to divide by and normalizes to zeros
+                        let scale = if std > 0.0 { std } else { 1.0 };
+                        let normalized: Vec<Value> = data.iter().map(|&x| finite_or_null((x - mean) / scale)).collect();
                     
-                    Ok(serde_json::json!({
-                        "normalized_data": normalized,
-                        "mean": mean,
-                        "std": std,
-                        "count": data.len()
-                    }))
-                }
-                "log_transform" => {
-                    let transformed: Vec<f64> = data.iter()
-                        .map(|&x| if x > 0.0 { x.ln() } else { f64::NEG_INFINITY })
-                        .collect();
+                        Ok(serde_json::json!({
+                            "normalized_data": normalized,
+                            "mean": mean,
+                            "std": std,
+                            "count": data.len()
+                        }))
+                    }
+                    "log_transform" => {
+                        // The log is undefined for non-positive values, which become null
+                        let transformed: Vec<Value> = data.iter()
+                            .map(|&x| if x > 0.0 { finite_or_null(x.ln()) } else { Value::Null })
+                            .collect();
                     
-                    Ok(serde_json::json!({
-                        "transformed_data": transformed,
-                        "operation": "log_transform",
-                        "count": data.len()
-                    }))
-                }
-                "exponential" => {
-                    let transformed: Vec<f64> = data.iter().map(|&x| x.exp()).collect();
+                        Ok(serde_json::json!({
+                            "transformed_data": transformed,
+                            "operation": "log_transform",
+                            "count": data.len()
+                        }))
+                    }
+                    "exponential" => {
+                        let transformed: Vec<Value> = data.iter().map(|&x| finite_or_null(x.exp())).collect();
                     
-                    Ok(serde_json::json!({
-                        "transformed_data": transformed,
-                        "operation": "exponential",
-                        "count": data.len()
-                    }))
+                        Ok(serde_json::json!({
+                            "transformed_data": transformed,
+                            "operation": "exponential",
+                            "count": data.len()
+                        }))
+                    }
+                    _ => Err(anyhow!("Unknown custom operation: {}", operation))
                 }
-                _ => Err(anyhow!("Unknown custom operation: {}", operation))
-            }
-        }));
+            },
+        );
+    }
+    
+    fn register_table(&mut self, schema: OperationSchema) {
+        self.table_schemas.insert(schema.name.clone(), schema);
+    }
+    
+    // Table operations reuse the parameters of their value counterparts where the two share options
+    fn register_table_operations(&mut self) {
+        for (name, description) in [
+            ("describe", "Per-column counts, summary statistics and most frequent values"),
+            ("shape", "Row and column counts"),
+            ("columns", "Column names"),
+            ("dtypes", "Column data types"),
+        ] {
+            self.register_table(OperationSchema::new(name, "table", description));
+        }
+        for (name, description) in [("head", "First rows of the table"), ("tail", "Last rows of the table")] {
+            self.register_table(
+                OperationSchema::new(name, "table", description)
+                    .param(ParamSpec::new("n", ParamType::Integer, "Number of rows to return").default(10).range(Some(0.0), None)),
+            );
+        }
+        self.register_table(
+            OperationSchema::new("profile", "table", "Types, null counts, distributions and top values of every column")
+                .param(ParamSpec::new("top_n", ParamType::Integer, "Most frequent values listed per column").default(10).range(Some(0.0), None)),
+        );
+        
+        // Rows with a missing value in any selected column are dropped before the matrix is computed
+        let columns = ParamSpec::new("columns", ParamType::array(ParamType::String), "Numeric columns to include; every numeric column when absent");
+        self.register_table(
+            OperationSchema::new("correlation", "statistics", "Pairwise correlation matrix of numeric columns")
+                .param(columns.clone())
+                .param(ParamSpec::new("method", ParamType::String, "Correlation coefficient")
+                    .default("pearson")
+                    .choices(&["pearson", "spearman", "kendall"])),
+        );
+        self.register_table(
+            OperationSchema::new("covariance", "statistics", "Sample covariance matrix of numeric columns").param(columns),
+        );
+        self.register_table(
+            OperationSchema::new("regression", "statistics", "Ordinary least squares fit of a target column on feature columns")
+                .param(ParamSpec::new("target", ParamType::String, "Dependent column").required())
+                .param(ParamSpec::new("features", ParamType::array(ParamType::String), "Explanatory columns").required())
+                .param(ParamSpec::new("include_residuals", ParamType::Boolean, "Return the residual of every row used").default(true)),
+        );
+        
+        let mut outliers = OperationSchema::new("outliers", "distribution", "Flags rows whose value lies far from the bulk of a column")
+            .param(ParamSpec::new("column", ParamType::String, "Numeric column to score").required());
+        outliers.parameters.extend(self.operations["outliers"].schema().parameters.iter().cloned());
+        self.register_table(
+            outliers
+                .param(ParamSpec::new("tag_rows", ParamType::Boolean, "Return the table with flag and score columns added").default(false))
+                .param(ParamSpec::new("flag_column", ParamType::String, "Name of the added flag column").default("is_outlier"))
+                .param(ParamSpec::new("score_column", ParamType::String, "Name of the added score column").default("outlier_score")),
+        );
+        
+        // Time-series operations read a numeric column against a date or datetime column
+        let series = |name: &str, description: &str| {
+            OperationSchema::new(name, "time_series", description)
+                .param(ParamSpec::new("time_column", ParamType::String, "Date or datetime column").required())
+                .param(ParamSpec::new("value_column", ParamType::String, "Numeric column").required())
+        };
+        let frequency = ParamSpec::new("frequency", ParamType::String, "Calendar period")
+            .default("day")
+            .choices(&["day", "week", "month", "quarter"])
+            .aliases(&["D", "daily", "W", "weekly", "M", "monthly", "Q", "quarterly"]);
+        let aggregation = |default: &str| {
+            ParamSpec::new("aggregation", ParamType::String, "How the values in a period or window combine")
+                .default(default)
+                .choices(&["sum", "mean", "median", "min", "max", "count", "first", "last", "std"])
+        };
+        let periods = ParamSpec::new("periods", ParamType::Integer, "Lag in observations").default(1).range(Some(1.0), None);
+        self.register_table(
+            series("resample", "Aggregates values into calendar periods")
+                .param(frequency.clone())
+                .param(aggregation("sum"))
+                .param(ParamSpec::new("include_empty", ParamType::Boolean, "Keep periods without observations").default(true)),
+        );
+        self.register_table(
+            series("rolling", "Aggregate over a window of consecutive observations")
+                .param(ParamSpec::new("window", ParamType::Integer, "Observations per window").default(7).range(Some(1.0), None))
+                .param(ParamSpec::new("min_periods", ParamType::Integer, "Observations a window needs for a result; defaults to the window")
+                    .range(Some(1.0), None))
+                .param(ParamSpec::new("center", ParamType::Boolean, "Centre each window on its observation").default(false))
+                .param(aggregation("mean")),
+        );
+        self.register_table(series("difference", "Change from the value a number of observations earlier").param(periods.clone()));
+        self.register_table(series("pct_change", "Relative change from the value a number of observations earlier").param(periods));
+        self.register_table(
+            series("fill_gaps", "Inserts missing calendar periods and fills their values")
+                .param(frequency.clone())
+                .param(ParamSpec::new("fill_method", ParamType::String, "Value given to inserted periods")
+                    .default("null")
+                    .choices(&["null", "zero", "forward", "backward", "linear"])
+                    .aliases(&["ffill", "bfill"])),
+        );
+        self.register_table(
+            series("seasonal_decompose", "Trend, seasonal and residual components")
+                .param(ParamSpec::new("period", ParamType::Integer, "Observations per season").required().range(Some(2.0), None))
+                .param(ParamSpec::new("model", ParamType::String, "How the components combine")
+                    .default("additive")
+                    .choices(&["additive", "multiplicative"])),
+        );
+        let mut forecast = series("forecast", "Forecast of a dated column with prediction bands and holdout accuracy").param(frequency);
+        forecast.parameters.extend(self.operations["forecast"].schema().parameters.iter().cloned());
+        self.register_table(forecast);
+        
+        let stratify_by = ParamSpec::new("stratify_by", ParamType::String, "Column whose values define the strata");
+        self.register_table(
+            OperationSchema::new("sample", "sampling", "Seeded random, stratified, systematic or reservoir sample of rows")
+                .param(ParamSpec::new("method", ParamType::String, "Sampling method")
+                    .default("random")
+                    .choices(&["random", "stratified", "systematic", "reservoir"]))
+                .param(ParamSpec::new("size", ParamType::Integer, "Number of rows to draw").range(Some(0.0), None))
+                .param(ParamSpec::new("fraction", ParamType::Number, "Share of rows to draw").range(Some(0.0), Some(1.0)))
+                .param(seed_param())
+                .param(stratify_by.clone()),
+        );
+        self.register_table(self.operations["train_test_split"].schema().clone().param(stratify_by));
+        
+        // Samples come from columns rather than packed values, so the packing parameters do not apply
+        let mut hypothesis = OperationSchema::new("hypothesis_test", "statistics", "t, Welch, chi-square, Mann-Whitney U and one-way ANOVA tests on table columns");
+        hypothesis.parameters = self.operations["hypothesis_test"].schema().parameters.iter()
+            .filter(|p| !matches!(p.name.as_str(), "group_sizes" | "shape"))
+            .cloned()
+            .collect();
+        self.register_table(
+            hypothesis
+                .param(ParamSpec::new("value_column", ParamType::String, "Numeric column holding the observations; unused by chi-square"))
+                .param(ParamSpec::new("group_column", ParamType::String, "Column whose values split the rows into samples"))
+                .param(ParamSpec::new("groups", ParamType::array(ParamType::String), "Groups to compare, in order; every group in order of first appearance when absent"))
+                .param(ParamSpec::new("category_column", ParamType::String, "Second categorical column for the chi-square test")),
+        );
     }
     
     pub async fn process_data(
         &self,
         data: &[f64],
         operation: &str,
-        _parameters: Option<&Value>,
+        parameters: Option<&Value>,
     ) -> Result<Value> {
         info!("Processing data with operation: {}, data size: {}", operation, data.len());
         
@@ -202,58 +702,343 @@ impl DataProcessor {
         }
         
         let operation_func = self.operations.get(operation)
-            .ok_or_else(|| anyhow!("Unknown operation: {}", operation))?;
+            .ok_or_else(|| anyhow!("Unknown operation: {}", operation))?
+            .clone();
+        let parameters = operation_func.schema().validate(parameters, false)?;
         
-        let result = operation_func(data, _parameters)?;
+        // The work runs on the compute pool so the async executor stays free to serve other requests
+        let data = data.to_vec();
+        let result = self.compute.run(move || operation_func.execute(&data, Some(&parameters))).await??;
         
         info!("Data processing completed successfully for operation: {}", operation);
         Ok(result)
     }
     
+    pub async fn process_columns(&self, df: &DataFrame, spec: &ColumnOperations) -> Result<Value> {
+        info!("Processing {} operations over {} columns grouped by {:?}", spec.operations.len(), spec.columns.len(), spec.group_by);
+        
+        if spec.columns.is_empty() || spec.operations.is_empty() {
+            return Err(anyhow!("At least one column and one operation are required"));
+        }
+        for operation in &spec.operations {
+            if !self.operations.contains_key(operation) {
+                return Err(anyhow!("Unknown operation: {}", operation));
+            }
+        }
+        
+        // Parameters are shared by every requested operation: each name must belong to at least one
+        // of them, and each operation validates only the names it declares
+        if let Some(Value::Object(params)) = &spec.parameters {
+            let declared = |name: &str| name == "weight_column"
+                || spec.operations.iter().any(|operation| self.operations[operation].schema().declares(name));
+            if let Some(unknown) = params.keys().find(|name| !declared(name)) {
+                return Err(anyhow!("Parameter '{}' is not used by any of the operations {}", unknown, spec.operations.join(", ")));
+            }
+        }
+        let mut validated = HashMap::new();
+        let mut operations = HashMap::new();
+        for operation in &spec.operations {
+            let operation_func = self.operations[operation].clone();
+            validated.insert(operation.clone(), operation_func.schema().validate(spec.parameters.as_ref(), true)?);
+            operations.insert(operation.clone(), operation_func);
+        }
+        
+        // Every group and column is computed on the compute pool, away from the HTTP workers
+        let (df, spec) = (df.clone(), spec.clone());
+        self.compute.run(move || Self::column_results(&df, &spec, &operations, &validated)).await?
+    }
+    
+    fn column_results(
+        df: &DataFrame,
+        spec: &ColumnOperations,
+        operations: &HashMap<String, Arc<dyn DataOperation>>,
+        validated: &HashMap<String, Value>,
+    ) -> Result<Value> {
+        let mut columns = Vec::with_capacity(spec.columns.len());
+        for name in &spec.columns {
+            let column = df.column(name).map_err(|_| anyhow!("Column '{}' not found", name))?;
+            if !column.dtype().is_primitive_numeric() {
+                return Err(anyhow!("Column '{}' has type {} and is not numeric", name, column.dtype()));
+            }
+            let values: Vec<Option<f64>> = column.cast(&DataType::Float64)?.f64()?.into_iter().collect();
+            columns.push((name, values));
+        }
+        
+        // A weight column feeds weighted operations such as percentiles; rows need both a value and a weight
+        let weights: Option<Vec<Option<f64>>> = match spec.parameters.as_ref().and_then(|p| p.get("weight_column")).and_then(|w| w.as_str()) {
+            Some(name) => {
+                let column = df.column(name).map_err(|_| anyhow!("Weight column '{}' not found", name))?;
+                Some(column.cast(&DataType::Float64)?.f64()?.into_iter().collect())
+            }
+            None => None,
+        };
+        
+        let groups = group_row_indices(df, &spec.group_by)?;
+        let mut results = Vec::new();
+        
+        for (group, rows) in &groups {
+            for (name, values) in &columns {
+                let used: Vec<usize> = rows.iter()
+                    .copied()
+                    .filter(|&row| values[row].is_some() && weights.as_ref().is_none_or(|w| w[row].is_some()))
+                    .collect();
+                let present: Vec<f64> = used.iter().filter_map(|&row| values[row]).collect();
+                let null_count = rows.len() - present.len();
+                
+                let group_weights: Option<Vec<f64>> = weights.as_ref()
+                    .map(|weights| used.iter().filter_map(|&row| weights[row]).collect());
+                
+                if null_count > 0 && !spec.skip_nulls {
+                    return Err(anyhow!(
+                        "Column '{}' has {} null values{}; enable skip_nulls to ignore them",
+                        name, null_count, if spec.group_by.is_empty() { String::new() } else { format!(" in group {}", group) }
+                    ));
+                }
+                
+                for operation in &spec.operations {
+                    let mut entry = serde_json::json!({
+                        "column": name,
+                        "operation": operation,
+                        "count": present.len(),
+                        "null_count": null_count
+                    });
+                    if !spec.group_by.is_empty() {
+                        entry["group"] = group.clone();
+                    }
+                    
+                    // A failing group is reported in place so the other groups still return results
+                    if present.is_empty() {
+                        entry["result"] = Value::Null;
+                        entry["error"] = Value::from("No non-null values");
+                    } else {
+                        let operation_func = &operations[operation];
+                        let mut parameters = validated[operation].clone();
+                        if let (Some(group_weights), true) = (&group_weights, operation_func.schema().declares("weights")) {
+                            parameters["weights"] = serde_json::json!(group_weights);
+                        }
+                        match operation_func.execute(&present, Some(&parameters)) {
+                            Ok(result) => entry["result"] = result,
+                            Err(e) => {
+                                entry["result"] = Value::Null;
+                                entry["error"] = Value::from(e.to_string());
+                            }
+                        }
+                    }
+                    results.push(entry);
+                }
+            }
+        }
+        
+        Ok(serde_json::json!({
+            "results": results,
+            "group_count": groups.len(),
+            "total_rows": df.height()
+        }))
+    }
+    
     pub async fn process_dataframe(
         &self,
         csv_data: &str,
         operations: &[String],
-        _parameters: Option<&Value>,
+        parameters: Option<&Value>,
     ) -> Result<Value> {
         info!("Processing DataFrame with {} operations", operations.len());
         
-        // Parse CSV data
-        let df = CsvReader::new(std::io::Cursor::new(csv_data))
-            .finish()
-            .map_err(|e| anyhow!("Failed to parse CSV: {}", e))?;
+        // Parse CSV data on the compute pool, like the operations that follow
+        let csv_data = csv_data.to_string();
+        let df = self.compute.run(move || {
+            CsvReader::new(std::io::Cursor::new(csv_data))
+                .finish()
+                .map_err(|e| anyhow!("Failed to parse CSV: {}", e))
+        }).await??;
         
+        self.process_loaded_dataframe(&df, operations, parameters).await
+    }
+    
+    pub async fn process_loaded_dataframe(
+        &self,
+        df: &DataFrame,
+        operations: &[String],
+        parameters: Option<&Value>,
+    ) -> Result<Value> {
+        for operation in operations {
+            if !self.table_schemas.contains_key(operation) {
+                return Err(anyhow!("Unknown DataFrame operation: {}", operation));
+            }
+        }
+        
+        // As with column operations, each parameter must belong to one of the requested operations
+        // and each operation validates only the names it declares
+        if let Some(Value::Object(params)) = parameters {
+            let declared = |name: &str| operations.iter().any(|operation| self.table_schemas[operation].declares(name));
+            if let Some(unknown) = params.keys().find(|name| !declared(name)) {
+                return Err(anyhow!("Parameter '{}' is not used by any of the operations {}", unknown, operations.join(", ")));
+            }
+        }
+        let validated = operations.iter()
+            .map(|operation| Ok((operation.clone(), self.table_schemas[operation].validate(parameters, true)?)))
+            .collect::<Result<Vec<_>>>()?;
+        
+        // Table operations run on the compute pool; cloning the frame only copies column handles
+        let df = df.clone();
+        self.compute.run(move || Self::table_operations(&df, &validated)).await?
+    }
+    
+    fn table_operations(df: &DataFrame, operations: &[(String, Value)]) -> Result<Value> {
         let mut results = Vec::new();
         
-        for operation in operations {
+        for (operation, parameters) in operations {
+            let parameters = Some(parameters);
             let result = match operation.as_str() {
                 "describe" => {
-                    // Polars 0.50 doesn't have describe method, using alternative
                     serde_json::json!({
                         "operation": "describe",
-                        "columns": df.get_column_names(),
                         "shape": [df.height(), df.width()],
-                        "dtypes": df.dtypes().iter().map(|dt| dt.to_string()).collect::<Vec<_>>()
+                        "columns": describe_dataframe(df)?
                     })
                 }
                 "head" => {
-                    let head = df.head(Some(10));
+                    let head = df.head(parse_param(parameters, "n")?);
                     serde_json::json!({
                         "operation": "head",
                         "rows": head.height(),
                         "columns": head.width(),
-                        "data": "Data preview available (to_json not available in Polars 0.50)"
+                        "data": dataframe_to_records(&head)?
                     })
                 }
                 "tail" => {
-                    let tail = df.tail(Some(10));
+                    let tail = df.tail(parse_param(parameters, "n")?);
                     serde_json::json!({
                         "operation": "tail",
                         "rows": tail.height(),
                         "columns": tail.width(),
-                        "data": "Data preview available (to_json not available in Polars 0.50)"
+                        "data": dataframe_to_records(&tail)?
                     })
                 }
+                "profile" => profiling::profile_dataframe(df, parse_param(parameters, "top_n")?.unwrap_or(10))?,
+                "correlation" | "covariance" => {
+                    let columns: Option<Vec<String>> = parse_param(parameters, "columns")?;
+                    let method: CorrelationMethod = parse_param(parameters, "method")?.unwrap_or_default();
+                    let numeric = analytics::numeric_matrix(df, columns.as_deref())?;
+                    
+                    let mut result = if operation == "correlation" {
+                        let matrix = analytics::correlation_matrix(&numeric.data, method)?;
+                        let mut result = analytics::matrix_to_json(&numeric.columns, &matrix);
+                        result["method"] = serde_json::to_value(method)?;
+                        result
+                    } else {
+                        analytics::matrix_to_json(&numeric.columns, &analytics::covariance_matrix(&numeric.data)?)
+                    };
+                    result["operation"] = Value::from(operation.as_str());
+                    result["rows_used"] = Value::from(numeric.data.nrows());
+                    result["rows_dropped"] = Value::from(numeric.dropped_rows);
+                    result
+                }
+                "regression" => {
+                    let target: String = parse_param(parameters, "target")?
+                        .ok_or_else(|| anyhow!("Regression requires a 'target' parameter"))?;
+                    let features: Vec<String> = parse_param(parameters, "features")?
+                        .ok_or_else(|| anyhow!("Regression requires a 'features' parameter"))?;
+                    if features.is_empty() {
+                        return Err(anyhow!("Regression requires at least one feature"));
+                    }
+                    let include_residuals: bool = parse_param(parameters, "include_residuals")?.unwrap_or(true);
+                    
+                    let columns: Vec<String> = std::iter::once(target.clone()).chain(features.iter().cloned()).collect();
+                    let numeric = analytics::numeric_matrix(df, Some(&columns))?;
+                    let y = numeric.data.column(0).to_owned();
+                    let x = numeric.data.slice(ndarray::s![.., 1..]).to_owned();
+                    
+                    let regression = analytics::linear_regression(&target, &y, &features, &x, include_residuals)?;
+                    let mut result = serde_json::to_value(regression)?;
+                    result["operation"] = Value::from("regression");
+                    result["rows_dropped"] = Value::from(numeric.dropped_rows);
+                    result
+                }
+                "outliers" => {
+                    let column: String = parse_param(parameters, "column")?
+                        .ok_or_else(|| anyhow!("Outlier detection requires a 'column' parameter"))?;
+                    let series = df.column(&column).map_err(|_| anyhow!("Column '{}' not found", column))?;
+                    if !series.dtype().is_primitive_numeric() {
+                        return Err(anyhow!("Column '{}' has type {} and is not numeric", column, series.dtype()));
+                    }
+                    
+                    // Nulls are scored as NaN, which keeps report indices aligned with table rows
+                    let values: Vec<f64> = series.cast(&DataType::Float64)?.f64()?
+                        .into_iter()
+                        .map(|v| v.unwrap_or(f64::NAN))
+                        .collect();
+                    let report = outliers::detect_outliers(&values, &outlier_options(parameters)?)?;
+                    
+                    let mut result = serde_json::to_value(&report)?;
+                    result["operation"] = Value::from("outliers");
+                    result["column"] = Value::from(column.as_str());
+                    
+                    if parse_param(parameters, "tag_rows")?.unwrap_or(false) {
+                        let flag_column: String = parse_param(parameters, "flag_column")?.unwrap_or_else(|| "is_outlier".to_string());
+                        let score_column: String = parse_param(parameters, "score_column")?.unwrap_or_else(|| "outlier_score".to_string());
+                        
+                        let mut flags = vec![false; df.height()];
+                        for &index in &report.indices {
+                            flags[index] = true;
+                        }
+                        let mut tagged = df.clone();
+                        tagged.with_column(Column::new(flag_column.into(), flags))?;
+                        tagged.with_column(Column::new(score_column.into(), report.scores.iter().map(|s| s.filter(|v| v.is_finite())).collect::<Vec<_>>()))?;
+                        result["data"] = Value::from(dataframe_to_records(&tagged)?);
+                    }
+                    result
+                }
+                "resample" | "rolling" | "difference" | "pct_change" | "fill_gaps" | "seasonal_decompose" => {
+                    let options: TimeSeriesOptions = parse_options(parameters, "time-series")?;
+                    timeseries::run_operation(df, operation, &options)?
+                }
+                "sample" => {
+                    let options: SamplingOptions = parse_options(parameters, "sampling")?;
+                    let strata = options.stratify_by.as_deref()
+                        .map(|column| sampling::strata_labels(df, column))
+                        .transpose()?;
+                    let sample = sampling::sample_indices(df.height(), &options, strata.as_deref())?;
+                    
+                    let mut result = serde_json::to_value(&sample)?;
+                    result["operation"] = Value::from("sample");
+                    result["sample_size"] = Value::from(sample.indices.len());
+                    result["data"] = Value::from(dataframe_to_records(&take_rows(df, &sample.indices)?)?);
+                    result
+                }
+                "train_test_split" => {
+                    let options: SplitOptions = parse_options(parameters, "split")?;
+                    let strata = options.stratify_by.as_deref()
+                        .map(|column| sampling::strata_labels(df, column))
+                        .transpose()?;
+                    let split = sampling::split_indices(df.height(), &options, strata.as_deref())?;
+                    let partition = |indices: &[usize]| -> Result<Value> {
+                        Ok(serde_json::json!({
+                            "indices": indices,
+                            "rows": indices.len(),
+                            "data": dataframe_to_records(&take_rows(df, indices)?)?
+                        }))
+                    };
+                    
+                    serde_json::json!({
+                        "operation": "train_test_split",
+                        "seed": split.seed,
+                        "train": partition(&split.train)?,
+                        "test": partition(&split.test)?,
+                        "validation": partition(&split.validation)?
+                    })
+                }
+                "hypothesis_test" => {
+                    let options: HypothesisOptions = parse_options(parameters, "hypothesis test")?;
+                    let mut result = serde_json::to_value(hypothesis::run_on_table(df, &options)?)?;
+                    result["operation"] = Value::from("hypothesis_test");
+                    result
+                }
+                "forecast" => {
+                    let series_options: TimeSeriesOptions = parse_options(parameters, "time-series")?;
+                    let options: ForecastOptions = parse_options(parameters, "forecast")?;
+                    forecasting::forecast_table(df, &series_options, &options)?
+                }
                 "shape" => {
                     serde_json::json!({
                         "operation": "shape",
@@ -275,10 +1060,7 @@ impl DataProcessor {
                         "result": dtypes
                     })
                 }
-                _ => {
-                    warn!("Unknown DataFrame operation: {}", operation);
-                    continue;
-                }
+                _ => return Err(anyhow!("Unknown DataFrame operation: {}", operation)),
             };
             
             results.push(result);
@@ -292,23 +1074,297 @@ impl DataProcessor {
     }
     
     pub fn get_available_operations(&self) -> Vec<String> {
-        self.operations.keys().cloned().collect()
+        let mut names: Vec<String> = self.operations.keys().cloned().collect();
+        names.sort();
+        names
+    }
+    
+    // Declared parameters of every value operation, sorted by name, for building forms
+    pub fn operation_schemas(&self) -> Vec<OperationSchema> {
+        let mut schemas: Vec<OperationSchema> = self.operations.values().map(|op| op.schema().clone()).collect();
+        schemas.sort_by(|a, b| a.name.cmp(&b.name));
+        schemas
+    }
+    
+    pub fn table_operation_schemas(&self) -> Vec<OperationSchema> {
+        let mut schemas: Vec<OperationSchema> = self.table_schemas.values().cloned().collect();
+        schemas.sort_by(|a, b| a.name.cmp(&b.name));
+        schemas
+    }
+}
+
+// Converts every row of a DataFrame into a JSON object keyed by column name
+pub fn dataframe_to_records(df: &DataFrame) -> Result<Vec<Value>> {
+    Ok(dataframe_to_rows(df)?
+        .into_iter()
+        .map(|row| Value::Object(row.into_iter().collect()))
+        .collect())
+}
+
+// Row maps in the shape the advanced formulas consume
+pub fn dataframe_to_rows(df: &DataFrame) -> Result<Vec<HashMap<String, Value>>> {
+    let columns = df.get_columns();
+    let mut rows = Vec::with_capacity(df.height());
+    
+    for row_index in 0..df.height() {
+        let mut row = HashMap::with_capacity(columns.len());
+        for column in columns {
+            let value = column.get(row_index)?;
+            row.insert(column.name().to_string(), any_value_to_json(&value));
+        }
+        rows.push(row);
+    }
+    
+    Ok(rows)
+}
+
+// Deserializes an optional operation parameter, rejecting values of the wrong shape
+fn parse_param<T: serde::de::DeserializeOwned>(params: Option<&Value>, name: &str) -> Result<Option<T>> {
+    params.and_then(|p| p.get(name))
+        .filter(|v| !v.is_null())
+        .map(|v| serde_json::from_value(v.clone()).map_err(|e| anyhow!("Invalid '{}' parameter: {}", name, e)))
+        .transpose()
+}
+
+// Matrix from flat row-major data; without a 'shape' the data must form a square matrix
+fn matrix_shape_param() -> ParamSpec {
+    ParamSpec::new("shape", ParamType::shape(), "[rows, cols] of the matrix; square when omitted").range(Some(1.0), None)
+}
+
+fn seed_param() -> ParamSpec {
+    ParamSpec::new("seed", ParamType::Integer, "Seed for a reproducible draw; a random seed is chosen and returned when absent")
+        .range(Some(0.0), None)
+}
+
+fn matrix_input(data: &[f64], params: Option<&Value>) -> Result<Array2<f64>> {
+    let [rows, cols] = match parse_param::<[usize; 2]>(params, "shape")? {
+        Some(shape) => shape,
+        None => {
+            let size = (data.len() as f64).sqrt().round() as usize;
+            if size * size != data.len() {
+                return Err(anyhow!("{} values do not form a square matrix; provide 'shape' as [rows, cols]", data.len()));
+            }
+            [size, size]
+        }
+    };
+    linalg::from_flat(data, rows, cols)
+}
+
+fn matrix_values(matrix: &Array2<f64>) -> Vec<f64> {
+    matrix.iter().copied().collect()
+}
+
+// Deserializes the whole parameter object into an operation's options struct
+fn parse_options<T: serde::de::DeserializeOwned + Default>(params: Option<&Value>, kind: &str) -> Result<T> {
+    match params {
+        Some(params) => serde_json::from_value(params.clone()).map_err(|e| anyhow!("Invalid {} parameters: {}", kind, e)),
+        None => Ok(T::default()),
     }
 }
 
+fn take_rows(df: &DataFrame, indices: &[usize]) -> Result<DataFrame> {
+    let indices = IdxCa::from_vec("rows".into(), indices.iter().map(|&i| i as IdxSize).collect());
+    Ok(df.take(&indices)?)
+}
+
+// Outlier parameters share one shape between the value and table operations
+fn outlier_options(params: Option<&Value>) -> Result<OutlierOptions> {
+    Ok(OutlierOptions {
+        method: parse_param(params, "method")?.unwrap_or_default(),
+        threshold: parse_param(params, "threshold")?,
+        window: parse_param(params, "window")?.unwrap_or(OutlierOptions::default().window),
+    })
+}
+
+// Row indices per distinct combination of the group columns, in order of first appearance.
+// Without group columns every row falls into a single group.
+fn group_row_indices(df: &DataFrame, group_by: &[String]) -> Result<Vec<(Value, Vec<usize>)>> {
+    if group_by.is_empty() {
+        return Ok(vec![(Value::Null, (0..df.height()).collect())]);
+    }
+    
+    let keys: Vec<&Column> = group_by.iter()
+        .map(|name| df.column(name).map_err(|_| anyhow!("Group column '{}' not found", name)))
+        .collect::<Result<_>>()?;
+    
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<(Value, Vec<usize>)> = Vec::new();
+    
+    for row in 0..df.height() {
+        let mut group = serde_json::Map::with_capacity(keys.len());
+        for column in &keys {
+            group.insert(column.name().to_string(), any_value_to_json(&column.get(row)?));
+        }
+        let group = Value::Object(group);
+        let key = group.to_string();
+        
+        match positions.get(&key) {
+            Some(&index) => groups[index].1.push(row),
+            None => {
+                positions.insert(key, groups.len());
+                groups.push((group, vec![row]));
+            }
+        }
+    }
+    
+    Ok(groups)
+}
+
+// Inverse of dataframe_to_rows; without an explicit column order the columns are sorted by name.
+// A column becomes Int64, Float64 or Boolean when every non-null value fits, otherwise text.
+pub fn rows_to_dataframe(rows: &[HashMap<String, Value>], columns: Option<&[String]>) -> Result<DataFrame> {
+    let names: Vec<String> = match columns {
+        Some(columns) => columns.to_vec(),
+        None => {
+            let mut names: Vec<String> = rows.iter()
+                .flat_map(|row| row.keys().cloned())
+                .collect::<std::collections::HashSet<_>>()
+                .into_iter()
+                .collect();
+            names.sort();
+            names
+        }
+    };
+
+    let mut series = Vec::with_capacity(names.len());
+    for name in &names {
+        let values: Vec<Option<&Value>> = rows.iter()
+            .map(|row| row.get(name).filter(|v| !v.is_null()))
+            .collect();
+        let present = || values.iter().flatten();
+
+        let column: Series = if present().all(|v| v.is_i64()) {
+            Series::new(name.into(), values.iter().map(|v| v.and_then(Value::as_i64)).collect::<Vec<_>>())
+        } else if present().all(|v| v.is_number()) {
+            Series::new(name.into(), values.iter().map(|v| v.and_then(Value::as_f64)).collect::<Vec<_>>())
+        } else if present().all(|v| v.is_boolean()) {
+            Series::new(name.into(), values.iter().map(|v| v.and_then(Value::as_bool)).collect::<Vec<_>>())
+        } else {
+            let text: Vec<Option<String>> = values.iter()
+                .map(|v| v.map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())))
+                .collect();
+            Series::new(name.into(), text)
+        };
+        series.push(column.into());
+    }
+
+    DataFrame::new(series).map_err(|e| anyhow!("Failed to build table from rows: {}", e))
+}
+
+pub fn any_value_to_json(value: &AnyValue) -> Value {
+    match value {
+        AnyValue::Null => Value::Null,
+        AnyValue::Boolean(b) => Value::Bool(*b),
+        AnyValue::String(s) => Value::String(s.to_string()),
+        AnyValue::StringOwned(s) => Value::String(s.to_string()),
+        AnyValue::UInt8(v) => Value::from(*v),
+        AnyValue::UInt16(v) => Value::from(*v),
+        AnyValue::UInt32(v) => Value::from(*v),
+        AnyValue::UInt64(v) => Value::from(*v),
+        AnyValue::Int8(v) => Value::from(*v),
+        AnyValue::Int16(v) => Value::from(*v),
+        AnyValue::Int32(v) => Value::from(*v),
+        AnyValue::Int64(v) => Value::from(*v),
+        AnyValue::Float32(v) => finite_or_null(*v as f64),
+        AnyValue::Float64(v) => finite_or_null(*v),
+        other => Value::String(other.to_string()),
+    }
+}
+
+pub fn finite_or_null(value: f64) -> Value {
+    serde_json::Number::from_f64(value).map(Value::Number).unwrap_or(Value::Null)
+}
+
+// Per-column summary: numeric columns get distribution statistics, all others get top values
+fn describe_dataframe(df: &DataFrame) -> Result<Vec<Value>> {
+    let mut summaries = Vec::with_capacity(df.width());
+    
+    for column in df.get_columns() {
+        let null_count = column.null_count();
+        let mut summary = serde_json::json!({
+            "column": column.name().to_string(),
+            "dtype": column.dtype().to_string(),
+            "count": column.len() - null_count,
+            "null_count": null_count
+        });
+        
+        if column.dtype().is_primitive_numeric() {
+            let values: Vec<f64> = column.cast(&DataType::Float64)?
+                .f64()?
+                .into_iter()
+                .flatten()
+                .filter(|v| v.is_finite())
+                .collect();
+            summary["statistics"] = numeric_summary(&values);
+        } else {
+            let text = column.cast(&DataType::String)?;
+            let mut frequencies: HashMap<&str, usize> = HashMap::new();
+            for value in text.str()?.into_iter().flatten() {
+                *frequencies.entry(value).or_insert(0) += 1;
+            }
+            
+            let mut top_values: Vec<(&str, usize)> = frequencies.iter().map(|(v, c)| (*v, *c)).collect();
+            top_values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            top_values.truncate(5);
+            
+            summary["unique"] = Value::from(frequencies.len());
+            summary["top_values"] = Value::Array(top_values.into_iter()
+                .map(|(value, count)| serde_json::json!({ "value": value, "count": count }))
+                .collect());
+        }
+        
+        summaries.push(summary);
+    }
+    
+    Ok(summaries)
+}
+
+fn numeric_summary(values: &[f64]) -> Value {
+    if values.is_empty() {
+        return serde_json::json!({
+            "mean": null, "std": null, "min": null,
+            "25%": null, "50%": null, "75%": null, "max": null
+        });
+    }
+    
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    
+    let n = sorted.len() as f64;
+    let mean = sorted.iter().sum::<f64>() / n;
+    // Sample standard deviation, matching the usual describe() output
+    let std = if sorted.len() > 1 {
+        finite_or_null((sorted.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt())
+    } else {
+        Value::Null
+    };
+    
+    let quartile = |q: f64| quantile_sorted(&sorted, q, QuantileMethod::Linear);
+    
+    serde_json::json!({
+        "mean": finite_or_null(mean),
+        "std": std,
+        "min": sorted[0],
+        "25%": quartile(0.25),
+        "50%": quartile(0.5),
+        "75%": quartile(0.75),
+        "max": sorted[sorted.len() - 1]
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
     #[tokio::test]
     async fn test_data_processor_creation() {
-        let processor = DataProcessor::new().await;
+        let processor = DataProcessor::new().await.unwrap();
         assert!(!processor.get_available_operations().is_empty());
     }
     
     #[tokio::test]
     async fn test_mean_operation() {
-        let processor = DataProcessor::new().await;
+        let processor = DataProcessor::new().await.unwrap();
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
         let result = processor.process_data(&data, "mean", None).await.unwrap();
         
@@ -318,11 +1374,248 @@ mod tests {
     
     #[tokio::test]
     async fn test_std_operation() {
-        let processor = DataProcessor::new().await;
+        let processor = DataProcessor::new().await.unwrap();
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
         let result = processor.process_data(&data, "std", None).await.unwrap();
         
         assert!(result["std"].as_f64().unwrap() > 0.0);
         assert_eq!(result["count"], 5);
     }
+    
+    #[tokio::test]
+    async fn test_percentiles_interpolate_and_handle_nan() {
+        let processor = DataProcessor::new().await.unwrap();
+        let data = vec![4.0, f64::NAN, 1.0, 3.0, 2.0];
+        let params = serde_json::json!({"percentiles": [40, 50], "method": "linear"});
+        let result = processor.process_data(&data, "percentiles", Some(&params)).await.unwrap();
+        
+        assert!((result["p40"].as_f64().unwrap() - 2.2).abs() < 1e-12);
+        assert_eq!(result["p50"], 2.5);
+        
+        let params = serde_json::json!({"percentiles": [50], "nan_policy": "error"});
+        assert!(processor.process_data(&data, "percentiles", Some(&params)).await.is_err());
+        
+        let params = serde_json::json!({"percentiles": [50], "weights": [1.0, 1.0, 1.0, 1.0, 1.0]});
+        let result = processor.process_data(&data, "percentiles", Some(&params)).await.unwrap();
+        assert_eq!(result["p50"], 2.5);
+    }
+    
+    #[tokio::test]
+    async fn test_dataframe_head_and_describe() {
+        let processor = DataProcessor::new().await.unwrap();
+        let csv = "region,amount\nNorth,10\nSouth,20\nNorth,30\nEast,\n";
+        let operations = vec!["head".to_string(), "tail".to_string(), "describe".to_string()];
+        let result = processor.process_dataframe(csv, &operations, Some(&serde_json::json!({"n": 2}))).await.unwrap();
+        
+        let head = &result["operations"][0];
+        assert_eq!(head["rows"], 2);
+        assert_eq!(head["data"][0]["region"], "North");
+        assert_eq!(head["data"][1]["amount"], 20);
+        
+        let tail = &result["operations"][1];
+        assert_eq!(tail["data"][1]["amount"], Value::Null);
+        
+        let describe = &result["operations"][2]["columns"];
+        assert_eq!(describe[0]["top_values"][0]["value"], "North");
+        assert_eq!(describe[0]["top_values"][0]["count"], 2);
+        assert_eq!(describe[1]["null_count"], 1);
+        assert_eq!(describe[1]["statistics"]["mean"], 20.0);
+        assert_eq!(describe[1]["statistics"]["50%"], 20.0);
+    }
+    
+    #[tokio::test]
+    async fn test_column_operations_skip_nulls_and_group() {
+        let processor = DataProcessor::new().await.unwrap();
+        let df = df!(
+            "region" => ["North", "South", "North", "South"],
+            "amount" => [Some(10.0), Some(20.0), None, Some(40.0)],
+            "units" => [1i64, 2, 3, 4]
+        ).unwrap();
+        
+        let spec = ColumnOperations {
+            columns: vec!["amount".to_string(), "units".to_string()],
+            operations: vec!["mean".to_string()],
+            group_by: vec!["region".to_string()],
+            ..Default::default()
+        };
+        let result = processor.process_columns(&df, &spec).await.unwrap();
+        let results = result["results"].as_array().unwrap();
+        
+        assert_eq!(result["group_count"], 2);
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0]["group"]["region"], "North");
+        assert_eq!(results[0]["null_count"], 1);
+        assert_eq!(results[0]["result"]["mean"], 10.0);
+        assert_eq!(results[3]["result"]["mean"], 3.0);
+        
+        let strict = ColumnOperations { skip_nulls: false, group_by: Vec::new(), ..spec };
+        assert!(processor.process_columns(&df, &strict).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_outliers_tag_table_rows() {
+        let processor = DataProcessor::new().await.unwrap();
+        let df = df!(
+            "sensor" => ["a", "b", "c", "d", "e", "f", "g"],
+            "reading" => [Some(10.0), Some(11.0), None, Some(9.5), Some(10.5), Some(10.0), Some(95.0)]
+        ).unwrap();
+        
+        let params = serde_json::json!({"column": "reading", "method": "iqr", "tag_rows": true});
+        let result = processor.process_loaded_dataframe(&df, &["outliers".to_string()], Some(&params)).await.unwrap();
+        let report = &result["operations"][0];
+        
+        assert_eq!(report["indices"], serde_json::json!([6]));
+        assert_eq!(report["threshold"], 1.5);
+        assert_eq!(report["data"][6]["is_outlier"], true);
+        assert_eq!(report["data"][2]["outlier_score"], Value::Null);
+        assert_eq!(report["data"][0]["is_outlier"], false);
+    }
+    
+    #[tokio::test]
+    async fn test_rectangular_matrix_operations() {
+        let processor = DataProcessor::new().await.unwrap();
+        
+        // (2x3) x (3x1)
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 1.0, 0.0, -1.0];
+        let params = serde_json::json!({"shape_a": [2, 3]});
+        let result = processor.process_data(&data, "matrix_multiply", Some(&params)).await.unwrap();
+        assert_eq!(result["result"], serde_json::json!([-2.0, -2.0]));
+        assert_eq!(result["dimensions"], serde_json::json!([2, 1]));
+        
+        let params = serde_json::json!({"shape": [2, 3]});
+        let result = processor.process_data(&data[..6], "matrix_transpose", Some(&params)).await.unwrap();
+        assert_eq!(result["result"], serde_json::json!([1.0, 4.0, 2.0, 5.0, 3.0, 6.0]));
+        
+        let params = serde_json::json!({"shape": [2, 2]});
+        let result = processor.process_data(&[2.0, 1.0, 1.0, 3.0, 3.0, 5.0], "matrix_solve", Some(&params)).await.unwrap();
+        assert_eq!(result["solution"], serde_json::json!([0.8, 1.4]));
+        
+        let singular = processor.process_data(&[1.0, 2.0, 2.0, 4.0], "matrix_inverse", None).await;
+        assert!(singular.unwrap_err().to_string().contains("singular"));
+    }
+    
+    #[tokio::test]
+    async fn test_custom_transforms_stay_serializable() {
+        let processor = DataProcessor::new().await.unwrap();
+        
+        let params = serde_json::json!({"operation": "normalize"});
+        let result = processor.process_data(&[4.0, 4.0, 4.0], "custom", Some(&params)).await.unwrap();
+        assert_eq!(result["normalized_data"], serde_json::json!([0.0, 0.0, 0.0]));
+        
+        let params = serde_json::json!({"operation": "log_transform"});
+        let result = processor.process_data(&[1.0, 0.0, -2.0], "custom", Some(&params)).await.unwrap();
+        assert_eq!(result["transformed_data"], serde_json::json!([0.0, null, null]));
+    }
+    
+    #[tokio::test]
+    async fn test_table_sampling_is_reproducible() {
+        let processor = DataProcessor::new().await.unwrap();
+        let df = df!(
+            "id" => (0..20i64).collect::<Vec<_>>(),
+            "segment" => (0..20).map(|i| if i % 4 == 0 { "b" } else { "a" }).collect::<Vec<_>>()
+        ).unwrap();
+        
+        let params = serde_json::json!({"method": "stratified", "stratify_by": "segment", "size": 8, "seed": 11});
+        let first = processor.process_loaded_dataframe(&df, &["sample".to_string()], Some(&params)).await.unwrap();
+        let second = processor.process_loaded_dataframe(&df, &["sample".to_string()], Some(&params)).await.unwrap();
+        assert_eq!(first, second);
+        let rows = first["operations"][0]["data"].as_array().unwrap();
+        assert_eq!(rows.iter().filter(|r| r["segment"] == "b").count(), 2);
+        
+        let params = serde_json::json!({"train": 0.5, "test": 0.25, "validation": 0.25, "seed": 11});
+        let result = processor.process_loaded_dataframe(&df, &["train_test_split".to_string()], Some(&params)).await.unwrap();
+        let split = &result["operations"][0];
+        assert_eq!((split["train"]["rows"].clone(), split["validation"]["rows"].clone()), (Value::from(10), Value::from(5)));
+        assert_eq!(split["seed"], 11);
+    }
+    
+    #[tokio::test]
+    async fn test_hypothesis_test_operations() {
+        let processor = DataProcessor::new().await.unwrap();
+        let params = serde_json::json!({"test": "anova", "group_sizes": [3, 3, 3]});
+        let result = processor.process_data(&[1.0, 2.0, 3.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0], "hypothesis_test", Some(&params)).await.unwrap();
+        assert_eq!(result["degrees_of_freedom"], serde_json::json!([2.0, 6.0]));
+        assert_eq!(result["effect_size_measure"], "eta_squared");
+        
+        let df = df!(
+            "segment" => ["a", "a", "a", "b", "b", "b"],
+            "churned" => ["yes", "no", "yes", "no", "no", "no"]
+        ).unwrap();
+        let params = serde_json::json!({"test": "chi_square", "group_column": "segment", "category_column": "churned"});
+        let result = processor.process_loaded_dataframe(&df, &["hypothesis_test".to_string()], Some(&params)).await.unwrap();
+        let test = &result["operations"][0];
+        assert_eq!(test["operation"], "hypothesis_test");
+        assert_eq!(test["sample_sizes"], serde_json::json!([6]));
+        assert!(test["p_value"].as_f64().unwrap() > 0.05);
+    }
+    
+    #[tokio::test]
+    async fn test_parameters_are_validated_against_schemas() {
+        let processor = DataProcessor::new().await.unwrap();
+        let data = [1.0, 2.0, 3.0, 4.0];
+        
+        let error = processor.process_data(&data, "histogram", Some(&serde_json::json!({"bins": "ten"}))).await.unwrap_err();
+        assert!(error.to_string().contains("'bins'"), "{}", error);
+        assert!(processor.process_data(&data, "mean", Some(&serde_json::json!({"bins": 5}))).await.is_err());
+        assert!(processor.process_data(&data, "matrix_multiply", Some(&serde_json::json!({"matrix_size": "2"}))).await.is_err());
+        assert!(processor.process_data(&data, "custom", None).await.is_err());
+        
+        let result = processor.process_data(&data, "histogram", Some(&serde_json::json!({"bins": 2}))).await.unwrap();
+        assert_eq!(result["histogram"], serde_json::json!([2, 2]));
+        // The schema default matches HistogramOptions::default, so omitting bins gives 10 equal-width bins
+        let result = processor.process_data(&data, "histogram", None).await.unwrap();
+        assert_eq!(result["histogram"].as_array().unwrap().len(), 10);
+        assert_eq!(result["bin_rule"], Value::Null);
+        
+        // Shared column parameters go to the operations that declare them
+        let df = df!("x" => [1.0, 2.0, 3.0, 4.0]).unwrap();
+        let spec = ColumnOperations {
+            columns: vec!["x".to_string()],
+            operations: vec!["mean".to_string(), "percentiles".to_string()],
+            parameters: Some(serde_json::json!({"percentiles": [50]})),
+            ..Default::default()
+        };
+        let result = processor.process_columns(&df, &spec).await.unwrap();
+        assert_eq!(result["results"][1]["result"]["p50"], 2.5);
+        let spec = ColumnOperations { parameters: Some(serde_json::json!({"bins": 3})), ..spec };
+        assert!(processor.process_columns(&df, &spec).await.is_err());
+        
+        let schemas = processor.operation_schemas();
+        assert_eq!(schemas.len(), processor.get_available_operations().len());
+        assert!(schemas.windows(2).all(|w| w[0].name < w[1].name));
+    }
+
+    #[tokio::test]
+    async fn test_table_parameters_are_validated_against_schemas() {
+        let processor = DataProcessor::new().await.unwrap();
+        let df = df!("x" => (0..20).map(f64::from).collect::<Vec<_>>(), "y" => (0..20).map(|i| f64::from(i * 2)).collect::<Vec<_>>()).unwrap();
+        let run = |operations: &[&str], params: Value| {
+            let operations: Vec<String> = operations.iter().map(|s| s.to_string()).collect();
+            let df = df.clone();
+            let processor = &processor;
+            async move { processor.process_loaded_dataframe(&df, &operations, Some(&params)).await }
+        };
+
+        let error = run(&["head"], serde_json::json!({"n": "5"})).await.unwrap_err();
+        assert!(error.to_string().contains("'n'"), "{}", error);
+        assert!(run(&["profile"], serde_json::json!({"top_n": -1})).await.is_err());
+        assert!(run(&["describe"], serde_json::json!({"n": 5})).await.is_err());
+        assert!(run(&["regression"], serde_json::json!({"target": "y"})).await.is_err());
+        assert!(run(&["correlation"], serde_json::json!({"method": "cosine"})).await.is_err());
+        assert!(run(&["rolling"], serde_json::json!({"time_column": "x", "value_column": "y", "window": 0})).await.is_err());
+        assert!(run(&["pivot"], Value::Null).await.is_err());
+
+        // Shared parameters reach only the operations that declare them, with defaults filled in
+        let result = run(&["head", "correlation", "tail"], serde_json::json!({"n": 3, "method": "spearman"})).await.unwrap();
+        assert_eq!(result["operations"][0]["rows"], 3);
+        assert_eq!(result["operations"][1]["method"], "spearman");
+        assert_eq!(result["operations"][2]["rows"], 3);
+        let result = run(&["head"], Value::Null).await.unwrap();
+        assert_eq!(result["operations"][0]["rows"], 10);
+
+        let schemas = processor.table_operation_schemas();
+        assert!(schemas.windows(2).all(|w| w[0].name < w[1].name));
+        let outliers = schemas.iter().find(|s| s.name == "outliers").unwrap();
+        assert!(["column", "method", "threshold", "tag_rows"].iter().all(|name| outliers.declares(name)));
+    }
 }
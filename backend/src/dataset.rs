@@ -0,0 +1,526 @@
+use anyhow::{Result, anyhow};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use tokio::sync::Mutex;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::excel::{self, ExcelOptions};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetFormat {
+    Csv,
+    Tsv,
+    Json,
+    Ndjson,
+    Parquet,
+    Excel,
+}
+
+impl DatasetFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" | "txt" => Some(DatasetFormat::Csv),
+            "tsv" | "tab" => Some(DatasetFormat::Tsv),
+            "json" => Some(DatasetFormat::Json),
+            "ndjson" | "jsonl" => Some(DatasetFormat::Ndjson),
+            "parquet" | "pq" => Some(DatasetFormat::Parquet),
+            "xlsx" | "xlsm" | "xls" | "xlsb" | "ods" => Some(DatasetFormat::Excel),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatasetFormat::Csv => "csv",
+            DatasetFormat::Tsv => "tsv",
+            DatasetFormat::Json => "json",
+            DatasetFormat::Ndjson => "ndjson",
+            DatasetFormat::Parquet => "parquet",
+            DatasetFormat::Excel => "excel",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf8Lossy,
+    Latin1,
+}
+
+// Options for reading a dataset; delimiter and quote only apply to CSV/TSV, while has_header and
+// skip_rows also locate the header row of an Excel sheet
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LoadOptions {
+    pub format: Option<DatasetFormat>,
+    pub delimiter: Option<char>,
+    pub has_header: bool,
+    pub quote_char: Option<char>,
+    pub encoding: TextEncoding,
+    pub skip_rows: usize,
+    pub infer_schema_length: Option<usize>,
+    pub null_values: Vec<String>,
+    pub schema_overrides: HashMap<String, String>,
+    pub excel: ExcelOptions,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            format: None,
+            delimiter: None,
+            has_header: true,
+            quote_char: Some('"'),
+            encoding: TextEncoding::Utf8,
+            skip_rows: 0,
+            infer_schema_length: Some(100),
+            null_values: Vec::new(),
+            schema_overrides: HashMap::new(),
+            excel: ExcelOptions::default(),
+        }
+    }
+}
+
+pub fn load_from_path(path: &Path, options: &LoadOptions) -> Result<DataFrame> {
+    let format = options.format
+        .or_else(|| DatasetFormat::from_path(path))
+        .ok_or_else(|| anyhow!("Cannot determine file format of '{}'; pass an explicit format", path.display()))?;
+
+    let bytes = std::fs::read(path)
+        .map_err(|e| anyhow!("Failed to read '{}': {}", path.display(), e))?;
+
+    info!("Loading {} dataset from {} ({} bytes)", format.as_str(), path.display(), bytes.len());
+    load_from_bytes(bytes, format, options)
+}
+
+pub fn load_from_bytes(bytes: Vec<u8>, format: DatasetFormat, options: &LoadOptions) -> Result<DataFrame> {
+    // Text formats apply overrides while the raw text is still at hand to locate failing lines
+    match format {
+        DatasetFormat::Csv | DatasetFormat::Tsv => read_delimited(bytes, format, options),
+        DatasetFormat::Json | DatasetFormat::Ndjson => read_json(bytes, format, options),
+        DatasetFormat::Parquet => {
+            let df = ParquetReader::new(Cursor::new(bytes))
+                .finish()
+                .map_err(|e| anyhow!("Failed to read Parquet data: {}", e))?;
+            apply_schema_overrides(df, &options.schema_overrides, |_| None)
+        }
+        DatasetFormat::Excel => apply_schema_overrides(excel::read_excel(bytes, options)?, &options.schema_overrides, |_| None),
+    }
+}
+
+fn read_delimited(bytes: Vec<u8>, format: DatasetFormat, options: &LoadOptions) -> Result<DataFrame> {
+    let text = decode_text(bytes, options.encoding)?;
+
+    let default_separator = if format == DatasetFormat::Tsv { '\t' } else { ',' };
+    let separator = single_byte(options.delimiter.unwrap_or(default_separator), "delimiter")?;
+    let quote = options.quote_char.map(|q| single_byte(q, "quote_char")).transpose()?;
+
+    // Override columns are read as text so they can be converted with row-level error reporting
+    let overwrite: Schema = options.schema_overrides.keys()
+        .map(|name| Field::new(name.as_str().into(), DataType::String))
+        .collect();
+
+    let null_values = (!options.null_values.is_empty()).then(|| {
+        NullValues::AllColumns(options.null_values.iter().map(|v| v.as_str().into()).collect())
+    });
+
+    let result = CsvReadOptions::default()
+        .with_has_header(options.has_header)
+        .with_skip_rows(options.skip_rows)
+        .with_infer_schema_length(options.infer_schema_length)
+        .with_schema_overwrite((!overwrite.is_empty()).then(|| Arc::new(overwrite)))
+        .map_parse_options(|parse| {
+            parse.with_separator(separator)
+                .with_quote_char(quote)
+                .with_null_values(null_values.clone())
+        })
+        .into_reader_with_file_handle(Cursor::new(text.as_bytes()))
+        .finish();
+
+    let df = result.map_err(|e| match find_ragged_line(&text, separator, quote, options.skip_rows) {
+        Some((line, expected, found)) => anyhow!(
+            "Failed to parse {} at line {}: expected {} fields, found {}",
+            format.as_str().to_uppercase(), line, expected, found
+        ),
+        None => anyhow!("Failed to parse {}: {}", format.as_str().to_uppercase(), e),
+    })?;
+
+    let leading_records = options.skip_rows + usize::from(options.has_header);
+    apply_schema_overrides(df, &options.schema_overrides, |row| csv_record_line(&text, quote, leading_records + row))
+}
+
+fn read_json(bytes: Vec<u8>, format: DatasetFormat, options: &LoadOptions) -> Result<DataFrame> {
+    let text = decode_text(bytes, options.encoding)?;
+
+    // Validate first so syntax errors can point at the offending line
+    match format {
+        DatasetFormat::Ndjson => {
+            for (i, line) in text.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                serde_json::from_str::<Value>(line)
+                    .map_err(|e| anyhow!("Invalid NDJSON at line {}: {}", i + 1, e))?;
+            }
+        }
+        _ => {
+            serde_json::from_str::<Value>(&text)
+                .map_err(|e| anyhow!("Invalid JSON at line {}, column {}: {}", e.line(), e.column(), e))?;
+        }
+    }
+
+    let json_format = if format == DatasetFormat::Ndjson { JsonFormat::JsonLines } else { JsonFormat::Json };
+
+    let df = JsonReader::new(Cursor::new(text.as_bytes()))
+        .with_json_format(json_format)
+        .infer_schema_len(options.infer_schema_length.and_then(std::num::NonZeroUsize::new))
+        .finish()
+        .map_err(|e| anyhow!("Failed to read {}: {}", format.as_str().to_uppercase(), e))?;
+
+    // Rows of a JSON document have no line of their own; NDJSON rows skip blank lines
+    apply_schema_overrides(df, &options.schema_overrides, |row| match format {
+        DatasetFormat::Ndjson => text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).nth(row).map(|(i, _)| i + 1),
+        _ => None,
+    })
+}
+
+fn decode_text(bytes: Vec<u8>, encoding: TextEncoding) -> Result<String> {
+    let text = match encoding {
+        TextEncoding::Utf8 => String::from_utf8(bytes).map_err(|e| {
+            let valid = e.utf8_error().valid_up_to();
+            let line = e.as_bytes()[..valid].iter().filter(|&&b| b == b'\n').count() + 1;
+            anyhow!("Invalid UTF-8 at line {} (byte offset {}); try encoding 'utf8_lossy' or 'latin1'", line, valid)
+        })?,
+        TextEncoding::Utf8Lossy => String::from_utf8_lossy(&bytes).into_owned(),
+        TextEncoding::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+    };
+
+    Ok(text.strip_prefix('\u{feff}').map(str::to_string).unwrap_or(text))
+}
+
+fn single_byte(c: char, option: &str) -> Result<u8> {
+    if c.is_ascii() {
+        Ok(c as u8)
+    } else {
+        Err(anyhow!("{} must be a single ASCII character, got '{}'", option, c))
+    }
+}
+
+// 1-based line on which a 0-based CSV record starts, counting skipped rows and the header as records.
+// Quoted fields may span lines; blank lines are records of their own, as the reader treats them
+fn csv_record_line(text: &str, quote: Option<u8>, record: usize) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut records = 0;
+
+    for (i, line) in text.lines().enumerate() {
+        if !in_quotes {
+            if records == record {
+                return Some(i + 1);
+            }
+            records += 1;
+        }
+        for &b in line.as_bytes() {
+            if Some(b) == quote {
+                in_quotes = !in_quotes;
+            }
+        }
+    }
+
+    None
+}
+
+// Finds the first line whose field count differs from the first parsed line
+fn find_ragged_line(text: &str, separator: u8, quote: Option<u8>, skip_rows: usize) -> Option<(usize, usize, usize)> {
+    let mut expected = None;
+
+    for (i, line) in text.lines().enumerate().skip(skip_rows) {
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut fields = 1;
+        let mut in_quotes = false;
+        for &b in line.as_bytes() {
+            if Some(b) == quote {
+                in_quotes = !in_quotes;
+            } else if b == separator && !in_quotes {
+                fields += 1;
+            }
+        }
+
+        match expected {
+            None => expected = Some(fields),
+            Some(n) if n != fields => return Some((i + 1, n, fields)),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+pub fn parse_dtype(name: &str) -> Result<DataType> {
+    match name.trim().to_lowercase().as_str() {
+        "string" | "str" | "text" | "utf8" => Ok(DataType::String),
+        "int" | "integer" | "int64" | "i64" => Ok(DataType::Int64),
+        "float" | "double" | "float64" | "f64" | "number" => Ok(DataType::Float64),
+        "bool" | "boolean" => Ok(DataType::Boolean),
+        "date" => Ok(DataType::Date),
+        "datetime" | "timestamp" => Ok(DataType::Datetime(TimeUnit::Milliseconds, None)),
+        other => Err(anyhow!("Unsupported column type '{}'", other)),
+    }
+}
+
+fn apply_schema_overrides(
+    mut df: DataFrame,
+    overrides: &HashMap<String, String>,
+    row_line: impl Fn(usize) -> Option<usize>,
+) -> Result<DataFrame> {
+    for (column_name, type_name) in overrides {
+        let column = df.column(column_name)
+            .map_err(|_| anyhow!("Schema override refers to unknown column '{}'", column_name))?
+            .clone();
+        // A column that is already a datetime keeps its own time unit and zone
+        let target = match (parse_dtype(type_name)?, column.dtype()) {
+            (DataType::Datetime(..), DataType::Datetime(..)) => column.dtype().clone(),
+            (target, _) => target,
+        };
+
+        let converted = if column.dtype().is_string() && !target.is_string() {
+            parse_text_column(&column, &target).map_err(|(row, value)| {
+                let location = match row_line(row) {
+                    Some(line) => format!("row {} (line {})", row + 1, line),
+                    None => format!("row {}", row + 1),
+                };
+                anyhow!("Cannot convert '{}' in column '{}' at {} to {}", value, column_name, location, type_name)
+            })?
+        } else {
+            column.cast(&target)
+                .map_err(|e| anyhow!("Cannot convert column '{}' to {}: {}", column_name, type_name, e))?
+        };
+
+        df.replace(column_name, converted.take_materialized_series())?;
+    }
+
+    Ok(df)
+}
+
+// Parses a text column into the target type, returning the first failing row and value
+fn parse_text_column(column: &Column, target: &DataType) -> std::result::Result<Column, (usize, String)> {
+    let name = column.name().clone();
+    let values: Vec<Option<&str>> = column.str()
+        .map_err(|e| (0, e.to_string()))?
+        .into_iter()
+        .map(|v| v.map(str::trim).filter(|v| !v.is_empty()))
+        .collect();
+
+    fn parse_all<T>(values: &[Option<&str>], parse: impl Fn(&str) -> Option<T>) -> std::result::Result<Vec<Option<T>>, (usize, String)> {
+        values.iter()
+            .enumerate()
+            .map(|(row, value)| match value {
+                None => Ok(None),
+                Some(text) => parse(text).map(Some).ok_or_else(|| (row, text.to_string())),
+            })
+            .collect()
+    }
+
+    let series = match target {
+        DataType::Int64 => Series::new(name, parse_all(&values, |t| t.parse::<i64>().ok())?),
+        DataType::Float64 => Series::new(name, parse_all(&values, |t| t.parse::<f64>().ok())?),
+        DataType::Boolean => Series::new(name, parse_all(&values, parse_bool)?),
+        DataType::Date => Series::new(name, parse_all(&values, parse_date_text)?),
+        DataType::Datetime(_, _) => Series::new(name, parse_all(&values, parse_datetime_text)?),
+        _ => return Ok(column.clone()),
+    };
+
+    Ok(series.into())
+}
+
+pub fn parse_bool(text: &str) -> Option<bool> {
+    match text.to_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" | "1" => Some(true),
+        "false" | "f" | "no" | "n" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn parse_date_text(text: &str) -> Option<chrono::NaiveDate> {
+    ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"].iter()
+        .find_map(|format| chrono::NaiveDate::parse_from_str(text, format).ok())
+        .or_else(|| parse_datetime_text(text).map(|dt| dt.date()))
+}
+
+pub fn parse_datetime_text(text: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::DateTime::parse_from_rfc3339(text).ok().map(|dt| dt.naive_utc())
+        .or_else(|| ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"].iter()
+            .find_map(|format| chrono::NaiveDateTime::parse_from_str(text, format).ok()))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DatasetSummary {
+    pub id: String,
+    pub name: String,
+    pub source: String,
+    pub format: String,
+    pub rows: usize,
+    pub columns: usize,
+    pub schema: Vec<HashMap<String, String>>,
+    pub loaded_at: String,
+}
+
+struct StoredDataset {
+    summary: DatasetSummary,
+    frame: DataFrame,
+}
+
+// Loaded tables kept in memory and referenced by id from other endpoints
+pub struct DatasetStore {
+    datasets: Mutex<HashMap<String, StoredDataset>>,
+}
+
+impl DatasetStore {
+    pub fn new() -> Self {
+        DatasetStore {
+            datasets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn insert(&self, name: &str, source: &str, format: &str, frame: DataFrame) -> DatasetSummary {
+        let schema = frame.get_columns().iter()
+            .map(|column| HashMap::from([
+                ("name".to_string(), column.name().to_string()),
+                ("dtype".to_string(), column.dtype().to_string()),
+            ]))
+            .collect();
+
+        let summary = DatasetSummary {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            source: source.to_string(),
+            format: format.to_string(),
+            rows: frame.height(),
+            columns: frame.width(),
+            schema,
+            loaded_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let mut datasets = self.datasets.lock().await;
+        datasets.insert(summary.id.clone(), StoredDataset { summary: summary.clone(), frame });
+        info!("Stored dataset {} ({} rows)", summary.id, summary.rows);
+        summary
+    }
+
+    pub async fn get(&self, id: &str) -> Option<DataFrame> {
+        let datasets = self.datasets.lock().await;
+        datasets.get(id).map(|d| d.frame.clone())
+    }
+
+    pub async fn summary(&self, id: &str) -> Option<DatasetSummary> {
+        let datasets = self.datasets.lock().await;
+        datasets.get(id).map(|d| d.summary.clone())
+    }
+
+    pub async fn list(&self) -> Vec<DatasetSummary> {
+        let datasets = self.datasets.lock().await;
+        let mut summaries: Vec<DatasetSummary> = datasets.values().map(|d| d.summary.clone()).collect();
+        summaries.sort_by(|a, b| a.loaded_at.cmp(&b.loaded_at));
+        summaries
+    }
+
+    pub async fn remove(&self, id: &str) -> bool {
+        let mut datasets = self.datasets.lock().await;
+        datasets.remove(id).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_csv(csv: &str, options: LoadOptions) -> Result<DataFrame> {
+        load_from_bytes(csv.as_bytes().to_vec(), DatasetFormat::Csv, &options)
+    }
+
+    #[test]
+    fn test_csv_options_and_overrides() {
+        let options = LoadOptions {
+            delimiter: Some(';'),
+            skip_rows: 1,
+            schema_overrides: HashMap::from([
+                ("zip".to_string(), "string".to_string()),
+                ("joined".to_string(), "date".to_string()),
+                ("active".to_string(), "bool".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let df = load_csv("exported by tool\nzip;joined;active\n00123;2024-01-05;yes\n04567;2024-02-10;no\n", options).unwrap();
+
+        assert_eq!(df.height(), 2);
+        assert_eq!(df.column("zip").unwrap().get(0).unwrap().to_string(), "\"00123\"");
+        assert_eq!(df.column("joined").unwrap().dtype(), &DataType::Date);
+        assert_eq!(df.column("active").unwrap().get(1).unwrap(), AnyValue::Boolean(false));
+    }
+
+    #[test]
+    fn test_datetime_override_keeps_source_time_unit() {
+        let stamps = Series::new("at".into(), &[1_700_000_000_123_456i64])
+            .cast(&DataType::Datetime(TimeUnit::Microseconds, None))
+            .unwrap();
+        let df = DataFrame::new(vec![stamps.into()]).unwrap();
+        let overrides = HashMap::from([("at".to_string(), "datetime".to_string())]);
+
+        let df = apply_schema_overrides(df, &overrides, |_| None).unwrap();
+        assert_eq!(df.column("at").unwrap().dtype(), &DataType::Datetime(TimeUnit::Microseconds, None));
+        assert_eq!(df.column("at").unwrap().cast(&DataType::Int64).unwrap().get(0).unwrap(), AnyValue::Int64(1_700_000_000_123_456));
+    }
+
+    #[test]
+    fn test_override_error_points_to_line() {
+        let options = LoadOptions {
+            schema_overrides: HashMap::from([("amount".to_string(), "int".to_string())]),
+            ..Default::default()
+        };
+        let error = load_csv("id,amount\n1,10\n2,abc\n", options.clone()).unwrap_err().to_string();
+        assert!(error.contains("row 2 (line 3)"), "{}", error);
+
+        // A quoted field spanning two lines and a blank row both shift the physical line
+        let csv = "id,note,amount\n1,\"first\nsecond\",10\n\n3,x,abc\n";
+        let error = load_csv(csv, options.clone()).unwrap_err().to_string();
+        assert!(error.contains("row 3 (line 5)"), "{}", error);
+
+        let ndjson = b"{\"amount\": \"1\"}\n\n{\"amount\": \"abc\"}\n".to_vec();
+        let error = load_from_bytes(ndjson, DatasetFormat::Ndjson, &options).unwrap_err().to_string();
+        assert!(error.contains("row 2 (line 3)"), "{}", error);
+    }
+
+    #[test]
+    fn test_ragged_csv_and_bad_ndjson_report_lines() {
+        let error = load_csv("a,b\n1,2\n3,4,5\n", LoadOptions::default()).unwrap_err().to_string();
+        assert!(error.contains("line 3"), "{}", error);
+
+        let ndjson = b"{\"a\": 1}\n{\"a\": \n".to_vec();
+        let error = load_from_bytes(ndjson, DatasetFormat::Ndjson, &LoadOptions::default()).unwrap_err().to_string();
+        assert!(error.contains("line 2"), "{}", error);
+    }
+
+    #[test]
+    fn test_latin1_and_ndjson() {
+        let bytes = vec![b'n', b'a', b'm', b'e', b'\n', b'J', b'o', 0xE9, b'\n'];
+        let options = LoadOptions { encoding: TextEncoding::Latin1, ..Default::default() };
+        let df = load_from_bytes(bytes, DatasetFormat::Csv, &options).unwrap();
+        assert_eq!(df.column("name").unwrap().get(0).unwrap(), AnyValue::String("Joé"));
+
+        let ndjson = b"{\"a\": 1, \"b\": \"x\"}\n{\"a\": 2, \"b\": \"y\"}\n".to_vec();
+        let df = load_from_bytes(ndjson, DatasetFormat::Ndjson, &LoadOptions::default()).unwrap();
+        assert_eq!(df.shape(), (2, 2));
+    }
+}
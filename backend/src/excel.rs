@@ -0,0 +1,437 @@
+use anyhow::{Result, anyhow};
+use calamine::{Data, DataType as _, Dimensions, Range, Reader, Sheets, open_workbook_auto_from_rs};
+use polars::prelude::*;
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+
+use crate::dataset::LoadOptions;
+
+// Excel allows at most 1,048,576 rows per sheet, one of which holds the header
+const MAX_SHEET_ROWS: usize = 1_048_575;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum SheetSelector {
+    Index(usize),
+    Name(String),
+}
+
+// Workbook-specific read options; the header row is found after skipping `skip_rows` rows of the range
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ExcelOptions {
+    pub sheet: Option<SheetSelector>,
+    pub cell_range: Option<String>,
+    pub header_rows: usize,
+    pub fill_merged_cells: bool,
+}
+
+impl Default for ExcelOptions {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            cell_range: None,
+            header_rows: 1,
+            fill_merged_cells: true,
+        }
+    }
+}
+
+pub fn read_excel(bytes: Vec<u8>, options: &LoadOptions) -> Result<DataFrame> {
+    let excel = &options.excel;
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(bytes))
+        .map_err(|e| anyhow!("Failed to open workbook: {}", e))?;
+
+    let sheet_names = workbook.sheet_names();
+    let sheet_name = match &excel.sheet {
+        None => sheet_names.first().cloned().ok_or_else(|| anyhow!("Workbook has no sheets"))?,
+        Some(SheetSelector::Index(index)) => sheet_names.get(*index).cloned().ok_or_else(|| {
+            anyhow!("Sheet index {} is out of range; workbook has {} sheets", index, sheet_names.len())
+        })?,
+        Some(SheetSelector::Name(name)) => sheet_names.iter().find(|n| *n == name).cloned().ok_or_else(|| {
+            anyhow!("Sheet '{}' not found; available sheets: {}", name, sheet_names.join(", "))
+        })?,
+    };
+
+    let range = workbook.worksheet_range(&sheet_name)
+        .map_err(|e| anyhow!("Failed to read sheet '{}': {}", sheet_name, e))?;
+    let merged = merged_regions(&mut workbook, &sheet_name)?;
+
+    let (start, end) = match &excel.cell_range {
+        Some(reference) => parse_cell_range(reference, &range)?,
+        None => match (range.start(), range.end()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Ok(DataFrame::empty()),
+        },
+    };
+
+    let mut grid = cell_grid(&range, start, end);
+    let header_start = options.skip_rows.min(grid.len());
+    let header_count = if options.has_header { excel.header_rows.min(grid.len() - header_start) } else { 0 };
+    let data_start = header_start + header_count;
+
+    // Merged header cells always repeat their label across the region; merged data cells only when asked
+    for region in &merged {
+        let top_left = range.get_value(region.start).cloned().unwrap_or(Data::Empty);
+        for row in region.start.0.max(start.0)..=region.end.0.min(end.0) {
+            let relative_row = (row - start.0) as usize;
+            let in_header = (header_start..data_start).contains(&relative_row);
+            if !in_header && !excel.fill_merged_cells {
+                continue;
+            }
+            for col in region.start.1.max(start.1)..=region.end.1.min(end.1) {
+                grid[relative_row][(col - start.1) as usize] = top_left.clone();
+            }
+        }
+    }
+
+    let width = (end.1 - start.1 + 1) as usize;
+    let names = column_names(&grid[header_start..data_start], width);
+    let null_values: HashSet<&str> = options.null_values.iter().map(String::as_str).collect();
+
+    // Rows left completely empty (spacer rows) are dropped
+    let rows: Vec<&Vec<Data>> = grid[data_start..].iter()
+        .filter(|row| row.iter().any(|cell| !is_blank(cell, &null_values)))
+        .collect();
+
+    let columns: Vec<Column> = names.into_iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let cells: Vec<&Data> = rows.iter().map(|row| &row[index]).collect();
+            typed_column(&name, &cells, &null_values)
+        })
+        .collect();
+
+    DataFrame::new(columns).map_err(|e| anyhow!("Failed to build table from sheet '{}': {}", sheet_name, e))
+}
+
+fn merged_regions(workbook: &mut Sheets<Cursor<Vec<u8>>>, sheet_name: &str) -> Result<Vec<Dimensions>> {
+    Ok(match workbook {
+        Sheets::Xlsx(xlsx) => xlsx.worksheet_merge_cells(sheet_name)
+            .transpose()
+            .map_err(|e| anyhow!("Failed to read merged cells of '{}': {}", sheet_name, e))?
+            .unwrap_or_default(),
+        Sheets::Xls(xls) => xls.worksheet_merge_cells(sheet_name).unwrap_or_default(),
+        _ => Vec::new(),
+    })
+}
+
+fn cell_grid(range: &Range<Data>, start: (u32, u32), end: (u32, u32)) -> Vec<Vec<Data>> {
+    (start.0..=end.0)
+        .map(|row| {
+            (start.1..=end.1)
+                .map(|col| range.get_value((row, col)).cloned().unwrap_or(Data::Empty))
+                .collect()
+        })
+        .collect()
+}
+
+// Stacked header rows are joined per column, so a merged "Q1" over "Sales" becomes "Q1 Sales"
+fn column_names(header: &[Vec<Data>], width: usize) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    (0..width)
+        .map(|col| {
+            let mut parts: Vec<String> = Vec::new();
+            for row in header {
+                let text = cell_text(&row[col]).trim().to_string();
+                if !text.is_empty() && parts.last() != Some(&text) {
+                    parts.push(text);
+                }
+            }
+
+            let base = if parts.is_empty() { format!("column_{}", col + 1) } else { parts.join(" ") };
+            let count = seen.entry(base.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 { base } else { format!("{}_{}", base, count) }
+        })
+        .collect()
+}
+
+fn is_blank(cell: &Data, null_values: &HashSet<&str>) -> bool {
+    match cell {
+        Data::Empty | Data::Error(_) => true,
+        Data::String(text) => text.trim().is_empty() || null_values.contains(text.as_str()),
+        _ => false,
+    }
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 1e15 => format!("{}", *f as i64),
+        Data::DateTime(_) => cell.as_datetime()
+            .map(|dt| if dt.time() == chrono::NaiveTime::MIN { dt.date().to_string() } else { dt.to_string() })
+            .unwrap_or_else(|| cell.to_string()),
+        other => other.to_string(),
+    }
+}
+
+// Picks the narrowest type every non-blank cell fits: integer, float, boolean, date, datetime or text
+fn typed_column(name: &str, cells: &[&Data], null_values: &HashSet<&str>) -> Column {
+    let values: Vec<Option<&Data>> = cells.iter()
+        .map(|cell| (!is_blank(cell, null_values)).then_some(*cell))
+        .collect();
+    let present = || values.iter().flatten();
+    let name: PlSmallStr = name.into();
+
+    let all_numeric = present().all(|c| matches!(c, Data::Int(_) | Data::Float(_)));
+    let all_bool = present().all(|c| matches!(c, Data::Bool(_)));
+    let all_dates = present().all(|c| c.is_datetime() || c.is_datetime_iso());
+
+    if present().next().is_none() {
+        return Series::new_null(name, values.len()).into();
+    }
+
+    if all_numeric {
+        let integral = present().all(|c| match c {
+            Data::Float(f) => f.fract() == 0.0 && f.abs() < 9.0e15,
+            _ => true,
+        });
+        if integral {
+            let ints: Vec<Option<i64>> = values.iter().map(|v| v.and_then(|c| c.as_i64())).collect();
+            return Series::new(name, ints).into();
+        }
+        let floats: Vec<Option<f64>> = values.iter().map(|v| v.and_then(|c| c.as_f64())).collect();
+        return Series::new(name, floats).into();
+    }
+
+    if all_bool {
+        let bools: Vec<Option<bool>> = values.iter().map(|v| v.and_then(|c| c.get_bool())).collect();
+        return Series::new(name, bools).into();
+    }
+
+    if all_dates {
+        let datetimes: Vec<Option<chrono::NaiveDateTime>> = values.iter().map(|v| v.and_then(|c| c.as_datetime())).collect();
+        if datetimes.iter().flatten().all(|dt| dt.time() == chrono::NaiveTime::MIN) {
+            let dates: Vec<Option<chrono::NaiveDate>> = datetimes.iter().map(|dt| dt.map(|dt| dt.date())).collect();
+            return Series::new(name, dates).into();
+        }
+        return Series::new(name, datetimes).into();
+    }
+
+    let texts: Vec<Option<String>> = values.iter().map(|v| v.map(cell_text)).collect();
+    Series::new(name, texts).into()
+}
+
+// Accepts "B2:F100", or column-only "B:F" to take every used row of those columns
+fn parse_cell_range(reference: &str, range: &Range<Data>) -> Result<((u32, u32), (u32, u32))> {
+    let invalid = || anyhow!("Invalid cell range '{}'; expected e.g. 'A1:D100' or 'A:D'", reference);
+    let (from, to) = reference.split_once(':').ok_or_else(invalid)?;
+    let (from_row, from_col) = parse_cell_ref(from).ok_or_else(invalid)?;
+    let (to_row, to_col) = parse_cell_ref(to).ok_or_else(invalid)?;
+
+    let used_start = range.start().map(|s| s.0).unwrap_or(0);
+    let used_end = range.end().map(|e| e.0).unwrap_or(0);
+    let start = (from_row.unwrap_or(used_start), from_col);
+    let end = (to_row.unwrap_or(used_end), to_col);
+
+    if start.0 > end.0 || start.1 > end.1 {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+// "C7" -> (Some(6), 2); "C" -> (None, 2)
+fn parse_cell_ref(reference: &str) -> Option<(Option<u32>, u32)> {
+    let reference = reference.trim().replace('$', "").to_uppercase();
+    let split = reference.find(|c: char| c.is_ascii_digit()).unwrap_or(reference.len());
+    let (letters, digits) = reference.split_at(split);
+
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let col = letters.chars().try_fold(0u32, |acc, c| acc.checked_mul(26)?.checked_add(c as u32 - 'A' as u32 + 1))? - 1;
+
+    let row = if digits.is_empty() {
+        None
+    } else {
+        Some(digits.parse::<u32>().ok().filter(|r| *r > 0)? - 1)
+    };
+    Some((row, col))
+}
+
+// One worksheet of an export; column_formats maps column names to Excel number formats such as "#,##0.00"
+pub struct SheetExport {
+    pub name: String,
+    pub frame: DataFrame,
+    pub column_formats: HashMap<String, String>,
+}
+
+pub fn workbook_bytes(sheets: &[SheetExport]) -> Result<Vec<u8>> {
+    if sheets.is_empty() {
+        return Err(anyhow!("Excel export requires at least one sheet"));
+    }
+
+    let mut workbook = Workbook::new();
+    for sheet in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&sheet.name)
+            .map_err(|e| anyhow!("Invalid sheet name '{}': {}", sheet.name, e))?;
+        write_sheet(worksheet, sheet)?;
+    }
+
+    workbook.save_to_buffer().map_err(|e| anyhow!("Failed to write workbook: {}", e))
+}
+
+fn write_sheet(worksheet: &mut Worksheet, sheet: &SheetExport) -> Result<()> {
+    let frame = &sheet.frame;
+    if frame.height() > MAX_SHEET_ROWS {
+        return Err(anyhow!(
+            "Sheet '{}' has {} rows; Excel sheets hold at most {}", sheet.name, frame.height(), MAX_SHEET_ROWS
+        ));
+    }
+    if let Some(unknown) = sheet.column_formats.keys().find(|name| frame.column(name.as_str()).is_err()) {
+        return Err(anyhow!("Column format refers to unknown column '{}' in sheet '{}'", unknown, sheet.name));
+    }
+
+    let excel_error = |e: rust_xlsxwriter::XlsxError| anyhow!("Failed to write sheet '{}': {}", sheet.name, e);
+    let header_format = Format::new().set_bold();
+
+    for (index, column) in frame.get_columns().iter().enumerate() {
+        let col = index as u16;
+        worksheet.write_string_with_format(0, col, column.name().as_str(), &header_format).map_err(excel_error)?;
+
+        let default_format = match column.dtype() {
+            DataType::Date => Some("yyyy-mm-dd"),
+            DataType::Datetime(_, _) => Some("yyyy-mm-dd hh:mm:ss"),
+            _ => None,
+        };
+        let format = match sheet.column_formats.get(column.name().as_str()).map(String::as_str).or(default_format) {
+            Some(number_format) => Format::new().set_num_format(number_format),
+            None => Format::new(),
+        };
+
+        match column.dtype() {
+            DataType::Boolean => {
+                for (row, value) in column.bool()?.into_iter().enumerate() {
+                    if let Some(value) = value {
+                        worksheet.write_boolean_with_format(row as u32 + 1, col, value, &format).map_err(excel_error)?;
+                    }
+                }
+            }
+            DataType::Date => {
+                for (row, value) in column.date()?.as_date_iter().enumerate() {
+                    if let Some(value) = value {
+                        worksheet.write_datetime_with_format(row as u32 + 1, col, value, &format).map_err(excel_error)?;
+                    }
+                }
+            }
+            DataType::Datetime(_, _) => {
+                for (row, value) in column.datetime()?.as_datetime_iter().enumerate() {
+                    if let Some(value) = value {
+                        worksheet.write_datetime_with_format(row as u32 + 1, col, value, &format).map_err(excel_error)?;
+                    }
+                }
+            }
+            dtype if dtype.is_primitive_numeric() => {
+                let numbers = column.cast(&DataType::Float64)?;
+                for (row, value) in numbers.f64()?.into_iter().enumerate() {
+                    if let Some(value) = value.filter(|v| v.is_finite()) {
+                        worksheet.write_number_with_format(row as u32 + 1, col, value, &format).map_err(excel_error)?;
+                    }
+                }
+            }
+            _ => {
+                let text = column.cast(&DataType::String)?;
+                for (row, value) in text.str()?.into_iter().enumerate() {
+                    if let Some(value) = value {
+                        worksheet.write_string_with_format(row as u32 + 1, col, value, &format).map_err(excel_error)?;
+                    }
+                }
+            }
+        }
+    }
+
+    worksheet.set_freeze_panes(1, 0).map_err(excel_error)?;
+    worksheet.autofit();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{DatasetFormat, load_from_bytes};
+
+    fn sample_workbook() -> Vec<u8> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name("Report").unwrap();
+        worksheet.write_string(0, 0, "Quarterly export").unwrap();
+        worksheet.merge_range(1, 1, 1, 2, "Q1", &Format::new()).unwrap();
+        worksheet.write_string(2, 0, "Region").unwrap();
+        worksheet.write_string(2, 1, "Sales").unwrap();
+        worksheet.write_string(2, 2, "Date").unwrap();
+        worksheet.write_string(3, 0, "North").unwrap();
+        worksheet.write_number(3, 1, 10.0).unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        worksheet.write_datetime_with_format(3, 2, date, &Format::new().set_num_format("yyyy-mm-dd")).unwrap();
+        worksheet.write_string(5, 0, "South").unwrap();
+        worksheet.write_number(5, 1, 12.5).unwrap();
+        workbook.add_worksheet().set_name("Other").unwrap();
+        workbook.save_to_buffer().unwrap()
+    }
+
+    #[test]
+    fn test_read_merged_header_and_typed_dates() {
+        let options = LoadOptions {
+            skip_rows: 1,
+            excel: ExcelOptions { sheet: Some(SheetSelector::Name("Report".to_string())), header_rows: 2, ..Default::default() },
+            ..Default::default()
+        };
+        let df = load_from_bytes(sample_workbook(), DatasetFormat::Excel, &options).unwrap();
+
+        let names: Vec<String> = df.get_column_names().iter().map(|n| n.to_string()).collect();
+        assert_eq!(names, vec!["Region", "Q1 Sales", "Q1 Date"]);
+        assert_eq!(df.height(), 2);
+        assert_eq!(df.column("Q1 Sales").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(df.column("Q1 Date").unwrap().dtype(), &DataType::Date);
+    }
+
+    #[test]
+    fn test_cell_range_and_sheet_errors() {
+        let options = LoadOptions {
+            excel: ExcelOptions { cell_range: Some("A3:B4".to_string()), ..Default::default() },
+            ..Default::default()
+        };
+        let df = load_from_bytes(sample_workbook(), DatasetFormat::Excel, &options).unwrap();
+        assert_eq!(df.shape(), (1, 2));
+        assert_eq!(df.column("Sales").unwrap().get(0).unwrap(), AnyValue::Int64(10));
+
+        let options = LoadOptions {
+            excel: ExcelOptions { sheet: Some(SheetSelector::Index(5)), ..Default::default() },
+            ..Default::default()
+        };
+        assert!(load_from_bytes(sample_workbook(), DatasetFormat::Excel, &options).is_err());
+        assert_eq!(parse_cell_ref("$AB$12"), Some((Some(11), 27)));
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let frame = df!(
+            "name" => ["a", "b"],
+            "amount" => [1.5, 2.25],
+            "active" => [true, false]
+        ).unwrap();
+        let sheets = vec![
+            SheetExport {
+                name: "Data".to_string(),
+                frame: frame.clone(),
+                column_formats: HashMap::from([("amount".to_string(), "#,##0.00".to_string())]),
+            },
+            SheetExport { name: "Copy".to_string(), frame, column_formats: HashMap::new() },
+        ];
+        let bytes = workbook_bytes(&sheets).unwrap();
+
+        let options = LoadOptions {
+            excel: ExcelOptions { sheet: Some(SheetSelector::Index(1)), ..Default::default() },
+            ..Default::default()
+        };
+        let df = load_from_bytes(bytes, DatasetFormat::Excel, &options).unwrap();
+        assert_eq!(df.shape(), (2, 3));
+        assert_eq!(df.column("amount").unwrap().get(1).unwrap(), AnyValue::Float64(2.25));
+        assert_eq!(df.column("active").unwrap().get(0).unwrap(), AnyValue::Boolean(true));
+    }
+}
@@ -0,0 +1,494 @@
+use anyhow::{Result, anyhow};
+use flate2::write::GzEncoder;
+use polars::io::utils::sync_on_close::SyncOnCloseType;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::dataset::DatasetFormat;
+use crate::excel::{self, SheetExport};
+
+// Gzip and zstd wrap the whole file for text formats; Parquet applies every codec per column chunk
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportCompression {
+    None,
+    Gzip,
+    Zstd,
+    Snappy,
+    Lz4,
+}
+
+impl ExportCompression {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportCompression::None => "none",
+            ExportCompression::Gzip => "gzip",
+            ExportCompression::Zstd => "zstd",
+            ExportCompression::Snappy => "snappy",
+            ExportCompression::Lz4 => "lz4",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub format: Option<DatasetFormat>,
+    pub compression: Option<ExportCompression>,
+    pub compression_level: Option<i32>,
+    pub delimiter: Option<char>,
+    pub include_header: bool,
+    pub overwrite: bool,
+    pub sheet_name: Option<String>,
+    pub column_formats: HashMap<String, String>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: None,
+            compression: None,
+            compression_level: None,
+            delimiter: None,
+            include_header: true,
+            overwrite: false,
+            sheet_name: None,
+            column_formats: HashMap::new(),
+        }
+    }
+}
+
+// Destination for writing a result table to disk alongside the HTTP response
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExportTarget {
+    pub path: String,
+    #[serde(default)]
+    pub options: ExportOptions,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExportSummary {
+    pub path: String,
+    pub format: String,
+    pub compression: String,
+    pub rows: usize,
+    pub columns: usize,
+    pub bytes: usize,
+    pub checksum: String,
+    pub checksum_algorithm: String,
+}
+
+pub fn export_dataframe(df: &mut DataFrame, path: &Path, options: &ExportOptions) -> Result<ExportSummary> {
+    let (format, compression) = resolve_format(path, options)?;
+
+    if path.exists() && !options.overwrite {
+        return Err(anyhow!("'{}' already exists; set overwrite to replace it", path.display()));
+    }
+
+    let encoded = encode(df, format, compression, options)?;
+    let bytes = match (format, compression) {
+        (DatasetFormat::Parquet, _) | (_, ExportCompression::None) => encoded,
+        (_, ExportCompression::Gzip) => {
+            let level = options.compression_level.map(|l| l.clamp(0, 9) as u32).unwrap_or(6);
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::new(level));
+            encoder.write_all(&encoded)?;
+            encoder.finish()?
+        }
+        (_, ExportCompression::Zstd) => zstd::encode_all(encoded.as_slice(), options.compression_level.unwrap_or(3))
+            .map_err(|e| anyhow!("Zstd compression failed: {}", e))?,
+        (_, other) => return Err(anyhow!("Compression '{}' is only supported for Parquet", other.as_str())),
+    };
+
+    let checksum = format!("{:x}", Sha256::digest(&bytes));
+    write_atomic(path, &bytes, options.overwrite)?;
+
+    info!("Exported {} rows as {} to {} ({} bytes)", df.height(), format.as_str(), path.display(), bytes.len());
+
+    Ok(ExportSummary {
+        path: path.display().to_string(),
+        format: format.as_str().to_string(),
+        compression: compression.as_str().to_string(),
+        rows: df.height(),
+        columns: df.width(),
+        bytes: bytes.len(),
+        checksum,
+        checksum_algorithm: "sha256".to_string(),
+    })
+}
+
+// An explicit format wins; otherwise "out.csv.gz" resolves to CSV with gzip compression
+fn resolve_format(path: &Path, options: &ExportOptions) -> Result<(DatasetFormat, ExportCompression)> {
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+    let suffix_compression = match extension.as_deref() {
+        Some("gz") => Some(ExportCompression::Gzip),
+        Some("zst") => Some(ExportCompression::Zstd),
+        _ => None,
+    };
+    let inner_path = if suffix_compression.is_some() { path.with_extension("") } else { path.to_path_buf() };
+
+    let format = options.format
+        .or_else(|| DatasetFormat::from_path(&inner_path))
+        .ok_or_else(|| anyhow!("Cannot determine export format of '{}'; pass an explicit format", path.display()))?;
+
+    let compression = match (options.compression.or(suffix_compression), format) {
+        (Some(compression), _) => compression,
+        (None, DatasetFormat::Parquet) => ExportCompression::Zstd,
+        (None, _) => ExportCompression::None,
+    };
+
+    if format == DatasetFormat::Excel && compression != ExportCompression::None {
+        return Err(anyhow!("Excel workbooks are already compressed; remove the compression option"));
+    }
+    Ok((format, compression))
+}
+
+fn encode(df: &mut DataFrame, format: DatasetFormat, compression: ExportCompression, options: &ExportOptions) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+
+    match format {
+        DatasetFormat::Csv | DatasetFormat::Tsv => {
+            let default_separator = if format == DatasetFormat::Tsv { '\t' } else { ',' };
+            let separator = options.delimiter.unwrap_or(default_separator);
+            if !separator.is_ascii() {
+                return Err(anyhow!("delimiter must be a single ASCII character, got '{}'", separator));
+            }
+            CsvWriter::new(&mut buffer)
+                .include_header(options.include_header)
+                .with_separator(separator as u8)
+                .finish(df)
+                .map_err(|e| anyhow!("Failed to write {}: {}", format.as_str().to_uppercase(), e))?;
+        }
+        DatasetFormat::Json | DatasetFormat::Ndjson => {
+            let json_format = if format == DatasetFormat::Ndjson { JsonFormat::JsonLines } else { JsonFormat::Json };
+            JsonWriter::new(&mut buffer)
+                .with_json_format(json_format)
+                .finish(df)
+                .map_err(|e| anyhow!("Failed to write {}: {}", format.as_str().to_uppercase(), e))?;
+        }
+        DatasetFormat::Parquet => {
+            ParquetWriter::new(&mut buffer)
+                .with_compression(parquet_compression(compression, options.compression_level)?)
+                .finish(df)
+                .map_err(|e| anyhow!("Failed to write Parquet: {}", e))?;
+        }
+        DatasetFormat::Excel => {
+            buffer = excel::workbook_bytes(&[SheetExport {
+                name: options.sheet_name.clone().unwrap_or_else(|| "Sheet1".to_string()),
+                frame: df.clone(),
+                column_formats: options.column_formats.clone(),
+            }])?;
+        }
+    }
+
+    Ok(buffer)
+}
+
+fn parquet_compression(compression: ExportCompression, level: Option<i32>) -> Result<ParquetCompression> {
+    let invalid_level = |e: PolarsError| anyhow!("Invalid compression level: {}", e);
+    Ok(match compression {
+        ExportCompression::None => ParquetCompression::Uncompressed,
+        ExportCompression::Snappy => ParquetCompression::Snappy,
+        ExportCompression::Lz4 => ParquetCompression::Lz4Raw,
+        ExportCompression::Gzip => ParquetCompression::Gzip(
+            level.map(|l| GzipLevel::try_new(l.clamp(0, 10) as u8)).transpose().map_err(invalid_level)?,
+        ),
+        ExportCompression::Zstd => ParquetCompression::Zstd(
+            level.map(ZstdLevel::try_new).transpose().map_err(invalid_level)?,
+        ),
+    })
+}
+
+// Readers never see a partial file: bytes go to a hidden temp file in the target directory,
+// which is synced and then published under the destination name
+pub fn write_atomic(path: &Path, bytes: &[u8], overwrite: bool) -> Result<()> {
+    let temp_path = temp_path_for(path)?;
+    let result = (|| -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        Ok(())
+    })();
+
+    result
+        .map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e))
+        .and_then(|_| publish(&temp_path, path, overwrite))
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp_path);
+        })
+}
+
+// Moves a finished temp file into place. Without overwrite the destination is created with a hard
+// link, which fails if the name exists, so a file that appeared after the up-front check survives.
+// Filesystems without hard links (FAT, many network mounts) claim the name with create_new instead
+fn publish(temp_path: &Path, path: &Path, overwrite: bool) -> Result<()> {
+    if overwrite {
+        return std::fs::rename(temp_path, path).map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e));
+    }
+    let published = std::fs::hard_link(temp_path, path).or_else(|e| match e.kind() {
+        std::io::ErrorKind::Unsupported | std::io::ErrorKind::PermissionDenied => claim_and_rename(temp_path, path),
+        _ => Err(e),
+    });
+    match published {
+        Ok(()) => {
+            let _ = std::fs::remove_file(temp_path);
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            Err(anyhow!("'{}' already exists; set overwrite to replace it", path.display()))
+        }
+        Err(e) => Err(anyhow!("Failed to write '{}': {}", path.display(), e)),
+    }
+}
+
+// create_new fails if the name exists; otherwise the temp file replaces the empty placeholder,
+// which nothing but this export can have created
+fn claim_and_rename(temp_path: &Path, path: &Path) -> std::io::Result<()> {
+    std::fs::OpenOptions::new().write(true).create_new(true).open(path)?;
+    std::fs::rename(temp_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(path);
+    })
+}
+
+fn temp_path_for(path: &Path) -> Result<PathBuf> {
+    let file_name = path.file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow!("Export path '{}' has no file name", path.display()))?;
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    if !directory.is_dir() {
+        return Err(anyhow!("Export directory '{}' does not exist", directory.display()));
+    }
+    Ok(directory.join(format!(".{}.{}.tmp", file_name, Uuid::new_v4())))
+}
+
+// Streams a lazy query straight to disk in batches, so the result never has to fit in memory.
+// Whole-file compression and Excel need the complete output up front and are rejected here
+pub fn export_lazy(frame: LazyFrame, path: &Path, options: &ExportOptions) -> Result<ExportSummary> {
+    let (format, compression) = resolve_format(path, options)?;
+
+    if path.exists() && !options.overwrite {
+        return Err(anyhow!("'{}' already exists; set overwrite to replace it", path.display()));
+    }
+
+    let columns = frame.clone().collect_schema()?.len();
+    let temp_path = temp_path_for(path)?;
+    let target = SinkTarget::Path(PlPath::new(&temp_path.to_string_lossy()));
+    let sink_options = SinkOptions { sync_on_close: SyncOnCloseType::All, ..Default::default() };
+
+    let sunk = match (format, compression) {
+        (DatasetFormat::Csv | DatasetFormat::Tsv, ExportCompression::None) => {
+            let default_separator = if format == DatasetFormat::Tsv { '\t' } else { ',' };
+            let separator = options.delimiter.unwrap_or(default_separator);
+            if !separator.is_ascii() {
+                return Err(anyhow!("delimiter must be a single ASCII character, got '{}'", separator));
+            }
+            let mut csv_options = CsvWriterOptions { include_header: options.include_header, ..Default::default() };
+            csv_options.serialize_options.separator = separator as u8;
+            frame.sink_csv(target, csv_options, None, sink_options)
+        }
+        (DatasetFormat::Ndjson, ExportCompression::None) => frame.sink_json(target, JsonWriterOptions::default(), None, sink_options),
+        (DatasetFormat::Parquet, _) => {
+            let parquet_options = ParquetWriteOptions {
+                compression: parquet_compression(compression, options.compression_level)?,
+                ..Default::default()
+            };
+            frame.sink_parquet(target, parquet_options, None, sink_options)
+        }
+        (DatasetFormat::Json | DatasetFormat::Excel, _) => {
+            return Err(anyhow!("{} cannot be written in batches; stream to csv, tsv, ndjson or parquet", format.as_str()));
+        }
+        (_, other) => return Err(anyhow!("Compression '{}' is not supported when streaming; use parquet for compressed output", other.as_str())),
+    };
+
+    let written = sunk.and_then(|plan| plan.collect())
+        .map_err(|e| anyhow!("Failed to stream {} to '{}': {}", format.as_str(), path.display(), e))
+        .and_then(|_| {
+            let (rows, bytes, checksum) = inspect_output(&temp_path, format, options)?;
+            publish(&temp_path, path, options.overwrite)?;
+            Ok((rows, bytes, checksum))
+        });
+    let (rows, bytes, checksum) = written.inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp_path);
+    })?;
+
+    info!("Streamed {} rows as {} to {} ({} bytes)", rows, format.as_str(), path.display(), bytes);
+
+    Ok(ExportSummary {
+        path: path.display().to_string(),
+        format: format.as_str().to_string(),
+        compression: compression.as_str().to_string(),
+        rows,
+        columns,
+        bytes,
+        checksum,
+        checksum_algorithm: "sha256".to_string(),
+    })
+}
+
+// Checksums the written file and counts its rows in the same pass, so the output is read only once.
+// Parquet row counts come from the footer; text rows are counted as record separators outside quotes
+fn inspect_output(path: &Path, format: DatasetFormat, options: &ExportOptions) -> Result<(usize, usize, String)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut bytes = 0;
+    let mut records = 0;
+    let mut in_quotes = false;
+    let mut last = b'\n';
+
+    loop {
+        let read = std::io::Read::read(&mut file, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        let chunk = &buffer[..read];
+        hasher.update(chunk);
+        bytes += read;
+        if format != DatasetFormat::Parquet {
+            for &b in chunk {
+                match b {
+                    b'"' if format != DatasetFormat::Ndjson => in_quotes = !in_quotes,
+                    b'\n' if !in_quotes => records += 1,
+                    _ => {}
+                }
+            }
+            last = chunk[read - 1];
+        }
+    }
+
+    let rows = match format {
+        DatasetFormat::Parquet => ParquetReader::new(std::fs::File::open(path)?).num_rows()?,
+        _ => {
+            let records = records + usize::from(last != b'\n');
+            let header = usize::from(format != DatasetFormat::Ndjson && options.include_header);
+            records.saturating_sub(header)
+        }
+    };
+    Ok((rows, bytes, format!("{:x}", hasher.finalize())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{LoadOptions, load_from_bytes};
+
+    fn sample() -> DataFrame {
+        df!(
+            "id" => [1i64, 2, 3],
+            "region" => ["North", "South", "East"],
+            "amount" => [Some(10.5), None, Some(7.25)]
+        ).unwrap()
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("export-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_gzip_csv_round_trip_with_checksum() {
+        let dir = temp_dir();
+        let path = dir.join("out.csv.gz");
+        let summary = export_dataframe(&mut sample(), &path, &ExportOptions::default()).unwrap();
+
+        assert_eq!((summary.format.as_str(), summary.compression.as_str()), ("csv", "gzip"));
+        assert_eq!(summary.rows, 3);
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(summary.checksum, format!("{:x}", Sha256::digest(&bytes)));
+
+        let mut csv = String::new();
+        std::io::Read::read_to_string(&mut flate2::read::GzDecoder::new(bytes.as_slice()), &mut csv).unwrap();
+        assert!(csv.starts_with("id,region,amount\n1,North,10.5\n"), "{}", csv);
+
+        // No temp files are left behind, and existing files are only replaced on request
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        assert!(export_dataframe(&mut sample(), &path, &ExportOptions::default()).is_err());
+        let options = ExportOptions { overwrite: true, ..Default::default() };
+        assert!(export_dataframe(&mut sample(), &path, &options).is_ok());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_publish_keeps_a_file_that_appeared_after_the_check() {
+        let dir = temp_dir();
+        let path = dir.join("late.csv");
+        let temp = dir.join(".late.tmp");
+
+        // Both the hard-link path and the create_new fallback refuse a name that is taken by now
+        type Publisher = fn(&Path, &Path) -> Result<()>;
+        let publishers: [(&str, Publisher); 2] = [
+            ("hard link", |temp, path| publish(temp, path, false)),
+            ("create_new", |temp, path| claim_and_rename(temp, path).map_err(Into::into)),
+        ];
+        for (label, publisher) in publishers {
+            std::fs::write(&temp, "new").unwrap();
+            assert!(!path.exists());
+            std::fs::write(&path, "existing").unwrap();
+            assert!(publisher(&temp, &path).is_err(), "{}", label);
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "existing", "{}", label);
+
+            // With the name free the temp file is published and gone
+            std::fs::remove_file(&path).unwrap();
+            publisher(&temp, &path).unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "new", "{}", label);
+            assert!(!temp.exists(), "{}", label);
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parquet_and_ndjson_exports() {
+        let dir = temp_dir();
+
+        let options = ExportOptions { compression: Some(ExportCompression::Snappy), ..Default::default() };
+        let summary = export_dataframe(&mut sample(), &dir.join("out.parquet"), &options).unwrap();
+        assert_eq!(summary.compression, "snappy");
+        let bytes = std::fs::read(dir.join("out.parquet")).unwrap();
+        let df = load_from_bytes(bytes, DatasetFormat::Parquet, &LoadOptions::default()).unwrap();
+        assert!(df.equals_missing(&sample()));
+
+        let options = ExportOptions { format: Some(DatasetFormat::Ndjson), ..Default::default() };
+        export_dataframe(&mut sample(), &dir.join("out.txt"), &options).unwrap();
+        let text = std::fs::read_to_string(dir.join("out.txt")).unwrap();
+        assert_eq!(text.lines().count(), 3);
+
+        let options = ExportOptions { compression: Some(ExportCompression::Snappy), ..Default::default() };
+        assert!(export_dataframe(&mut sample(), &dir.join("out.json"), &options).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_streamed_exports_count_rows_while_checksumming() {
+        let dir = temp_dir();
+        let frame = df!(
+            "id" => [1i64, 2, 3],
+            "note" => ["plain", "two\nlines", "\"quoted\""]
+        ).unwrap().lazy();
+
+        for (name, rows) in [("out.csv", 3), ("out.ndjson", 3), ("out.parquet", 3)] {
+            let path = dir.join(name);
+            let summary = export_lazy(frame.clone(), &path, &ExportOptions::default()).unwrap();
+            assert_eq!(summary.rows, rows, "{}", name);
+            assert_eq!(summary.checksum, format!("{:x}", Sha256::digest(std::fs::read(&path).unwrap())));
+        }
+
+        let options = ExportOptions { include_header: false, ..Default::default() };
+        assert_eq!(export_lazy(frame, &dir.join("bare.csv"), &options).unwrap().rows, 3);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
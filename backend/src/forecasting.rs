@@ -0,0 +1,477 @@
+use anyhow::{Result, anyhow};
+use polars::prelude::DataFrame;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::data_processor::finite_or_null;
+use crate::distributions::normal_quantile;
+use crate::timeseries::{self, DecompositionModel, TimeSeriesOptions};
+
+const MAX_HORIZON: usize = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastMethod {
+    MovingAverage,
+    #[default]
+    #[serde(alias = "ses", alias = "simple_exponential_smoothing")]
+    SimpleExponential,
+    #[serde(alias = "holt", alias = "double_exponential_smoothing")]
+    DoubleExponential,
+    #[serde(alias = "holt_winters", alias = "triple_exponential_smoothing")]
+    TripleExponential,
+    LinearTrend,
+}
+
+// Smoothing parameters left unset are chosen by grid search on the in-sample one-step error
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ForecastOptions {
+    pub method: ForecastMethod,
+    pub horizon: usize,
+    pub window: usize,
+    pub alpha: Option<f64>,
+    pub beta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub season_length: Option<usize>,
+    pub seasonal: DecompositionModel,
+    // Trailing observations held back to measure accuracy; defaults to min(horizon, 20% of the data)
+    pub holdout: Option<usize>,
+    pub confidence: f64,
+}
+
+impl Default for ForecastOptions {
+    fn default() -> Self {
+        Self {
+            method: ForecastMethod::SimpleExponential,
+            horizon: 10,
+            window: 3,
+            alpha: None,
+            beta: None,
+            gamma: None,
+            season_length: None,
+            seasonal: DecompositionModel::Additive,
+            holdout: None,
+            confidence: 0.95,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HoldoutMetrics {
+    pub size: usize,
+    pub mae: f64,
+    // Undefined when every held-out actual is zero
+    pub mape: Option<f64>,
+    pub rmse: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Forecast {
+    pub method: ForecastMethod,
+    pub horizon: usize,
+    pub predictions: Vec<f64>,
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+    pub confidence: f64,
+    pub alpha: Option<f64>,
+    pub beta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub residual_std: f64,
+    pub holdout: Option<HoldoutMetrics>,
+    pub warnings: Vec<String>,
+}
+
+struct Fit {
+    fitted: Vec<Option<f64>>,
+    predictions: Vec<f64>,
+    // Standard error of each step ahead, in units of the one-step residual standard deviation
+    spread: Vec<f64>,
+    alpha: Option<f64>,
+    beta: Option<f64>,
+    gamma: Option<f64>,
+}
+
+impl Fit {
+    fn sse(&self, values: &[f64]) -> f64 {
+        self.fitted.iter()
+            .zip(values)
+            .filter_map(|(f, y)| f.map(|f| (y - f).powi(2)))
+            .sum()
+    }
+}
+
+// Fits on all values and forecasts `horizon` steps; bands assume normal one-step errors and use the
+// usual approximate variance growth for each model
+pub fn forecast(values: &[f64], options: &ForecastOptions) -> Result<Forecast> {
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(anyhow!("Forecast input must not contain missing or non-finite values"));
+    }
+    if options.horizon == 0 || options.horizon > MAX_HORIZON {
+        return Err(anyhow!("Horizon must be between 1 and {}, got {}", MAX_HORIZON, options.horizon));
+    }
+    if !(options.confidence > 0.0 && options.confidence < 1.0) {
+        return Err(anyhow!("Confidence must lie strictly between 0 and 1, got {}", options.confidence));
+    }
+    for (name, value) in [("alpha", options.alpha), ("beta", options.beta), ("gamma", options.gamma)] {
+        if let Some(value) = value.filter(|v| !(*v > 0.0 && *v <= 1.0)) {
+            return Err(anyhow!("'{}' must lie in (0, 1], got {}", name, value));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let holdout_size = options.holdout.unwrap_or_else(|| options.horizon.min(values.len() / 5));
+    let holdout = if holdout_size == 0 {
+        warnings.push("No holdout was evaluated; error metrics are unavailable".to_string());
+        None
+    } else if holdout_size >= values.len() {
+        return Err(anyhow!("Holdout of {} leaves no data to fit on ({} values)", holdout_size, values.len()));
+    } else {
+        let (train, test) = values.split_at(values.len() - holdout_size);
+        match fit(train, options, holdout_size) {
+            Ok(fit) => Some(holdout_metrics(test, &fit.predictions)),
+            Err(e) => {
+                warnings.push(format!("Holdout evaluation skipped: {}", e));
+                None
+            }
+        }
+    };
+
+    let fit = fit(values, options, options.horizon)?;
+    let residuals: Vec<f64> = fit.fitted.iter()
+        .zip(values)
+        .filter_map(|(f, y)| f.map(|f| y - f))
+        .collect();
+    let residual_std = if residuals.is_empty() {
+        0.0
+    } else {
+        (residuals.iter().map(|r| r * r).sum::<f64>() / residuals.len() as f64).sqrt()
+    };
+
+    let z = normal_quantile(0.5 + options.confidence / 2.0)?;
+    let margins: Vec<f64> = fit.spread.iter().map(|s| z * residual_std * s).collect();
+
+    Ok(Forecast {
+        method: options.method,
+        horizon: options.horizon,
+        lower: fit.predictions.iter().zip(&margins).map(|(p, m)| p - m).collect(),
+        upper: fit.predictions.iter().zip(&margins).map(|(p, m)| p + m).collect(),
+        predictions: fit.predictions,
+        confidence: options.confidence,
+        alpha: fit.alpha,
+        beta: fit.beta,
+        gamma: fit.gamma,
+        residual_std,
+        holdout,
+        warnings,
+    })
+}
+
+// Forecasts a table's time series and labels each step with the period that follows the last observation
+pub fn forecast_table(df: &DataFrame, series_options: &TimeSeriesOptions, options: &ForecastOptions) -> Result<Value> {
+    let (time_column, value_column) = series_options.columns()?;
+    let series = timeseries::series_from_dataframe(df, time_column, value_column)?;
+    let values = series.complete_values("forecasting")?;
+    let result = forecast(&values, options)?;
+
+    let frequency = series_options.frequency;
+    let mut period = series.timestamps.last().map(|t| frequency.period_start(t.date()));
+    let data: Vec<Value> = (0..result.horizon)
+        .map(|h| {
+            period = period.and_then(|p| frequency.next_period(p));
+            serde_json::json!({
+                time_column: period.map(|p| p.format("%Y-%m-%d").to_string()),
+                "forecast": finite_or_null(result.predictions[h]),
+                "lower": finite_or_null(result.lower[h]),
+                "upper": finite_or_null(result.upper[h])
+            })
+        })
+        .collect();
+
+    let mut output = serde_json::to_value(&result)?;
+    output["operation"] = Value::from("forecast");
+    output["frequency"] = serde_json::to_value(frequency)?;
+    output["time_column"] = Value::from(time_column);
+    output["value_column"] = Value::from(value_column);
+    output["rows_dropped"] = Value::from(series.dropped_rows);
+    output["data"] = Value::from(data);
+    Ok(output)
+}
+
+fn holdout_metrics(actual: &[f64], predicted: &[f64]) -> HoldoutMetrics {
+    let n = actual.len() as f64;
+    let errors: Vec<f64> = actual.iter().zip(predicted).map(|(a, p)| a - p).collect();
+    let percentage: Vec<f64> = actual.iter()
+        .zip(&errors)
+        .filter(|(a, _)| **a != 0.0)
+        .map(|(a, e)| (e / a).abs() * 100.0)
+        .collect();
+
+    HoldoutMetrics {
+        size: actual.len(),
+        mae: errors.iter().map(|e| e.abs()).sum::<f64>() / n,
+        mape: (!percentage.is_empty()).then(|| percentage.iter().sum::<f64>() / percentage.len() as f64),
+        rmse: (errors.iter().map(|e| e * e).sum::<f64>() / n).sqrt(),
+    }
+}
+
+fn fit(values: &[f64], options: &ForecastOptions, horizon: usize) -> Result<Fit> {
+    match options.method {
+        ForecastMethod::MovingAverage => moving_average(values, options.window, horizon),
+        ForecastMethod::LinearTrend => linear_trend(values, horizon),
+        ForecastMethod::SimpleExponential => {
+            require_length(values, 2, "Simple exponential smoothing")?;
+            let alphas = candidates(options.alpha, 0.05);
+            best_fit(values, alphas.iter().map(|&a| simple_exponential(values, a, horizon)))
+        }
+        ForecastMethod::DoubleExponential => {
+            require_length(values, 3, "Double exponential smoothing")?;
+            let (alphas, betas) = (candidates(options.alpha, 0.05), candidates(options.beta, 0.05));
+            best_fit(values, alphas.iter().flat_map(|&a| betas.iter().map(move |&b| double_exponential(values, a, b, horizon))))
+        }
+        ForecastMethod::TripleExponential => {
+            let season = options.season_length
+                .ok_or_else(|| anyhow!("Triple exponential smoothing requires 'season_length'"))?;
+            if season < 2 {
+                return Err(anyhow!("'season_length' must be at least 2, got {}", season));
+            }
+            require_length(values, 2 * season, "Triple exponential smoothing")?;
+            if options.seasonal == DecompositionModel::Multiplicative && values.iter().any(|&v| v <= 0.0) {
+                return Err(anyhow!("Multiplicative seasonality requires strictly positive values"));
+            }
+
+            let (alphas, betas, gammas) = (candidates(options.alpha, 0.1), candidates(options.beta, 0.1), candidates(options.gamma, 0.1));
+            let fits = alphas.iter().flat_map(|&a| {
+                let gammas = &gammas;
+                betas.iter().flat_map(move |&b| gammas.iter().map(move |&g| triple_exponential(values, a, b, g, season, options.seasonal, horizon)))
+            });
+            best_fit(values, fits)
+        }
+    }
+}
+
+fn require_length(values: &[f64], minimum: usize, method: &str) -> Result<()> {
+    if values.len() < minimum {
+        return Err(anyhow!("{} needs at least {} values, got {}", method, minimum, values.len()));
+    }
+    Ok(())
+}
+
+// A fixed parameter, or an evenly spaced grid strictly inside (0, 1)
+fn candidates(fixed: Option<f64>, step: f64) -> Vec<f64> {
+    match fixed {
+        Some(value) => vec![value],
+        None => (1..).map(|i| i as f64 * step).take_while(|v| *v < 1.0 - 1e-9).collect(),
+    }
+}
+
+fn best_fit(values: &[f64], fits: impl Iterator<Item = Fit>) -> Result<Fit> {
+    fits.map(|fit| (fit.sse(values), fit))
+        .filter(|(sse, _)| sse.is_finite())
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, fit)| fit)
+        .ok_or_else(|| anyhow!("Smoothing produced no finite fit"))
+}
+
+fn moving_average(values: &[f64], window: usize, horizon: usize) -> Result<Fit> {
+    if window == 0 {
+        return Err(anyhow!("Moving average window must be at least 1"));
+    }
+    require_length(values, window, "Moving average")?;
+
+    let mean = |slice: &[f64]| slice.iter().sum::<f64>() / slice.len() as f64;
+    let fitted = (0..values.len())
+        .map(|t| (t >= window).then(|| mean(&values[t - window..t])))
+        .collect();
+    let level = mean(&values[values.len() - window..]);
+
+    Ok(Fit {
+        fitted,
+        predictions: vec![level; horizon],
+        // The level is an average of `window` noisy values, so its own error adds to the new observation's
+        spread: vec![(1.0 + 1.0 / window as f64).sqrt(); horizon],
+        alpha: None,
+        beta: None,
+        gamma: None,
+    })
+}
+
+fn simple_exponential(values: &[f64], alpha: f64, horizon: usize) -> Fit {
+    let mut level = values[0];
+    let mut fitted = vec![None];
+    for &y in &values[1..] {
+        fitted.push(Some(level));
+        level = alpha * y + (1.0 - alpha) * level;
+    }
+
+    Fit {
+        fitted,
+        predictions: vec![level; horizon],
+        spread: (0..horizon).map(|h| (1.0 + h as f64 * alpha * alpha).sqrt()).collect(),
+        alpha: Some(alpha),
+        beta: None,
+        gamma: None,
+    }
+}
+
+fn double_exponential(values: &[f64], alpha: f64, beta: f64, horizon: usize) -> Fit {
+    let mut level = values[0];
+    let mut trend = values[1] - values[0];
+    let mut fitted = vec![None];
+    for &y in &values[1..] {
+        fitted.push(Some(level + trend));
+        let previous = level;
+        level = alpha * y + (1.0 - alpha) * (level + trend);
+        trend = beta * (level - previous) + (1.0 - beta) * trend;
+    }
+
+    Fit {
+        fitted,
+        predictions: (1..=horizon).map(|h| level + h as f64 * trend).collect(),
+        spread: step_spread(horizon, |j| alpha * (1.0 + j as f64 * beta)),
+        alpha: Some(alpha),
+        beta: Some(beta),
+        gamma: None,
+    }
+}
+
+// Holt-Winters with the level and trend initialised from the first two seasons
+fn triple_exponential(values: &[f64], alpha: f64, beta: f64, gamma: f64, season: usize, model: DecompositionModel, horizon: usize) -> Fit {
+    let mean = |slice: &[f64]| slice.iter().sum::<f64>() / slice.len() as f64;
+    let mut level = mean(&values[..season]);
+    let mut trend = (mean(&values[season..2 * season]) - level) / season as f64;
+    let mut seasonals: Vec<f64> = values[..season].iter()
+        .map(|&y| match model {
+            DecompositionModel::Additive => y - level,
+            DecompositionModel::Multiplicative => y / level,
+        })
+        .collect();
+
+    let mut fitted = vec![None; season];
+    for (t, &y) in values.iter().enumerate().skip(season) {
+        let seasonal = seasonals[t - season];
+        let previous = level;
+        match model {
+            DecompositionModel::Additive => {
+                fitted.push(Some(level + trend + seasonal));
+                level = alpha * (y - seasonal) + (1.0 - alpha) * (level + trend);
+                seasonals.push(gamma * (y - level) + (1.0 - gamma) * seasonal);
+            }
+            DecompositionModel::Multiplicative => {
+                fitted.push(Some((level + trend) * seasonal));
+                level = alpha * (y / seasonal) + (1.0 - alpha) * (level + trend);
+                seasonals.push(gamma * (y / level) + (1.0 - gamma) * seasonal);
+            }
+        }
+        trend = beta * (level - previous) + (1.0 - beta) * trend;
+    }
+
+    let n = values.len();
+    let predictions = (1..=horizon)
+        .map(|h| {
+            let seasonal = seasonals[n - season + (h - 1) % season];
+            match model {
+                DecompositionModel::Additive => level + h as f64 * trend + seasonal,
+                DecompositionModel::Multiplicative => (level + h as f64 * trend) * seasonal,
+            }
+        })
+        .collect();
+
+    Fit {
+        fitted,
+        predictions,
+        spread: step_spread(horizon, |j| {
+            let seasonal_term = if j % season == 0 { gamma * (1.0 - alpha) } else { 0.0 };
+            alpha * (1.0 + j as f64 * beta) + seasonal_term
+        }),
+        alpha: Some(alpha),
+        beta: Some(beta),
+        gamma: Some(gamma),
+    }
+}
+
+// sqrt(1 + sum of c_j^2 for j < h), the h-step variance factor of an innovations state space model
+fn step_spread(horizon: usize, c: impl Fn(usize) -> f64) -> Vec<f64> {
+    let mut total = 1.0;
+    (1..=horizon)
+        .map(|h| {
+            if h > 1 {
+                total += c(h - 1).powi(2);
+            }
+            total.sqrt()
+        })
+        .collect()
+}
+
+// Least-squares line through (t, y); bands widen with distance from the centre of the data
+fn linear_trend(values: &[f64], horizon: usize) -> Result<Fit> {
+    require_length(values, 3, "Linear trend")?;
+    let n = values.len() as f64;
+    let mean_t = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+    let sxx: f64 = (0..values.len()).map(|t| (t as f64 - mean_t).powi(2)).sum();
+    let sxy: f64 = values.iter().enumerate().map(|(t, y)| (t as f64 - mean_t) * (y - mean_y)).sum();
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_t;
+
+    let line = |t: f64| intercept + slope * t;
+    Ok(Fit {
+        fitted: (0..values.len()).map(|t| Some(line(t as f64))).collect(),
+        predictions: (1..=horizon).map(|h| line(n - 1.0 + h as f64)).collect(),
+        spread: (1..=horizon)
+            .map(|h| (1.0 + 1.0 / n + (n - 1.0 + h as f64 - mean_t).powi(2) / sxx).sqrt())
+            .collect(),
+        alpha: None,
+        beta: None,
+        gamma: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(values: &[f64], options: ForecastOptions) -> Forecast {
+        forecast(values, &options).unwrap()
+    }
+
+    #[test]
+    fn test_trend_methods_extrapolate_a_line() {
+        let values: Vec<f64> = (0..30).map(|t| 5.0 + 2.0 * t as f64).collect();
+
+        let linear = run(&values, ForecastOptions { method: ForecastMethod::LinearTrend, horizon: 3, ..Default::default() });
+        assert!((linear.predictions[0] - 65.0).abs() < 1e-9);
+        assert!(linear.holdout.unwrap().rmse < 1e-9);
+
+        let holt = run(&values, ForecastOptions { method: ForecastMethod::DoubleExponential, horizon: 2, ..Default::default() });
+        assert!((holt.predictions[1] - 67.0).abs() < 1e-6, "{:?}", holt.predictions);
+
+        let moving = run(&values, ForecastOptions { method: ForecastMethod::MovingAverage, window: 3, horizon: 2, ..Default::default() });
+        assert_eq!(moving.predictions, vec![61.0, 61.0]);
+        assert!(moving.lower[0] < 61.0 && moving.upper[0] > 61.0);
+    }
+
+    #[test]
+    fn test_holt_winters_follows_season_and_bands_widen() {
+        let pattern = [10.0, -5.0, 0.0, -5.0];
+        let values: Vec<f64> = (0..40).map(|t| 100.0 + t as f64 + pattern[t % 4] + if t % 3 == 0 { 0.5 } else { -0.25 }).collect();
+        let options = ForecastOptions {
+            method: ForecastMethod::TripleExponential,
+            season_length: Some(4),
+            horizon: 8,
+            ..Default::default()
+        };
+        let result = run(&values, options);
+
+        assert!((result.predictions[0] - (140.0 + 10.0)).abs() < 2.0, "{:?}", result.predictions);
+        assert!((result.predictions[1] - (141.0 - 5.0)).abs() < 2.0, "{:?}", result.predictions);
+        let width = |h: usize| result.upper[h] - result.lower[h];
+        assert!(width(7) >= width(0));
+        assert!(result.holdout.unwrap().mape.unwrap() < 2.0);
+
+        let simple = run(&[3.0, 3.0, 3.0, 3.0], ForecastOptions { holdout: Some(0), ..Default::default() });
+        assert_eq!(simple.predictions[0], 3.0);
+        assert!(simple.holdout.is_none());
+        assert!(forecast(&[1.0, f64::NAN], &ForecastOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// Similarity metrics supported by the fuzzy formulas
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityMetric {
+    Levenshtein,
+    #[default]
+    JaroWinkler,
+    TokenSet,
+}
+
+impl SimilarityMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SimilarityMetric::Levenshtein => "levenshtein",
+            SimilarityMetric::JaroWinkler => "jaro_winkler",
+            SimilarityMetric::TokenSet => "token_set",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct FuzzyMatchConfig {
+    pub metric: SimilarityMetric,
+    pub threshold: f64,
+    pub blocking_columns: Vec<String>,
+    pub blocking_prefix: Option<usize>,
+    pub case_sensitive: bool,
+}
+
+impl Default for FuzzyMatchConfig {
+    fn default() -> Self {
+        Self {
+            metric: SimilarityMetric::JaroWinkler,
+            threshold: 0.85,
+            blocking_columns: Vec::new(),
+            blocking_prefix: None,
+            case_sensitive: false,
+        }
+    }
+}
+
+impl FuzzyMatchConfig {
+    // Normalize text before comparison: trim, collapse whitespace and fold case
+    pub fn normalize(&self, text: &str) -> String {
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if self.case_sensitive {
+            collapsed
+        } else {
+            collapsed.to_lowercase()
+        }
+    }
+
+    // Build the block a record belongs to; only records sharing a block are compared
+    pub fn block_key(&self, normalized: &str, blocking_values: &[String]) -> String {
+        let mut key = blocking_values.join("|");
+        if let Some(prefix_len) = self.blocking_prefix {
+            key.push('#');
+            key.extend(normalized.chars().take(prefix_len));
+        }
+        key
+    }
+
+    pub fn score(&self, a: &str, b: &str) -> f64 {
+        similarity(self.metric, a, b)
+    }
+}
+
+pub fn similarity(metric: SimilarityMetric, a: &str, b: &str) -> f64 {
+    match metric {
+        SimilarityMetric::Levenshtein => levenshtein_similarity(a, b),
+        SimilarityMetric::JaroWinkler => jaro_winkler(a, b),
+        SimilarityMetric::TokenSet => token_set_ratio(a, b),
+    }
+}
+
+pub fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.is_empty() {
+        return b.len();
+    }
+    if b.is_empty() {
+        return a.len();
+    }
+
+    // Two-row dynamic programming table
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j + 1] + 1)
+                .min(current[j] + 1)
+                .min(previous[j] + cost);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+pub fn levenshtein_similarity(a: &str, b: &str) -> f64 {
+    let max_len = a.chars().count().max(b.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein_distance(a, b) as f64 / max_len as f64
+}
+
+pub fn jaro(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let match_window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matches = vec![false; a.len()];
+    let mut b_matches = vec![false; b.len()];
+    let mut matches = 0usize;
+
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(match_window);
+        let end = (i + match_window + 1).min(b.len());
+        for j in start..end {
+            if !b_matches[j] && b[j] == *ca {
+                a_matches[i] = true;
+                b_matches[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+
+    if matches == 0 {
+        return 0.0;
+    }
+
+    // Count transpositions between the matched characters of both strings
+    let mut transpositions = 0usize;
+    let mut k = 0usize;
+    for (i, ca) in a.iter().enumerate() {
+        if !a_matches[i] {
+            continue;
+        }
+        while !b_matches[k] {
+            k += 1;
+        }
+        if *ca != b[k] {
+            transpositions += 1;
+        }
+        k += 1;
+    }
+
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64 / 2.0) / m) / 3.0
+}
+
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let jaro_score = jaro(a, b);
+    let prefix = a.chars()
+        .zip(b.chars())
+        .take_while(|(ca, cb)| ca == cb)
+        .take(4)
+        .count();
+
+    jaro_score + prefix as f64 * 0.1 * (1.0 - jaro_score)
+}
+
+// Token-set ratio: compares the shared tokens against each side's full token set,
+// so word order and repeated words do not lower the score
+pub fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let tokens_a: HashSet<&str> = a.split_whitespace().collect();
+    let tokens_b: HashSet<&str> = b.split_whitespace().collect();
+
+    if tokens_a.is_empty() && tokens_b.is_empty() {
+        return 1.0;
+    }
+    if tokens_a.is_empty() || tokens_b.is_empty() {
+        return 0.0;
+    }
+
+    let sorted_join = |tokens: Vec<&str>| {
+        let mut tokens = tokens;
+        tokens.sort_unstable();
+        tokens.join(" ")
+    };
+
+    let intersection = sorted_join(tokens_a.intersection(&tokens_b).copied().collect());
+    let only_a = sorted_join(tokens_a.difference(&tokens_b).copied().collect());
+    let only_b = sorted_join(tokens_b.difference(&tokens_a).copied().collect());
+
+    let combine = |rest: &str| {
+        match (intersection.is_empty(), rest.is_empty()) {
+            (true, _) => rest.to_string(),
+            (false, true) => intersection.clone(),
+            (false, false) => format!("{} {}", intersection, rest),
+        }
+    };
+
+    let combined_a = combine(&only_a);
+    let combined_b = combine(&only_b);
+
+    let mut best = levenshtein_similarity(&combined_a, &combined_b);
+    if !intersection.is_empty() {
+        best = best
+            .max(levenshtein_similarity(&intersection, &combined_a))
+            .max(levenshtein_similarity(&intersection, &combined_b));
+    }
+    best
+}
+
+// A candidate record prepared for matching
+#[derive(Clone, Debug)]
+pub struct FuzzyCandidate {
+    pub index: usize,
+    pub normalized: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct FuzzyMatch {
+    pub index: usize,
+    pub score: f64,
+}
+
+// Candidates grouped by block key so a probe is only compared within its block
+#[derive(Default)]
+pub struct BlockedIndex {
+    blocks: HashMap<String, Vec<FuzzyCandidate>>,
+    exact: HashMap<String, usize>,
+}
+
+impl BlockedIndex {
+    pub fn insert(&mut self, block_key: String, candidate: FuzzyCandidate) {
+        self.exact
+            .entry(format!("{}\u{1f}{}", block_key, candidate.normalized))
+            .or_insert(candidate.index);
+        self.blocks.entry(block_key).or_default().push(candidate);
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    // Returns the best candidate at or above the threshold, plus the number of comparisons made
+    pub fn best_match(&self, config: &FuzzyMatchConfig, block_key: &str, probe: &str) -> (Option<FuzzyMatch>, usize) {
+        if let Some(&index) = self.exact.get(&format!("{}\u{1f}{}", block_key, probe)) {
+            return (Some(FuzzyMatch { index, score: 1.0 }), 0);
+        }
+
+        let candidates = match self.blocks.get(block_key) {
+            Some(candidates) => candidates,
+            None => return (None, 0),
+        };
+
+        let mut best: Option<FuzzyMatch> = None;
+        for candidate in candidates {
+            let score = config.score(probe, &candidate.normalized);
+            if score >= config.threshold && best.as_ref().is_none_or(|b| score > b.score) {
+                best = Some(FuzzyMatch { index: candidate.index, score });
+            }
+        }
+
+        (best, candidates.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_distance("", "abc"), 3);
+        assert!((levenshtein_similarity("abc", "abc") - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_jaro_winkler() {
+        assert!((jaro("martha", "marhta") - 0.9444).abs() < 1e-3);
+        assert!((jaro_winkler("martha", "marhta") - 0.9611).abs() < 1e-3);
+        assert_eq!(jaro_winkler("abc", "xyz"), 0.0);
+    }
+
+    #[test]
+    fn test_token_set_ratio_ignores_order() {
+        assert!((token_set_ratio("acme corp ltd", "ltd acme corp") - 1.0).abs() < 1e-12);
+        assert!(token_set_ratio("acme corporation", "acme corp") > 0.5);
+    }
+
+    #[test]
+    fn test_blocked_index_respects_blocks() {
+        let config = FuzzyMatchConfig { threshold: 0.8, ..Default::default() };
+        let mut index = BlockedIndex::default();
+        index.insert("north".to_string(), FuzzyCandidate { index: 0, normalized: "acme corp".to_string() });
+        index.insert("south".to_string(), FuzzyCandidate { index: 1, normalized: "acme corp".to_string() });
+
+        let (found, _) = index.best_match(&config, "south", "acme corp.");
+        assert_eq!(found.unwrap().index, 1);
+
+        let (missing, _) = index.best_match(&config, "east", "acme corp");
+        assert!(missing.is_none());
+    }
+}
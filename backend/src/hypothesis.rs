@@ -0,0 +1,454 @@
+use anyhow::{Result, anyhow};
+use ndarray::Array2;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::analytics::average_ranks;
+use crate::distributions::{chi_square_sf, f_sf, normal_cdf, student_t_cdf};
+use crate::linalg;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TestKind {
+    #[default]
+    OneSampleT,
+    TwoSampleT,
+    WelchT,
+    ChiSquare,
+    MannWhitneyU,
+    Anova,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Alternative {
+    #[default]
+    TwoSided,
+    // The first sample's mean (or location) is below the second's, or below mu
+    Less,
+    Greater,
+}
+
+// Samples come either from flat values or from table columns; missing and non-finite values are omitted
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct HypothesisOptions {
+    pub test: TestKind,
+    pub alternative: Alternative,
+    // Hypothesised mean for the one-sample t-test
+    pub mu: f64,
+    // Flat input: lengths of the consecutive samples packed into the data
+    pub group_sizes: Option<Vec<usize>>,
+    // Flat input: [rows, cols] of the contingency table for the chi-square test
+    pub shape: Option<[usize; 2]>,
+    pub value_column: Option<String>,
+    pub group_column: Option<String>,
+    // Groups to compare, in order; defaults to every group in order of first appearance
+    pub groups: Option<Vec<String>>,
+    // Second categorical column for the chi-square test on a table
+    pub category_column: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum DegreesOfFreedom {
+    Single(f64),
+    // Numerator and denominator, as for the F distribution
+    Pair(f64, f64),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TestResult {
+    pub test: TestKind,
+    pub alternative: Alternative,
+    pub statistic: f64,
+    pub p_value: f64,
+    pub degrees_of_freedom: Option<DegreesOfFreedom>,
+    pub effect_size: f64,
+    pub effect_size_measure: String,
+    pub sample_sizes: Vec<usize>,
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn sample_variance(values: &[f64]) -> f64 {
+    let m = mean(values);
+    values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
+
+fn require_size(values: &[f64], minimum: usize, label: &str) -> Result<()> {
+    if values.len() < minimum {
+        return Err(anyhow!("{} needs at least {} values, got {}", label, minimum, values.len()));
+    }
+    Ok(())
+}
+
+fn p_value_from_cdf(cdf: f64, alternative: Alternative) -> f64 {
+    match alternative {
+        Alternative::TwoSided => (2.0 * cdf.min(1.0 - cdf)).min(1.0),
+        Alternative::Less => cdf,
+        Alternative::Greater => 1.0 - cdf,
+    }
+}
+
+pub fn one_sample_t(values: &[f64], mu: f64, alternative: Alternative) -> Result<TestResult> {
+    require_size(values, 2, "One-sample t-test")?;
+    let n = values.len() as f64;
+    let std = sample_variance(values).sqrt();
+    if std == 0.0 {
+        return Err(anyhow!("Sample has zero variance; the t statistic is undefined"));
+    }
+
+    let statistic = (mean(values) - mu) / (std / n.sqrt());
+    let df = n - 1.0;
+    Ok(TestResult {
+        test: TestKind::OneSampleT,
+        alternative,
+        statistic,
+        p_value: p_value_from_cdf(student_t_cdf(statistic, df), alternative),
+        degrees_of_freedom: Some(DegreesOfFreedom::Single(df)),
+        effect_size: (mean(values) - mu) / std,
+        effect_size_measure: "cohens_d".to_string(),
+        sample_sizes: vec![values.len()],
+    })
+}
+
+// Student's test pools the variances; Welch's does not and uses the Welch-Satterthwaite degrees of freedom
+pub fn two_sample_t(a: &[f64], b: &[f64], equal_variance: bool, alternative: Alternative) -> Result<TestResult> {
+    require_size(a, 2, "First sample")?;
+    require_size(b, 2, "Second sample")?;
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let (v1, v2) = (sample_variance(a), sample_variance(b));
+    let difference = mean(a) - mean(b);
+
+    let (standard_error, df, effect_scale) = if equal_variance {
+        let pooled = ((n1 - 1.0) * v1 + (n2 - 1.0) * v2) / (n1 + n2 - 2.0);
+        ((pooled * (1.0 / n1 + 1.0 / n2)).sqrt(), n1 + n2 - 2.0, pooled.sqrt())
+    } else {
+        let (q1, q2) = (v1 / n1, v2 / n2);
+        let df = (q1 + q2).powi(2) / (q1 * q1 / (n1 - 1.0) + q2 * q2 / (n2 - 1.0));
+        ((q1 + q2).sqrt(), df, ((v1 + v2) / 2.0).sqrt())
+    };
+    if standard_error == 0.0 {
+        return Err(anyhow!("Both samples have zero variance; the t statistic is undefined"));
+    }
+
+    let statistic = difference / standard_error;
+    Ok(TestResult {
+        test: if equal_variance { TestKind::TwoSampleT } else { TestKind::WelchT },
+        alternative,
+        statistic,
+        p_value: p_value_from_cdf(student_t_cdf(statistic, df), alternative),
+        degrees_of_freedom: Some(DegreesOfFreedom::Single(df)),
+        effect_size: difference / effect_scale,
+        effect_size_measure: "cohens_d".to_string(),
+        sample_sizes: vec![a.len(), b.len()],
+    })
+}
+
+// Pearson's chi-square on observed counts; effect size is Cramer's V
+pub fn chi_square_independence(table: &Array2<f64>) -> Result<TestResult> {
+    let (rows, cols) = table.dim();
+    if rows < 2 || cols < 2 {
+        return Err(anyhow!("Contingency table must be at least 2x2, got {}x{}", rows, cols));
+    }
+    if table.iter().any(|&v| v < 0.0) {
+        return Err(anyhow!("Contingency table counts must be non-negative"));
+    }
+
+    let total = table.sum();
+    let row_totals = table.sum_axis(ndarray::Axis(1));
+    let col_totals = table.sum_axis(ndarray::Axis(0));
+    if row_totals.iter().chain(col_totals.iter()).any(|&t| t == 0.0) {
+        return Err(anyhow!("Every row and column of the contingency table needs a non-zero total"));
+    }
+
+    let mut statistic = 0.0;
+    for i in 0..rows {
+        for j in 0..cols {
+            let expected = row_totals[i] * col_totals[j] / total;
+            statistic += (table[[i, j]] - expected).powi(2) / expected;
+        }
+    }
+
+    let df = ((rows - 1) * (cols - 1)) as f64;
+    Ok(TestResult {
+        test: TestKind::ChiSquare,
+        alternative: Alternative::Greater,
+        statistic,
+        p_value: chi_square_sf(statistic, df),
+        degrees_of_freedom: Some(DegreesOfFreedom::Single(df)),
+        effect_size: (statistic / (total * (rows.min(cols) - 1) as f64)).sqrt(),
+        effect_size_measure: "cramers_v".to_string(),
+        sample_sizes: vec![total as usize],
+    })
+}
+
+// U is reported for the first sample. The p-value uses the normal approximation with tie and
+// continuity corrections; effect size is the rank-biserial correlation, positive when the first sample tends higher
+pub fn mann_whitney_u(a: &[f64], b: &[f64], alternative: Alternative) -> Result<TestResult> {
+    require_size(a, 1, "First sample")?;
+    require_size(b, 1, "Second sample")?;
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let combined: Vec<f64> = a.iter().chain(b).copied().collect();
+    let ranks = average_ranks(&combined);
+
+    let rank_sum: f64 = ranks[..a.len()].iter().sum();
+    let u1 = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let mean_u = n1 * n2 / 2.0;
+
+    let mut sorted = combined.clone();
+    sorted.sort_by(f64::total_cmp);
+    let tie_term: f64 = sorted.chunk_by(|x, y| x == y)
+        .map(|run| run.len() as f64)
+        .map(|t| t * t * t - t)
+        .sum();
+    let n = n1 + n2;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return Err(anyhow!("All values are tied; the Mann-Whitney U test is undefined"));
+    }
+
+    let sd = variance.sqrt();
+    let p_value = match alternative {
+        Alternative::TwoSided => (2.0 * normal_cdf(-((u1 - mean_u).abs() - 0.5).max(0.0) / sd)).min(1.0),
+        Alternative::Greater => normal_cdf(-(u1 - mean_u - 0.5) / sd),
+        Alternative::Less => normal_cdf((u1 - mean_u + 0.5) / sd),
+    };
+
+    Ok(TestResult {
+        test: TestKind::MannWhitneyU,
+        alternative,
+        statistic: u1,
+        p_value,
+        degrees_of_freedom: None,
+        effect_size: 2.0 * u1 / (n1 * n2) - 1.0,
+        effect_size_measure: "rank_biserial".to_string(),
+        sample_sizes: vec![a.len(), b.len()],
+    })
+}
+
+// One-way ANOVA F-test; effect size is eta squared (share of variance between groups)
+pub fn one_way_anova(groups: &[Vec<f64>]) -> Result<TestResult> {
+    if groups.len() < 2 {
+        return Err(anyhow!("ANOVA needs at least 2 groups, got {}", groups.len()));
+    }
+    for (i, group) in groups.iter().enumerate() {
+        require_size(group, 1, &format!("Group {}", i + 1))?;
+    }
+
+    let all: Vec<f64> = groups.iter().flatten().copied().collect();
+    let grand_mean = mean(&all);
+    let between: f64 = groups.iter().map(|g| g.len() as f64 * (mean(g) - grand_mean).powi(2)).sum();
+    let within: f64 = groups.iter()
+        .map(|g| {
+            let m = mean(g);
+            g.iter().map(|v| (v - m).powi(2)).sum::<f64>()
+        })
+        .sum();
+
+    let df_between = (groups.len() - 1) as f64;
+    let df_within = (all.len() - groups.len()) as f64;
+    if df_within == 0.0 {
+        return Err(anyhow!("ANOVA needs more observations than groups"));
+    }
+    if within == 0.0 {
+        return Err(anyhow!("Groups have no variance within them; the F statistic is undefined"));
+    }
+
+    let statistic = (between / df_between) / (within / df_within);
+    Ok(TestResult {
+        test: TestKind::Anova,
+        alternative: Alternative::Greater,
+        statistic,
+        p_value: f_sf(statistic, df_between, df_within),
+        degrees_of_freedom: Some(DegreesOfFreedom::Pair(df_between, df_within)),
+        effect_size: between / (between + within),
+        effect_size_measure: "eta_squared".to_string(),
+        sample_sizes: groups.iter().map(|g| g.len()).collect(),
+    })
+}
+
+fn run_on_samples(samples: &[Vec<f64>], options: &HypothesisOptions) -> Result<TestResult> {
+    let expect = |count: usize| -> Result<()> {
+        if samples.len() != count {
+            return Err(anyhow!("{:?} compares {} samples, got {}", options.test, count, samples.len()));
+        }
+        Ok(())
+    };
+    match options.test {
+        TestKind::OneSampleT => {
+            expect(1)?;
+            one_sample_t(&samples[0], options.mu, options.alternative)
+        }
+        TestKind::TwoSampleT | TestKind::WelchT => {
+            expect(2)?;
+            two_sample_t(&samples[0], &samples[1], options.test == TestKind::TwoSampleT, options.alternative)
+        }
+        TestKind::MannWhitneyU => {
+            expect(2)?;
+            mann_whitney_u(&samples[0], &samples[1], options.alternative)
+        }
+        TestKind::Anova => one_way_anova(samples),
+        TestKind::ChiSquare => Err(anyhow!("The chi-square test takes a contingency table, not samples")),
+    }
+}
+
+// Flat input holds consecutive samples split by `group_sizes`, or a row-major contingency table
+pub fn run_on_values(data: &[f64], options: &HypothesisOptions) -> Result<TestResult> {
+    if options.test == TestKind::ChiSquare {
+        let [rows, cols] = options.shape
+            .ok_or_else(|| anyhow!("The chi-square test requires 'shape' as [rows, cols] for the contingency table"))?;
+        return chi_square_independence(&linalg::from_flat(data, rows, cols)?);
+    }
+
+    let sizes = options.group_sizes.clone().unwrap_or_else(|| vec![data.len()]);
+    if sizes.iter().sum::<usize>() != data.len() {
+        return Err(anyhow!("group_sizes add up to {} but the data holds {} values", sizes.iter().sum::<usize>(), data.len()));
+    }
+    let mut offset = 0;
+    let samples: Vec<Vec<f64>> = sizes.iter()
+        .map(|&size| {
+            let sample = data[offset..offset + size].iter().copied().filter(|v| v.is_finite()).collect();
+            offset += size;
+            sample
+        })
+        .collect();
+    run_on_samples(&samples, options)
+}
+
+pub fn run_on_table(df: &DataFrame, options: &HypothesisOptions) -> Result<TestResult> {
+    let labels = |column: &str| -> Result<Vec<Option<String>>> {
+        let values = df.column(column).map_err(|_| anyhow!("Column '{}' not found", column))?.cast(&DataType::String)?;
+        Ok(values.str()?.into_iter().map(|v| v.map(str::to_string)).collect())
+    };
+    let group_column = options.group_column.as_deref();
+
+    if options.test == TestKind::ChiSquare {
+        let (rows_column, cols_column) = group_column.zip(options.category_column.as_deref())
+            .ok_or_else(|| anyhow!("The chi-square test requires 'group_column' and 'category_column'"))?;
+        let (row_labels, col_labels) = (labels(rows_column)?, labels(cols_column)?);
+        let row_levels = distinct(&row_labels, options.groups.as_deref());
+        let col_levels = distinct(&col_labels, None);
+
+        let mut table = Array2::<f64>::zeros((row_levels.len(), col_levels.len()));
+        for (r, c) in row_labels.iter().zip(&col_labels) {
+            let i = r.as_ref().and_then(|r| row_levels.iter().position(|l| l == r));
+            let j = c.as_ref().and_then(|c| col_levels.iter().position(|l| l == c));
+            if let (Some(i), Some(j)) = (i, j) {
+                table[[i, j]] += 1.0;
+            }
+        }
+        return chi_square_independence(&table);
+    }
+
+    let value_column = options.value_column.as_deref()
+        .ok_or_else(|| anyhow!("Hypothesis tests on a table require a 'value_column' parameter"))?;
+    let column = df.column(value_column).map_err(|_| anyhow!("Column '{}' not found", value_column))?;
+    if !column.dtype().is_primitive_numeric() {
+        return Err(anyhow!("Column '{}' has type {} and is not numeric", value_column, column.dtype()));
+    }
+    let values: Vec<Option<f64>> = column.cast(&DataType::Float64)?.f64()?.into_iter().collect();
+    let finite = |v: &Option<f64>| v.filter(|v| v.is_finite());
+
+    let samples: Vec<Vec<f64>> = match group_column {
+        None => vec![values.iter().filter_map(finite).collect()],
+        Some(group_column) => {
+            let group_labels = labels(group_column)?;
+            distinct(&group_labels, options.groups.as_deref())
+                .iter()
+                .map(|level| {
+                    values.iter()
+                        .zip(&group_labels)
+                        .filter(|(_, label)| label.as_deref() == Some(level.as_str()))
+                        .filter_map(|(v, _)| finite(v))
+                        .collect()
+                })
+                .collect()
+        }
+    };
+    run_on_samples(&samples, options)
+}
+
+// Requested levels in the given order, otherwise every non-null level in order of first appearance
+fn distinct(labels: &[Option<String>], requested: Option<&[String]>) -> Vec<String> {
+    if let Some(requested) = requested {
+        return requested.to_vec();
+    }
+    let mut levels: Vec<String> = Vec::new();
+    for label in labels.iter().flatten() {
+        if !levels.contains(label) {
+            levels.push(label.clone());
+        }
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_t_tests() {
+        let a = [5.1, 4.9, 5.6, 5.8, 6.0, 5.4];
+        let b = [4.2, 4.8, 4.4, 4.9, 4.5, 5.0, 4.1];
+
+        let one = one_sample_t(&a, 5.0, Alternative::TwoSided).unwrap();
+        let expected_t = (mean(&a) - 5.0) / (sample_variance(&a).sqrt() / 6f64.sqrt());
+        assert!((one.statistic - expected_t).abs() < 1e-12);
+        assert_eq!(one.degrees_of_freedom, Some(DegreesOfFreedom::Single(5.0)));
+
+        let student = two_sample_t(&a, &b, true, Alternative::TwoSided).unwrap();
+        let welch = two_sample_t(&a, &b, false, Alternative::TwoSided).unwrap();
+        assert!(student.p_value < 0.01 && welch.p_value < 0.01);
+        assert_eq!(student.degrees_of_freedom, Some(DegreesOfFreedom::Single(11.0)));
+        assert!(matches!(welch.degrees_of_freedom, Some(DegreesOfFreedom::Single(df)) if df < 11.0));
+
+        let greater = two_sample_t(&a, &b, true, Alternative::Greater).unwrap();
+        assert!((greater.p_value - student.p_value / 2.0).abs() < 1e-12);
+        assert!(student.effect_size > 1.0);
+    }
+
+    #[test]
+    fn test_chi_square_mann_whitney_and_anova() {
+        let table = array![[10.0, 20.0], [20.0, 10.0]];
+        let chi = chi_square_independence(&table).unwrap();
+        assert!((chi.statistic - 20.0 / 3.0).abs() < 1e-12);
+        assert!((chi.p_value - chi_square_sf(20.0 / 3.0, 1.0)).abs() < 1e-15);
+        assert!((chi.effect_size - (20.0f64 / 3.0 / 60.0).sqrt()).abs() < 1e-12);
+
+        // Every value of the first sample exceeds every value of the second
+        let mw = mann_whitney_u(&[6.0, 7.0, 8.0, 9.0], &[1.0, 2.0, 3.0], Alternative::TwoSided).unwrap();
+        assert_eq!(mw.statistic, 12.0);
+        assert_eq!(mw.effect_size, 1.0);
+        assert!(mw.p_value < 0.1);
+
+        let groups = vec![vec![1.0, 2.0, 3.0], vec![2.0, 3.0, 4.0], vec![5.0, 6.0, 7.0]];
+        let anova = one_way_anova(&groups).unwrap();
+        assert!((anova.statistic - 13.0).abs() < 1e-12);
+        assert_eq!(anova.degrees_of_freedom, Some(DegreesOfFreedom::Pair(2.0, 6.0)));
+        assert!((anova.effect_size - 26.0 / 32.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_table_and_flat_inputs_agree() {
+        let df = df!(
+            "segment" => ["a", "b", "a", "b", "a", "b", "a"],
+            "spend" => [Some(10.0), Some(4.0), Some(12.0), Some(5.0), None, Some(3.5), Some(11.0)]
+        ).unwrap();
+        let options = HypothesisOptions {
+            test: TestKind::WelchT,
+            value_column: Some("spend".to_string()),
+            group_column: Some("segment".to_string()),
+            ..Default::default()
+        };
+        let from_table = run_on_table(&df, &options).unwrap();
+        let flat = HypothesisOptions { group_sizes: Some(vec![3, 3]), ..options };
+        let from_values = run_on_values(&[10.0, 12.0, 11.0, 4.0, 5.0, 3.5], &flat).unwrap();
+        assert_eq!(from_table.statistic, from_values.statistic);
+        assert_eq!(from_table.sample_sizes, vec![3, 3]);
+    }
+}
@@ -0,0 +1,642 @@
+use anyhow::{Result, anyhow};
+use ndarray::Array2;
+
+// Pivots smaller than this, relative to the largest entry, are treated as zero
+const SINGULAR_TOLERANCE: f64 = 1e-12;
+
+// Beyond this condition number fewer than ~4 significant digits of a solution survive rounding
+pub const MAX_CONDITION_NUMBER: f64 = 1e12;
+
+const MAX_SWEEPS: usize = 100;
+const MAX_QR_ITERATIONS: usize = 30;
+
+// Row-major flat data with an explicit shape, as the numeric operations receive it
+pub fn from_flat(data: &[f64], rows: usize, cols: usize) -> Result<Array2<f64>> {
+    if rows == 0 || cols == 0 {
+        return Err(anyhow!("Matrix dimensions must be positive, got {}x{}", rows, cols));
+    }
+    if data.len() != rows * cols {
+        return Err(anyhow!("A {}x{} matrix needs {} values, got {}", rows, cols, rows * cols, data.len()));
+    }
+    if data.iter().any(|v| !v.is_finite()) {
+        return Err(anyhow!("Matrix entries must be finite numbers"));
+    }
+    Ok(Array2::from_shape_vec((rows, cols), data.to_vec())?)
+}
+
+fn ensure_square(matrix: &Array2<f64>, action: &str) -> Result<usize> {
+    let (rows, cols) = matrix.dim();
+    if rows != cols {
+        return Err(anyhow!("Only square matrices can be {}, got {}x{}", action, rows, cols));
+    }
+    Ok(rows)
+}
+
+fn max_abs(matrix: &Array2<f64>) -> f64 {
+    matrix.iter().fold(0.0f64, |m, v| m.max(v.abs()))
+}
+
+// Gauss-Jordan elimination with partial pivoting
+pub fn inverse(matrix: &Array2<f64>) -> Result<Array2<f64>> {
+    let n = ensure_square(matrix, "inverted")?;
+    let scale = max_abs(matrix).max(f64::MIN_POSITIVE);
+    let mut a = matrix.clone();
+    let mut inv = Array2::<f64>::eye(n);
+
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .max_by(|&i, &j| a[[i, col]].abs().total_cmp(&a[[j, col]].abs()))
+            .unwrap_or(col);
+        if a[[pivot_row, col]].abs() <= SINGULAR_TOLERANCE * scale {
+            return Err(anyhow!("Matrix is singular (no usable pivot in column {})", col + 1));
+        }
+
+        if pivot_row != col {
+            for k in 0..n {
+                a.swap([pivot_row, k], [col, k]);
+                inv.swap([pivot_row, k], [col, k]);
+            }
+        }
+
+        let pivot = a[[col, col]];
+        for k in 0..n {
+            a[[col, k]] /= pivot;
+            inv[[col, k]] /= pivot;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = a[[row, col]];
+            if factor == 0.0 {
+                continue;
+            }
+            for k in 0..n {
+                a[[row, k]] -= factor * a[[col, k]];
+                inv[[row, k]] -= factor * inv[[col, k]];
+            }
+        }
+    }
+
+    Ok(inv)
+}
+
+struct LuDecomposition {
+    lu: Array2<f64>,
+    pivots: Vec<usize>,
+    sign: f64,
+    singular_column: Option<usize>,
+}
+
+// PA = LU with partial pivoting, L (unit diagonal) and U packed into one matrix
+fn lu_decompose(matrix: &Array2<f64>) -> Result<LuDecomposition> {
+    let n = ensure_square(matrix, "factorised")?;
+    let scale = max_abs(matrix).max(f64::MIN_POSITIVE);
+    let mut lu = matrix.clone();
+    let mut pivots: Vec<usize> = (0..n).collect();
+    let mut sign = 1.0;
+    let mut singular_column = None;
+
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .max_by(|&i, &j| lu[[i, col]].abs().total_cmp(&lu[[j, col]].abs()))
+            .unwrap_or(col);
+        if lu[[pivot_row, col]].abs() <= SINGULAR_TOLERANCE * scale {
+            singular_column.get_or_insert(col);
+            continue;
+        }
+        if pivot_row != col {
+            for k in 0..n {
+                lu.swap([pivot_row, k], [col, k]);
+            }
+            pivots.swap(pivot_row, col);
+            sign = -sign;
+        }
+
+        for row in (col + 1)..n {
+            let factor = lu[[row, col]] / lu[[col, col]];
+            lu[[row, col]] = factor;
+            for k in (col + 1)..n {
+                lu[[row, k]] -= factor * lu[[col, k]];
+            }
+        }
+    }
+
+    Ok(LuDecomposition { lu, pivots, sign, singular_column })
+}
+
+pub fn determinant(matrix: &Array2<f64>) -> Result<f64> {
+    let decomposition = lu_decompose(matrix)?;
+    if decomposition.singular_column.is_some() {
+        return Ok(0.0);
+    }
+    Ok(decomposition.sign * decomposition.lu.diag().product())
+}
+
+// Solves AX = B for every column of B
+pub fn solve(a: &Array2<f64>, b: &Array2<f64>) -> Result<Array2<f64>> {
+    let n = ensure_square(a, "solved against")?;
+    if b.nrows() != n {
+        return Err(anyhow!("Right-hand side has {} rows but the matrix has {}", b.nrows(), n));
+    }
+
+    let LuDecomposition { lu, pivots, singular_column, .. } = lu_decompose(a)?;
+    if let Some(col) = singular_column {
+        return Err(anyhow!("Matrix is singular (no usable pivot in column {}); the system has no unique solution", col + 1));
+    }
+
+    let mut x = Array2::<f64>::zeros(b.dim());
+    for (row, &source) in pivots.iter().enumerate() {
+        x.row_mut(row).assign(&b.row(source));
+    }
+    for col in 0..b.ncols() {
+        for i in 0..n {
+            let sum: f64 = (0..i).map(|k| lu[[i, k]] * x[[k, col]]).sum();
+            x[[i, col]] -= sum;
+        }
+        for i in (0..n).rev() {
+            let sum: f64 = ((i + 1)..n).map(|k| lu[[i, k]] * x[[k, col]]).sum();
+            x[[i, col]] = (x[[i, col]] - sum) / lu[[i, i]];
+        }
+    }
+    Ok(x)
+}
+
+// Ratio of the largest to the smallest singular value; infinite for rank-deficient matrices
+pub fn condition_number(matrix: &Array2<f64>) -> Result<f64> {
+    let singular_values = svd(matrix)?.singular_values;
+    let largest = singular_values.first().copied().unwrap_or(0.0);
+    let smallest = singular_values.last().copied().unwrap_or(0.0);
+    Ok(if smallest > 0.0 { largest / smallest } else { f64::INFINITY })
+}
+
+// Guard for inverse and solve requests, where an ill-conditioned input silently yields noise
+pub fn ensure_well_conditioned(matrix: &Array2<f64>) -> Result<f64> {
+    let condition = condition_number(matrix)?;
+    if condition > MAX_CONDITION_NUMBER {
+        return Err(anyhow!(
+            "Matrix is singular or ill-conditioned (condition number {:.3e} exceeds {:.0e}); results would be dominated by rounding error",
+            condition, MAX_CONDITION_NUMBER
+        ));
+    }
+    Ok(condition)
+}
+
+pub struct SingularValueDecomposition {
+    pub u: Array2<f64>,
+    pub singular_values: Vec<f64>,
+    pub vt: Array2<f64>,
+}
+
+// Thin SVD (A = U diag(S) Vt, k = min(rows, cols)) by one-sided Jacobi rotations;
+// singular values come back in descending order. Columns of U for zero singular values are left zero
+pub fn svd(matrix: &Array2<f64>) -> Result<SingularValueDecomposition> {
+    let (rows, cols) = matrix.dim();
+    if rows < cols {
+        let transposed = svd(&matrix.t().to_owned())?;
+        return Ok(SingularValueDecomposition {
+            u: transposed.vt.t().to_owned(),
+            singular_values: transposed.singular_values,
+            vt: transposed.u.t().to_owned(),
+        });
+    }
+
+    let mut u = matrix.clone();
+    let mut v = Array2::<f64>::eye(cols);
+    let mut converged = false;
+
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..cols {
+            for q in (p + 1)..cols {
+                let alpha = u.column(p).dot(&u.column(p));
+                let beta = u.column(q).dot(&u.column(q));
+                let gamma = u.column(p).dot(&u.column(q));
+                if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() || gamma == 0.0 {
+                    continue;
+                }
+                rotated = true;
+
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                rotate_columns(&mut u, p, q, c, s);
+                rotate_columns(&mut v, p, q, c, s);
+            }
+        }
+        if !rotated {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return Err(anyhow!("SVD did not converge after {} sweeps", MAX_SWEEPS));
+    }
+
+    let norms: Vec<f64> = (0..cols).map(|j| u.column(j).dot(&u.column(j)).sqrt()).collect();
+    let mut order: Vec<usize> = (0..cols).collect();
+    order.sort_by(|&a, &b| norms[b].total_cmp(&norms[a]));
+
+    let tolerance = f64::EPSILON * rows as f64 * norms[order[0]];
+    let mut sorted_u = Array2::<f64>::zeros((rows, cols));
+    let mut vt = Array2::<f64>::zeros((cols, cols));
+    let mut singular_values = Vec::with_capacity(cols);
+    for (target, &source) in order.iter().enumerate() {
+        let sigma = norms[source];
+        if sigma > tolerance {
+            sorted_u.column_mut(target).assign(&(&u.column(source) / sigma));
+            singular_values.push(sigma);
+        } else {
+            singular_values.push(0.0);
+        }
+        vt.row_mut(target).assign(&v.column(source));
+    }
+
+    Ok(SingularValueDecomposition { u: sorted_u, singular_values, vt })
+}
+
+fn rotate_columns(matrix: &mut Array2<f64>, p: usize, q: usize, c: f64, s: f64) {
+    for k in 0..matrix.nrows() {
+        let (mp, mq) = (matrix[[k, p]], matrix[[k, q]]);
+        matrix[[k, p]] = c * mp - s * mq;
+        matrix[[k, q]] = s * mp + c * mq;
+    }
+}
+
+pub struct EigenDecomposition {
+    pub real: Vec<f64>,
+    pub imaginary: Vec<f64>,
+    // Eigenvectors as columns, matching the order of the values; only computed for symmetric input
+    pub vectors: Option<Array2<f64>>,
+}
+
+// Symmetric matrices use the Jacobi method (real values and orthonormal vectors); anything else is
+// reduced to Hessenberg form and run through the shifted QR algorithm, which may give complex pairs.
+// Values are sorted by descending real part
+pub fn eigen(matrix: &Array2<f64>) -> Result<EigenDecomposition> {
+    let n = ensure_square(matrix, "decomposed into eigenvalues")?;
+    let scale = max_abs(matrix);
+    let symmetric = (0..n).all(|i| (0..i).all(|j| (matrix[[i, j]] - matrix[[j, i]]).abs() <= SINGULAR_TOLERANCE * scale));
+
+    let (real, imaginary, vectors) = if symmetric {
+        let (values, vectors) = symmetric_eigen(matrix)?;
+        (values, vec![0.0; n], Some(vectors))
+    } else {
+        let (real, imaginary) = hessenberg_qr_eigenvalues(matrix)?;
+        (real, imaginary, None)
+    };
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| real[b].total_cmp(&real[a]).then(imaginary[b].total_cmp(&imaginary[a])));
+
+    Ok(EigenDecomposition {
+        real: order.iter().map(|&i| real[i]).collect(),
+        imaginary: order.iter().map(|&i| imaginary[i]).collect(),
+        vectors: vectors.map(|v| {
+            let mut sorted = Array2::<f64>::zeros((n, n));
+            for (target, &source) in order.iter().enumerate() {
+                sorted.column_mut(target).assign(&v.column(source));
+            }
+            sorted
+        }),
+    })
+}
+
+// Cyclic Jacobi rotations until the off-diagonal mass is negligible
+fn symmetric_eigen(matrix: &Array2<f64>) -> Result<(Vec<f64>, Array2<f64>)> {
+    let n = matrix.nrows();
+    let mut a = matrix.clone();
+    let mut v = Array2::<f64>::eye(n);
+    let norm = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+    for _ in 0..MAX_SWEEPS {
+        let off_diagonal = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[[i, j]] * a[[i, j]])
+            .sum::<f64>()
+            .sqrt();
+        if off_diagonal <= f64::EPSILON * norm {
+            return Ok((a.diag().to_vec(), v));
+        }
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[[p, q]] == 0.0 {
+                    continue;
+                }
+                let theta = (a[[q, q]] - a[[p, p]]) / (2.0 * a[[p, q]]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                rotate_columns(&mut a, p, q, c, s);
+                for k in 0..n {
+                    let (ap, aq) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * ap - s * aq;
+                    a[[q, k]] = s * ap + c * aq;
+                }
+                rotate_columns(&mut v, p, q, c, s);
+            }
+        }
+    }
+    Err(anyhow!("Eigenvalue iteration did not converge after {} sweeps", MAX_SWEEPS))
+}
+
+// Reduction to upper Hessenberg form by stabilised elimination, then the Francis double-shift
+// QR iteration. Indices are 1-based inside this routine to follow the classic EISPACK/Numerical
+// Recipes formulation (hqr), which keeps the deflation logic recognisable
+fn hessenberg_qr_eigenvalues(matrix: &Array2<f64>) -> Result<(Vec<f64>, Vec<f64>)> {
+    let n = matrix.nrows();
+    let mut a = Array2::<f64>::zeros((n + 1, n + 1));
+    a.slice_mut(ndarray::s![1.., 1..]).assign(matrix);
+
+    for m in 2..n {
+        let mut x: f64 = 0.0;
+        let mut i = m;
+        for j in m..=n {
+            if a[[j, m - 1]].abs() > x.abs() {
+                x = a[[j, m - 1]];
+                i = j;
+            }
+        }
+        if i != m {
+            for j in (m - 1)..=n {
+                a.swap([i, j], [m, j]);
+            }
+            for j in 1..=n {
+                a.swap([j, i], [j, m]);
+            }
+        }
+        if x != 0.0 {
+            for i in (m + 1)..=n {
+                let mut y = a[[i, m - 1]];
+                if y != 0.0 {
+                    y /= x;
+                    a[[i, m - 1]] = y;
+                    for j in m..=n {
+                        a[[i, j]] -= y * a[[m, j]];
+                    }
+                    for j in 1..=n {
+                        a[[j, m]] += y * a[[j, i]];
+                    }
+                }
+            }
+        }
+    }
+    for i in 3..=n {
+        for j in 1..(i - 1) {
+            a[[i, j]] = 0.0;
+        }
+    }
+
+    let mut real = vec![0.0; n + 1];
+    let mut imaginary = vec![0.0; n + 1];
+    let mut norm = 0.0;
+    for i in 1..=n {
+        for j in (i - 1).max(1)..=n {
+            norm += a[[i, j]].abs();
+        }
+    }
+
+    let mut nn = n;
+    let mut t = 0.0;
+    while nn >= 1 {
+        let mut iterations = 0;
+        loop {
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = a[[l - 1, l - 1]].abs() + a[[l, l]].abs();
+                if s == 0.0 {
+                    s = norm;
+                }
+                if a[[l, l - 1]].abs() + s == s {
+                    a[[l, l - 1]] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+
+            let mut x = a[[nn, nn]];
+            if l == nn {
+                // One root found
+                real[nn] = x + t;
+                imaginary[nn] = 0.0;
+                nn -= 1;
+                break;
+            }
+
+            let mut y = a[[nn - 1, nn - 1]];
+            let mut w = a[[nn, nn - 1]] * a[[nn - 1, nn]];
+            if l == nn - 1 {
+                // Two roots found, real or a complex pair
+                let p = 0.5 * (y - x);
+                let q = p * p + w;
+                let mut z = q.abs().sqrt();
+                x += t;
+                if q >= 0.0 {
+                    z = p + sign_of(z, p);
+                    real[nn - 1] = x + z;
+                    real[nn] = if z != 0.0 { x - w / z } else { x + z };
+                    imaginary[nn - 1] = 0.0;
+                    imaginary[nn] = 0.0;
+                } else {
+                    real[nn - 1] = x + p;
+                    real[nn] = x + p;
+                    imaginary[nn - 1] = -z;
+                    imaginary[nn] = z;
+                }
+                nn -= 2;
+                break;
+            }
+
+            if iterations == MAX_QR_ITERATIONS {
+                return Err(anyhow!("Eigenvalue iteration did not converge after {} iterations", MAX_QR_ITERATIONS));
+            }
+            if iterations == 10 || iterations == 20 {
+                // Exceptional shift to break cycles
+                t += x;
+                for i in 1..=nn {
+                    a[[i, i]] -= x;
+                }
+                let s = a[[nn, nn - 1]].abs() + a[[nn - 1, nn - 2]].abs();
+                x = 0.75 * s;
+                y = x;
+                w = -0.4375 * s * s;
+            }
+            iterations += 1;
+
+            let mut m = nn - 2;
+            let (mut p, mut q, mut r, mut z);
+            loop {
+                z = a[[m, m]];
+                r = x - z;
+                let s = y - z;
+                p = (r * s - w) / a[[m + 1, m]] + a[[m, m + 1]];
+                q = a[[m + 1, m + 1]] - z - r - s;
+                r = a[[m + 2, m + 1]];
+                let scale = p.abs() + q.abs() + r.abs();
+                p /= scale;
+                q /= scale;
+                r /= scale;
+                if m == l {
+                    break;
+                }
+                let u = a[[m, m - 1]].abs() * (q.abs() + r.abs());
+                let v = p.abs() * (a[[m - 1, m - 1]].abs() + z.abs() + a[[m + 1, m + 1]].abs());
+                if u + v == v {
+                    break;
+                }
+                m -= 1;
+            }
+
+            for i in (m + 2)..=nn {
+                a[[i, i - 2]] = 0.0;
+                if i != m + 2 {
+                    a[[i, i - 3]] = 0.0;
+                }
+            }
+
+            for k in m..nn {
+                if k != m {
+                    p = a[[k, k - 1]];
+                    q = a[[k + 1, k - 1]];
+                    r = if k != nn - 1 { a[[k + 2, k - 1]] } else { 0.0 };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x != 0.0 {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+                let s = sign_of((p * p + q * q + r * r).sqrt(), p);
+                if s == 0.0 {
+                    continue;
+                }
+                if k == m {
+                    if l != m {
+                        a[[k, k - 1]] = -a[[k, k - 1]];
+                    }
+                } else {
+                    a[[k, k - 1]] = -s * x;
+                }
+                p += s;
+                x = p / s;
+                y = q / s;
+                z = r / s;
+                q /= p;
+                r /= p;
+                for j in k..=nn {
+                    let mut p = a[[k, j]] + q * a[[k + 1, j]];
+                    if k != nn - 1 {
+                        p += r * a[[k + 2, j]];
+                        a[[k + 2, j]] -= p * z;
+                    }
+                    a[[k + 1, j]] -= p * y;
+                    a[[k, j]] -= p * x;
+                }
+                for i in l..=nn.min(k + 3) {
+                    let mut p = x * a[[i, k]] + y * a[[i, k + 1]];
+                    if k != nn - 1 {
+                        p += z * a[[i, k + 2]];
+                        a[[i, k + 2]] -= p * r;
+                    }
+                    a[[i, k + 1]] -= p * q;
+                    a[[i, k]] -= p;
+                }
+            }
+        }
+    }
+
+    Ok((real[1..].to_vec(), imaginary[1..].to_vec()))
+}
+
+// Magnitude of a with the sign of b (Fortran SIGN)
+fn sign_of(a: f64, b: f64) -> f64 {
+    if b >= 0.0 { a.abs() } else { -a.abs() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_inverse_and_singular_error() {
+        let m = array![[4.0, 7.0], [2.0, 6.0]];
+        let inv = inverse(&m).unwrap();
+        let identity = m.dot(&inv);
+        assert!((identity[[0, 0]] - 1.0).abs() < 1e-12 && identity[[0, 1]].abs() < 1e-12);
+
+        let singular = array![[1.0, 2.0], [2.0, 4.0]];
+        assert!(inverse(&singular).unwrap_err().to_string().contains("singular"));
+    }
+
+    #[test]
+    fn test_determinant_and_solve() {
+        let a = array![[2.0, 1.0, -1.0], [-3.0, -1.0, 2.0], [-2.0, 1.0, 2.0]];
+        assert!((determinant(&a).unwrap() - (-1.0)).abs() < 1e-12);
+        assert_eq!(determinant(&array![[1.0, 2.0], [2.0, 4.0]]).unwrap(), 0.0);
+
+        let b = array![[8.0], [-11.0], [-3.0]];
+        let x = solve(&a, &b).unwrap();
+        for (got, expected) in x.iter().zip([2.0, 3.0, -1.0]) {
+            assert!((got - expected).abs() < 1e-12);
+        }
+        assert!(solve(&array![[1.0, 2.0], [2.0, 4.0]], &array![[1.0], [2.0]]).unwrap_err().to_string().contains("singular"));
+
+        // The 12x12 Hilbert matrix has a condition number around 1e16
+        let hilbert = Array2::from_shape_fn((12, 12), |(i, j)| 1.0 / (i + j + 1) as f64);
+        assert!(ensure_well_conditioned(&hilbert).unwrap_err().to_string().contains("ill-conditioned"));
+    }
+
+    #[test]
+    fn test_eigen_symmetric_and_general() {
+        let symmetric = array![[2.0, 1.0], [1.0, 2.0]];
+        let result = eigen(&symmetric).unwrap();
+        assert!((result.real[0] - 3.0).abs() < 1e-12 && (result.real[1] - 1.0).abs() < 1e-12);
+        let vectors = result.vectors.unwrap();
+        let residual = symmetric.dot(&vectors.column(0)) - &vectors.column(0) * 3.0;
+        assert!(residual.iter().all(|v| v.abs() < 1e-12));
+
+        let general = array![[4.0, 1.0, 2.0], [0.0, 3.0, 5.0], [0.0, 0.0, -1.0]];
+        let result = eigen(&general).unwrap();
+        for (got, expected) in result.real.iter().zip([4.0, 3.0, -1.0]) {
+            assert!((got - expected).abs() < 1e-9, "{:?}", result.real);
+        }
+        assert!(result.vectors.is_none());
+
+        let rotation = array![[0.0, -1.0], [1.0, 0.0]];
+        let result = eigen(&rotation).unwrap();
+        assert!(result.real.iter().all(|v| v.abs() < 1e-12));
+        assert!((result.imaginary[0].abs() - 1.0).abs() < 1e-12);
+        assert!((result.imaginary[0] + result.imaginary[1]).abs() < 1e-12);
+
+        // Eigenvalues of a larger non-symmetric matrix must sum to the trace and multiply to the determinant
+        let larger = Array2::from_shape_fn((6, 6), |(i, j)| ((i * 7 + j * 3) % 11) as f64 - 5.0);
+        let result = eigen(&larger).unwrap();
+        let trace: f64 = result.real.iter().sum();
+        assert!((trace - larger.diag().sum()).abs() < 1e-8);
+        let (mut re, mut im) = (1.0, 0.0);
+        for (a, b) in result.real.iter().zip(&result.imaginary) {
+            (re, im) = (re * a - im * b, re * b + im * a);
+        }
+        let det = determinant(&larger).unwrap();
+        assert!((re - det).abs() < 1e-6 * det.abs().max(1.0) && im.abs() < 1e-6 * det.abs().max(1.0), "{} {} {}", re, im, det);
+    }
+
+    #[test]
+    fn test_svd_reconstructs_rectangular_matrices() {
+        for matrix in [array![[3.0, 2.0], [2.0, 3.0], [2.0, -2.0]], array![[3.0, 2.0, 2.0], [2.0, 3.0, -2.0]]] {
+            let decomposition = svd(&matrix).unwrap();
+            assert!((decomposition.singular_values[0] - 5.0).abs() < 1e-12);
+            assert!((decomposition.singular_values[1] - 3.0).abs() < 1e-12);
+
+            let sigma = Array2::from_diag(&ndarray::Array1::from(decomposition.singular_values.clone()));
+            let rebuilt = decomposition.u.dot(&sigma).dot(&decomposition.vt);
+            assert!((&rebuilt - &matrix).iter().all(|v| v.abs() < 1e-12));
+        }
+        assert!(from_flat(&[1.0, 2.0, 3.0], 2, 2).is_err());
+    }
+}
@@ -1,17 +1,41 @@
 use actix_cors::Cors;
-use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder, Result};
+use actix_web::{delete, get, post, web, App, HttpResponse, HttpServer, Responder, Result};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, error};
 
+mod analytics;
 mod data_processor;
+mod dataset;
+mod distributions;
+mod excel;
+mod export;
+mod forecasting;
 mod workflow_engine;
 mod advanced_formulas;
+mod binning;
+mod casting;
+mod compute;
+mod fuzzy_matching;
+mod hypothesis;
+mod linalg;
+mod outliers;
+mod row_filter;
+mod row_sort;
+mod sampling;
+mod scaling;
+mod streaming;
 // mod database;  // Commented out for initial build
 mod models;
+mod profiling;
+mod quantiles;
+mod registry;
+mod timeseries;
 
-use data_processor::DataProcessor;
+use data_processor::{dataframe_to_records, dataframe_to_rows, rows_to_dataframe, ColumnOperations, DataProcessor};
+use dataset::{DatasetFormat, DatasetStore, LoadOptions};
 use workflow_engine::{WorkflowEngine, WorkflowStep};
+use compute::run_blocking;
 use advanced_formulas::{AdvancedFormulaProcessor, AdvancedFormulaRequest, FormulaResult};
 // use database::Database;  // Commented out for initial build
 
@@ -20,6 +44,7 @@ struct AppState {
     data_processor: Arc<DataProcessor>,
     workflow_engine: Arc<WorkflowEngine>,
     advanced_formula_processor: Arc<AdvancedFormulaProcessor>,
+    dataset_store: Arc<DatasetStore>,
     // database: Arc<Database>,  // Commented out for initial build
 }
 
@@ -47,6 +72,76 @@ struct DataResponse {
     timestamp: String,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+struct DataFrameRequest {
+    csv_data: Option<String>,
+    dataset_id: Option<String>,
+    operations: Vec<String>,
+    parameters: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ColumnOperationsRequest {
+    csv_data: Option<String>,
+    dataset_id: Option<String>,
+    #[serde(flatten)]
+    spec: ColumnOperations,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct LoadDatasetRequest {
+    path: String,
+    name: Option<String>,
+    options: Option<LoadOptions>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct UploadDatasetQuery {
+    name: Option<String>,
+    format: Option<DatasetFormat>,
+    // JSON-encoded LoadOptions
+    options: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct DatasetRowsQuery {
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ExportRequest {
+    path: String,
+    dataset_id: Option<String>,
+    data: Option<Vec<std::collections::HashMap<String, serde_json::Value>>>,
+    columns: Option<Vec<String>>,
+    options: Option<export::ExportOptions>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct StreamRequest {
+    path: String,
+    options: Option<LoadOptions>,
+    #[serde(flatten)]
+    query: streaming::StreamQuery,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ExcelExportRequest {
+    path: String,
+    sheets: Vec<ExcelSheetRequest>,
+    // Existing files are only replaced on request, as with /export
+    #[serde(default)]
+    overwrite: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ExcelSheetRequest {
+    dataset_id: String,
+    name: Option<String>,
+    column_formats: Option<std::collections::HashMap<String, String>>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 struct WorkflowRequest {
     name: String,
@@ -137,6 +232,111 @@ async fn process_data(
     }
 }
 
+// DataFrame processing endpoint
+#[post("/process-dataframe")]
+async fn process_dataframe(
+    req: web::Json<DataFrameRequest>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let start_time = std::time::Instant::now();
+    
+    info!("Processing DataFrame request: operations={:?}", req.operations);
+    
+    let result = match (&req.dataset_id, &req.csv_data) {
+        (Some(dataset_id), _) => match state.dataset_store.get(dataset_id).await {
+            Some(df) => state.data_processor.process_loaded_dataframe(&df, &req.operations, req.parameters.as_ref()).await,
+            None => return Ok(dataset_not_found(dataset_id)),
+        },
+        (None, Some(csv_data)) => state.data_processor.process_dataframe(csv_data, &req.operations, req.parameters.as_ref()).await,
+        (None, None) => {
+            let response = serde_json::json!({
+                "status": "error",
+                "error": "Either csv_data or dataset_id is required",
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            return Ok(HttpResponse::BadRequest().json(response));
+        }
+    };
+    
+    match result {
+        Ok(result) => {
+            let processing_time = start_time.elapsed().as_millis() as u64;
+            
+            let response = DataResponse {
+                status: "success".to_string(),
+                result,
+                processing_time_ms: processing_time,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            };
+            
+            info!("DataFrame processing completed successfully in {}ms", processing_time);
+            Ok(HttpResponse::Ok().json(response))
+        }
+        Err(e) => {
+            error!("DataFrame processing failed: {}", e);
+            let response = serde_json::json!({
+                "status": "error",
+                "error": e.to_string(),
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            Ok(HttpResponse::InternalServerError().json(response))
+        }
+    }
+}
+
+// Column-aware operations over a loaded dataset or inline CSV
+#[post("/process-columns")]
+async fn process_columns(
+    req: web::Json<ColumnOperationsRequest>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let start_time = std::time::Instant::now();
+    
+    info!("Processing column operations: operations={:?}, columns={:?}", req.spec.operations, req.spec.columns);
+    
+    let df = match (&req.dataset_id, &req.csv_data) {
+        (Some(dataset_id), _) => match state.dataset_store.get(dataset_id).await {
+            Some(df) => Ok(df),
+            None => return Ok(dataset_not_found(dataset_id)),
+        },
+        (None, Some(csv_data)) => {
+            let bytes = csv_data.as_bytes().to_vec();
+            run_blocking(move || dataset::load_from_bytes(bytes, DatasetFormat::Csv, &LoadOptions::default())).await
+        }
+        (None, None) => Err(anyhow::anyhow!("Either csv_data or dataset_id is required")),
+    };
+    
+    let result = match df {
+        Ok(df) => state.data_processor.process_columns(&df, &req.spec).await,
+        Err(e) => Err(e),
+    };
+    
+    match result {
+        Ok(result) => {
+            let processing_time = start_time.elapsed().as_millis() as u64;
+            
+            let response = DataResponse {
+                status: "success".to_string(),
+                result,
+                processing_time_ms: processing_time,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            };
+            
+            info!("Column operations completed successfully in {}ms", processing_time);
+            Ok(HttpResponse::Ok().json(response))
+        }
+        Err(e) => {
+            error!("Column operations failed: {}", e);
+            let response = serde_json::json!({
+                "status": "error",
+                "error": e.to_string(),
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            Ok(HttpResponse::BadRequest().json(response))
+        }
+    }
+}
+
 // Workflow execution endpoint
 #[post("/execute-workflow")]
 async fn execute_workflow(
@@ -196,6 +396,29 @@ async fn process_advanced_formula(
     state: web::Data<AppState>,
 ) -> Result<impl Responder> {
     let start_time = std::time::Instant::now();
+    let mut req = req.into_inner();
+    
+    // Formulas can run against a loaded dataset instead of inline rows
+    if let Some(dataset_id) = req.dataset_id.clone() {
+        if req.data.is_empty() {
+            let df = match state.dataset_store.get(&dataset_id).await {
+                Some(df) => df,
+                None => return Ok(dataset_not_found(&dataset_id)),
+            };
+            match dataframe_to_rows(&df) {
+                Ok(rows) => req.data = rows,
+                Err(e) => {
+                    error!("Failed to read dataset {}: {}", dataset_id, e);
+                    let response = serde_json::json!({
+                        "status": "error",
+                        "error": e.to_string(),
+                        "timestamp": chrono::Utc::now().to_rfc3339()
+                    });
+                    return Ok(HttpResponse::InternalServerError().json(response));
+                }
+            }
+        }
+    }
     
     info!("Processing advanced formula: {} with {} rows", 
           req.formula_type, req.data.len());
@@ -212,7 +435,7 @@ async fn process_advanced_formula(
     }
     
     // Process the advanced formula
-    match state.advanced_formula_processor.process_advanced_formula(req.into_inner()).await {
+    match state.advanced_formula_processor.process_advanced_formula(req).await {
         Ok(result) => {
             let processing_time = start_time.elapsed().as_millis() as u64;
             
@@ -249,6 +472,355 @@ async fn get_supported_formulas(
     Ok(HttpResponse::Ok().json(response))
 }
 
+// Operation discovery endpoint: names, categories and parameter schemas of the value operations
+// and of the table operations run by /process-dataframe
+#[get("/operations")]
+async fn get_operations(
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let operations = state.data_processor.operation_schemas();
+    let table_operations = state.data_processor.table_operation_schemas();
+    
+    let response = serde_json::json!({
+        "status": "success",
+        "operations": operations,
+        "count": operations.len(),
+        "table_operations": table_operations,
+        "table_count": table_operations.len(),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    });
+    
+    info!("Retrieved {} operation and {} table operation schemas", operations.len(), table_operations.len());
+    Ok(HttpResponse::Ok().json(response))
+}
+
+// Dataset loading endpoints
+#[post("/datasets/load")]
+async fn load_dataset(
+    req: web::Json<LoadDatasetRequest>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let start_time = std::time::Instant::now();
+    let options = req.options.clone().unwrap_or_default();
+    let path = std::path::Path::new(&req.path);
+    
+    info!("Loading dataset from path: {}", req.path);
+    
+    let (load_path, load_options) = (path.to_path_buf(), options.clone());
+    match run_blocking(move || dataset::load_from_path(&load_path, &load_options)).await {
+        Ok(df) => {
+            let format = options.format.or_else(|| DatasetFormat::from_path(path))
+                .map(|f| f.as_str())
+                .unwrap_or("unknown");
+            let name = req.name.clone().unwrap_or_else(|| {
+                path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| req.path.clone())
+            });
+            Ok(dataset_loaded(&state, &name, &req.path, format, df, start_time).await)
+        }
+        Err(e) => {
+            error!("Dataset load failed: {}", e);
+            Ok(dataset_load_failed(e))
+        }
+    }
+}
+
+#[post("/datasets/upload")]
+async fn upload_dataset(
+    body: web::Bytes,
+    query: web::Query<UploadDatasetQuery>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let start_time = std::time::Instant::now();
+    
+    info!("Loading uploaded dataset: {} bytes", body.len());
+    
+    let options: LoadOptions = match query.options.as_deref().map(serde_json::from_str).transpose() {
+        Ok(options) => options.unwrap_or_default(),
+        Err(e) => {
+            let response = serde_json::json!({
+                "status": "error",
+                "error": format!("Invalid load options: {}", e),
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            return Ok(HttpResponse::BadRequest().json(response));
+        }
+    };
+    let format = query.format.or(options.format).unwrap_or(DatasetFormat::Csv);
+    
+    let load_options = options.clone();
+    match run_blocking(move || dataset::load_from_bytes(body.to_vec(), format, &load_options)).await {
+        Ok(df) => {
+            let name = query.name.clone().unwrap_or_else(|| "upload".to_string());
+            Ok(dataset_loaded(&state, &name, "upload", format.as_str(), df, start_time).await)
+        }
+        Err(e) => {
+            error!("Dataset upload failed: {}", e);
+            Ok(dataset_load_failed(e))
+        }
+    }
+}
+
+#[get("/datasets")]
+async fn list_datasets(state: web::Data<AppState>) -> Result<impl Responder> {
+    let datasets = state.dataset_store.list().await;
+    
+    let response = serde_json::json!({
+        "status": "success",
+        "datasets": datasets,
+        "count": datasets.len(),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    });
+    
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[get("/datasets/{dataset_id}")]
+async fn get_dataset(
+    path: web::Path<String>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let dataset_id = path.into_inner();
+    match state.dataset_store.summary(&dataset_id).await {
+        Some(summary) => {
+            let response = serde_json::json!({
+                "status": "success",
+                "dataset": summary,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            Ok(HttpResponse::Ok().json(response))
+        }
+        None => Ok(dataset_not_found(&dataset_id)),
+    }
+}
+
+#[delete("/datasets/{dataset_id}")]
+async fn delete_dataset(
+    path: web::Path<String>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let dataset_id = path.into_inner();
+    if !state.dataset_store.remove(&dataset_id).await {
+        return Ok(dataset_not_found(&dataset_id));
+    }
+    
+    info!("Dataset {} removed", dataset_id);
+    let response = serde_json::json!({
+        "status": "success",
+        "dataset_id": dataset_id,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    });
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[get("/datasets/{dataset_id}/rows")]
+async fn get_dataset_rows(
+    path: web::Path<String>,
+    query: web::Query<DatasetRowsQuery>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let dataset_id = path.into_inner();
+    let df = match state.dataset_store.get(&dataset_id).await {
+        Some(df) => df,
+        None => return Ok(dataset_not_found(&dataset_id)),
+    };
+    
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(100);
+    let page = df.slice(offset as i64, limit);
+    
+    match dataframe_to_records(&page) {
+        Ok(rows) => {
+            let response = serde_json::json!({
+                "status": "success",
+                "dataset_id": dataset_id,
+                "offset": offset,
+                "total_rows": df.height(),
+                "rows": rows,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            Ok(HttpResponse::Ok().json(response))
+        }
+        Err(e) => {
+            error!("Failed to read dataset rows: {}", e);
+            Ok(dataset_load_failed(e))
+        }
+    }
+}
+
+// Export endpoints
+#[post("/export")]
+async fn export_table(
+    req: web::Json<ExportRequest>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let start_time = std::time::Instant::now();
+    
+    info!("Exporting table to file: {}", req.path);
+    
+    let frame = match (&req.dataset_id, &req.data) {
+        (Some(dataset_id), _) => match state.dataset_store.get(dataset_id).await {
+            Some(df) => Ok(df),
+            None => return Ok(dataset_not_found(dataset_id)),
+        },
+        (None, Some(rows)) => rows_to_dataframe(rows, req.columns.as_deref()),
+        (None, None) => Err(anyhow::anyhow!("Export requires either 'dataset_id' or 'data'")),
+    };
+    
+    let options = req.options.clone().unwrap_or_default();
+    let path = req.path.clone();
+    let exported = match frame {
+        Ok(mut df) => run_blocking(move || export::export_dataframe(&mut df, std::path::Path::new(&path), &options)).await,
+        Err(e) => Err(e),
+    };
+    
+    match exported {
+        Ok(summary) => {
+            let processing_time = start_time.elapsed().as_millis() as u64;
+            let response = serde_json::json!({
+                "status": "success",
+                "export": summary,
+                "processing_time_ms": processing_time,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            Ok(HttpResponse::Ok().json(response))
+        }
+        Err(e) => {
+            error!("Export failed: {}", e);
+            Ok(dataset_load_failed(e))
+        }
+    }
+}
+
+// Streaming endpoint: queries a local CSV/Parquet file in batches without loading it into memory
+#[post("/stream")]
+async fn stream_file(req: web::Json<StreamRequest>) -> Result<impl Responder> {
+    let start_time = std::time::Instant::now();
+    let options = req.options.clone().unwrap_or_default();
+    
+    info!("Streaming query over file: {}", req.path);
+    
+    // A scan over a multi-gigabyte file can take minutes, so it never runs on the HTTP worker
+    let (path, query) = (req.path.clone(), req.query.clone());
+    match run_blocking(move || streaming::run(std::path::Path::new(&path), &options, &query)).await {
+        Ok(result) => {
+            let processing_time = start_time.elapsed().as_millis() as u64;
+            info!("Streaming query over {} produced {} rows in {}ms", req.path, result.rows, processing_time);
+            
+            let response = serde_json::json!({
+                "status": "success",
+                "result": result,
+                "processing_time_ms": processing_time,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            Ok(HttpResponse::Ok().json(response))
+        }
+        Err(e) => {
+            error!("Streaming query failed: {}", e);
+            Ok(dataset_load_failed(e))
+        }
+    }
+}
+
+#[post("/export/excel")]
+async fn export_excel(
+    req: web::Json<ExcelExportRequest>,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let start_time = std::time::Instant::now();
+    
+    info!("Exporting {} sheets to Excel file: {}", req.sheets.len(), req.path);
+    
+    let mut sheets = Vec::with_capacity(req.sheets.len());
+    for (index, sheet) in req.sheets.iter().enumerate() {
+        let frame = match state.dataset_store.get(&sheet.dataset_id).await {
+            Some(df) => df,
+            None => return Ok(dataset_not_found(&sheet.dataset_id)),
+        };
+        sheets.push(excel::SheetExport {
+            name: sheet.name.clone().unwrap_or_else(|| format!("Sheet{}", index + 1)),
+            frame,
+            column_formats: sheet.column_formats.clone().unwrap_or_default(),
+        });
+    }
+    
+    let sheet_summaries: Vec<serde_json::Value> = sheets.iter()
+        .map(|s| serde_json::json!({ "name": s.name, "rows": s.frame.height(), "columns": s.frame.width() }))
+        .collect();
+    
+    let (path, overwrite) = (req.path.clone(), req.overwrite);
+    let written = run_blocking(move || {
+        let path = std::path::Path::new(&path);
+        if path.exists() && !overwrite {
+            return Err(anyhow::anyhow!("'{}' already exists; set overwrite to replace it", path.display()));
+        }
+        let bytes = excel::workbook_bytes(&sheets)?;
+        export::write_atomic(path, &bytes, overwrite).map(|_| bytes.len())
+    }).await;
+    
+    match written {
+        Ok(bytes) => {
+            let processing_time = start_time.elapsed().as_millis() as u64;
+            
+            info!("Excel export written to {} ({} bytes) in {}ms", req.path, bytes, processing_time);
+            
+            let response = serde_json::json!({
+                "status": "success",
+                "path": req.path,
+                "format": DatasetFormat::Excel.as_str(),
+                "bytes": bytes,
+                "sheets": sheet_summaries,
+                "processing_time_ms": processing_time,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            Ok(HttpResponse::Ok().json(response))
+        }
+        Err(e) => {
+            error!("Excel export failed: {}", e);
+            Ok(dataset_load_failed(e))
+        }
+    }
+}
+
+async fn dataset_loaded(
+    state: &AppState,
+    name: &str,
+    source: &str,
+    format: &str,
+    df: polars::prelude::DataFrame,
+    start_time: std::time::Instant,
+) -> HttpResponse {
+    let summary = state.dataset_store.insert(name, source, format, df).await;
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    
+    info!("Dataset {} loaded with {} rows in {}ms", summary.id, summary.rows, processing_time);
+    
+    let response = serde_json::json!({
+        "status": "success",
+        "dataset": summary,
+        "processing_time_ms": processing_time,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    });
+    HttpResponse::Ok().json(response)
+}
+
+fn dataset_load_failed(e: anyhow::Error) -> HttpResponse {
+    let response = serde_json::json!({
+        "status": "error",
+        "error": e.to_string(),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    });
+    HttpResponse::BadRequest().json(response)
+}
+
+fn dataset_not_found(dataset_id: &str) -> HttpResponse {
+    let response = serde_json::json!({
+        "status": "error",
+        "error": format!("Dataset {} not found", dataset_id),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    });
+    HttpResponse::NotFound().json(response)
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Initialize logging
@@ -260,15 +832,23 @@ async fn main() -> std::io::Result<()> {
     info!("🔄 Workflow Engine: Temporal + custom");
     
     // Initialize components
-    let data_processor = Arc::new(DataProcessor::new().await);
+    let data_processor = match DataProcessor::new().await {
+        Ok(processor) => Arc::new(processor),
+        Err(e) => {
+            error!("Failed to initialize data processor: {}", e);
+            return Err(std::io::Error::other(e.to_string()));
+        }
+    };
     let workflow_engine = Arc::new(WorkflowEngine::new().await);
     let advanced_formula_processor = Arc::new(AdvancedFormulaProcessor::new());
+    let dataset_store = Arc::new(DatasetStore::new());
     // let database = Arc::new(Database::new().await);  // Commented out for initial build
     
     let app_state = web::Data::new(AppState {
         data_processor,
         workflow_engine,
         advanced_formula_processor,
+        dataset_store,
         // database,  // Commented out for initial build
     });
     
@@ -286,13 +866,26 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .wrap(cors)
             .app_data(app_state.clone())
+            .app_data(web::PayloadConfig::new(512 * 1024 * 1024))
             .service(health_check)
             .service(root)
             .service(process_data)
+            .service(process_dataframe)
+            .service(process_columns)
             .service(execute_workflow)
             .service(test)
             .service(process_advanced_formula)
             .service(get_supported_formulas)
+            .service(get_operations)
+            .service(load_dataset)
+            .service(upload_dataset)
+            .service(list_datasets)
+            .service(get_dataset)
+            .service(delete_dataset)
+            .service(get_dataset_rows)
+            .service(export_table)
+            .service(export_excel)
+            .service(stream_file)
     })
     .bind("127.0.0.1:5002")?
     .run()
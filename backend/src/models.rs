@@ -119,6 +119,7 @@ pub enum DataSourceType {
     Csv,
     Json,
     Parquet,
+    Excel,
     Database,
     Api,
     Stream,
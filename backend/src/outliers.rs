@@ -0,0 +1,230 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::quantiles::{quantile_sorted, QuantileMethod};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlierMethod {
+    #[default]
+    ZScore,
+    #[serde(alias = "mad")]
+    ModifiedZScore,
+    Iqr,
+    Rolling,
+}
+
+impl OutlierMethod {
+    // Conventional cut-offs: |z| > 3, |modified z| > 3.5 (Iglewicz & Hoaglin), Tukey's 1.5 x IQR
+    fn default_threshold(&self) -> f64 {
+        match self {
+            OutlierMethod::ZScore | OutlierMethod::Rolling => 3.0,
+            OutlierMethod::ModifiedZScore => 3.5,
+            OutlierMethod::Iqr => 1.5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct OutlierOptions {
+    pub method: OutlierMethod,
+    pub threshold: Option<f64>,
+    // Rolling method: number of preceding values each point is compared against
+    pub window: usize,
+}
+
+impl Default for OutlierOptions {
+    fn default() -> Self {
+        Self {
+            method: OutlierMethod::ZScore,
+            threshold: None,
+            window: 20,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OutlierReport {
+    pub method: OutlierMethod,
+    pub threshold: f64,
+    // Value bounds beyond which points are flagged; per-point for the rolling method, so omitted there
+    pub lower_bound: Option<f64>,
+    pub upper_bound: Option<f64>,
+    pub indices: Vec<usize>,
+    // One score per input value; null where no score is defined (NaN input, warm-up of a rolling window)
+    pub scores: Vec<Option<f64>>,
+    pub outlier_count: usize,
+    pub warnings: Vec<String>,
+}
+
+pub fn detect_outliers(values: &[f64], options: &OutlierOptions) -> Result<OutlierReport> {
+    let threshold = options.threshold.unwrap_or_else(|| options.method.default_threshold());
+    if !threshold.is_finite() || threshold <= 0.0 {
+        return Err(anyhow!("Outlier threshold must be a positive number, got {}", threshold));
+    }
+
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(f64::total_cmp);
+    let mut warnings = Vec::new();
+
+    let (scores, lower_bound, upper_bound) = if sorted.is_empty() {
+        warnings.push("No finite values to score".to_string());
+        (vec![None; values.len()], None, None)
+    } else {
+        match options.method {
+            OutlierMethod::ZScore => {
+                let n = sorted.len() as f64;
+                let mean = sorted.iter().sum::<f64>() / n;
+                let std = if sorted.len() > 1 {
+                    (sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+                } else {
+                    0.0
+                };
+                if std == 0.0 {
+                    warnings.push("Standard deviation is zero; no value can be flagged".to_string());
+                }
+                let scores = score_each(values, |v| if std > 0.0 { (v - mean) / std } else { 0.0 });
+                (scores, Some(mean - threshold * std), Some(mean + threshold * std))
+            }
+            OutlierMethod::ModifiedZScore => {
+                let median = quantile_sorted(&sorted, 0.5, QuantileMethod::Linear);
+                let mut deviations: Vec<f64> = sorted.iter().map(|v| (v - median).abs()).collect();
+                deviations.sort_by(f64::total_cmp);
+                let mad = quantile_sorted(&deviations, 0.5, QuantileMethod::Linear);
+
+                // With MAD = 0 (over half the values identical) fall back to the scaled mean absolute deviation
+                let scale = if mad > 0.0 {
+                    mad / 0.6745
+                } else {
+                    let mean_ad = deviations.iter().sum::<f64>() / deviations.len() as f64;
+                    if mean_ad > 0.0 {
+                        warnings.push("MAD is zero; using the mean absolute deviation instead".to_string());
+                    } else {
+                        warnings.push("All values are identical; no value can be flagged".to_string());
+                    }
+                    mean_ad * 1.253314
+                };
+                let scores = score_each(values, |v| if scale > 0.0 { (v - median) / scale } else { 0.0 });
+                (scores, Some(median - threshold * scale), Some(median + threshold * scale))
+            }
+            OutlierMethod::Iqr => {
+                let q1 = quantile_sorted(&sorted, 0.25, QuantileMethod::Linear);
+                let q3 = quantile_sorted(&sorted, 0.75, QuantileMethod::Linear);
+                let iqr = q3 - q1;
+                let (lower, upper) = (q1 - threshold * iqr, q3 + threshold * iqr);
+                if iqr == 0.0 {
+                    warnings.push("IQR is zero; every value outside the quartiles is flagged".to_string());
+                }
+
+                // Distance past the nearer fence in IQR units, zero inside the fences
+                let scores = score_each(values, |v| {
+                    let distance = if v < lower { v - lower } else if v > upper { v - upper } else { 0.0 };
+                    if iqr > 0.0 { distance / iqr } else { distance }
+                });
+                (scores, Some(lower), Some(upper))
+            }
+            OutlierMethod::Rolling => {
+                if options.window < 2 {
+                    return Err(anyhow!("Rolling window must be at least 2, got {}", options.window));
+                }
+                (rolling_scores(values, options.window), None, None)
+            }
+        }
+    };
+
+    let indices: Vec<usize> = scores.iter()
+        .enumerate()
+        .filter(|(_, score)| match (options.method, score) {
+            (OutlierMethod::Iqr, Some(score)) => *score != 0.0,
+            (_, Some(score)) => score.abs() > threshold,
+            (_, None) => false,
+        })
+        .map(|(i, _)| i)
+        .collect();
+
+    Ok(OutlierReport {
+        method: options.method,
+        threshold,
+        lower_bound,
+        upper_bound,
+        outlier_count: indices.len(),
+        indices,
+        scores,
+        warnings,
+    })
+}
+
+fn score_each(values: &[f64], score: impl Fn(f64) -> f64) -> Vec<Option<f64>> {
+    values.iter().map(|&v| v.is_finite().then(|| score(v))).collect()
+}
+
+// Z-score of each point against the mean and standard deviation of the preceding window,
+// so a spike does not inflate its own baseline; needs at least half a window of history
+fn rolling_scores(values: &[f64], window: usize) -> Vec<Option<f64>> {
+    let min_history = (window / 2).max(2);
+
+    (0..values.len())
+        .map(|i| {
+            if !values[i].is_finite() {
+                return None;
+            }
+            let history: Vec<f64> = values[i.saturating_sub(window)..i].iter()
+                .copied()
+                .filter(|v| v.is_finite())
+                .collect();
+            if history.len() < min_history {
+                return None;
+            }
+
+            let n = history.len() as f64;
+            let mean = history.iter().sum::<f64>() / n;
+            let std = (history.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+            Some(if std > 0.0 {
+                (values[i] - mean) / std
+            } else if values[i] == mean {
+                0.0
+            } else {
+                f64::INFINITY.copysign(values[i] - mean)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(values: &[f64], method: OutlierMethod) -> OutlierReport {
+        detect_outliers(values, &OutlierOptions { method, ..Default::default() }).unwrap()
+    }
+
+    #[test]
+    fn test_static_methods_flag_the_spike() {
+        let mut values = vec![10.0, 10.4, 9.6, 10.5, 9.5, 10.0, 10.2, 9.8, 10.1, 9.9, 10.0, 10.3];
+        values.push(50.0);
+        values.push(f64::NAN);
+
+        assert_eq!(run(&values, OutlierMethod::ZScore).indices, vec![12]);
+        assert_eq!(run(&values, OutlierMethod::ModifiedZScore).indices, vec![12]);
+
+        let iqr = run(&values, OutlierMethod::Iqr);
+        assert_eq!(iqr.indices, vec![12]);
+        assert!(iqr.upper_bound.unwrap() < 50.0);
+        assert_eq!(iqr.scores[13], None);
+    }
+
+    #[test]
+    fn test_rolling_and_degenerate_inputs() {
+        let values: Vec<f64> = (0..30).map(|i| if i == 25 { 40.0 } else { (i % 3) as f64 }).collect();
+        let options = OutlierOptions { method: OutlierMethod::Rolling, window: 10, ..Default::default() };
+        let report = detect_outliers(&values, &options).unwrap();
+        assert_eq!(report.indices, vec![25]);
+        assert_eq!(report.scores[0], None);
+
+        let constant = run(&[5.0, 5.0, 5.0], OutlierMethod::ZScore);
+        assert!(constant.indices.is_empty());
+        assert!(!constant.warnings.is_empty());
+        assert!(run(&[], OutlierMethod::ModifiedZScore).indices.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::Result;
+use polars::prelude::*;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static EMAIL_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Za-z0-9._%+\-]+@[A-Za-z0-9.\-]+\.[A-Za-z]{2,}$").unwrap()
+});
+
+static PHONE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\+?[0-9()\s.\-]{7,20}$").unwrap()
+});
+
+static DATE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4}[-/.]\d{1,2}[-/.]\d{1,2}|\d{1,2}[-/.]\d{1,2}[-/.]\d{2,4})([ T]\d{1,2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+\-]\d{2}:?\d{2})?)?$").unwrap()
+});
+
+static NUMERIC_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[+\-]?(\d+|\d{1,3}(,\d{3})+)(\.\d+)?([eE][+\-]?\d+)?$").unwrap()
+});
+
+const BOOLEAN_WORDS: [&str; 8] = ["true", "false", "yes", "no", "y", "n", "t", "f"];
+
+// Share of non-blank text values a pattern must match before it decides the inferred type
+const INFERENCE_THRESHOLD: f64 = 0.95;
+
+pub fn profile_dataframe(df: &DataFrame, top_n: usize) -> Result<Value> {
+    let mut columns = Vec::with_capacity(df.width());
+    for column in df.get_columns() {
+        columns.push(profile_column(column, top_n)?);
+    }
+
+    Ok(serde_json::json!({
+        "operation": "profile",
+        "row_count": df.height(),
+        "column_count": df.width(),
+        "columns": columns
+    }))
+}
+
+fn profile_column(column: &Column, top_n: usize) -> Result<Value> {
+    let row_count = column.len();
+    let null_count = column.null_count();
+    let dtype = column.dtype();
+
+    let text = column.cast(&DataType::String)?;
+    let values: Vec<&str> = text.str()?.into_iter().flatten().collect();
+    let non_blank: Vec<&str> = values.iter().copied().filter(|v| !v.trim().is_empty()).collect();
+    let blank_count = values.len() - non_blank.len();
+
+    let mut frequencies: HashMap<&str, usize> = HashMap::new();
+    for value in &values {
+        *frequencies.entry(value).or_insert(0) += 1;
+    }
+
+    let mut top_values: Vec<(&str, usize)> = frequencies.iter().map(|(v, c)| (*v, *c)).collect();
+    top_values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    top_values.truncate(top_n);
+
+    let patterns = if dtype.is_string() { pattern_rates(&non_blank) } else { HashMap::new() };
+    let inferred_type = infer_type(dtype, &non_blank, &patterns);
+
+    let (min, max) = if dtype.is_primitive_numeric() {
+        let numbers: Vec<f64> = column.cast(&DataType::Float64)?.f64()?.into_iter().flatten().collect();
+        (
+            numbers.iter().copied().reduce(f64::min).map(number_or_null).unwrap_or(Value::Null),
+            numbers.iter().copied().reduce(f64::max).map(number_or_null).unwrap_or(Value::Null),
+        )
+    } else {
+        (
+            non_blank.iter().min().map(|v| Value::from(*v)).unwrap_or(Value::Null),
+            non_blank.iter().max().map(|v| Value::from(*v)).unwrap_or(Value::Null),
+        )
+    };
+
+    let mut profile = serde_json::json!({
+        "column": column.name().to_string(),
+        "dtype": dtype.to_string(),
+        "inferred_type": inferred_type,
+        "null_count": null_count,
+        "null_rate": rate(null_count, row_count),
+        "blank_count": blank_count,
+        "blank_rate": rate(blank_count, row_count),
+        "distinct_count": frequencies.len(),
+        "min": min,
+        "max": max,
+        "top_values": top_values.into_iter()
+            .map(|(value, count)| serde_json::json!({ "value": value, "count": count }))
+            .collect::<Vec<_>>()
+    });
+
+    if dtype.is_string() {
+        profile["length"] = length_distribution(&values);
+        profile["patterns"] = serde_json::json!(patterns);
+    }
+
+    profile["issues"] = serde_json::json!(quality_issues(row_count, null_count, blank_count, frequencies.len(), &patterns));
+
+    Ok(profile)
+}
+
+fn pattern_rates(values: &[&str]) -> HashMap<&'static str, f64> {
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    for value in values {
+        let value = value.trim();
+        if EMAIL_PATTERN.is_match(value) {
+            *counts.entry("email").or_insert(0) += 1;
+        }
+        if PHONE_PATTERN.is_match(value) && value.chars().filter(|c| c.is_ascii_digit()).count() >= 7 {
+            *counts.entry("phone").or_insert(0) += 1;
+        }
+        if DATE_PATTERN.is_match(value) {
+            *counts.entry("date").or_insert(0) += 1;
+        }
+        if NUMERIC_PATTERN.is_match(value) {
+            *counts.entry("numeric_as_text").or_insert(0) += 1;
+        }
+        if BOOLEAN_WORDS.contains(&value.to_lowercase().as_str()) {
+            *counts.entry("boolean_as_text").or_insert(0) += 1;
+        }
+    }
+
+    counts.into_iter()
+        .map(|(pattern, count)| (pattern, rate(count, values.len())))
+        .collect()
+}
+
+fn infer_type(dtype: &DataType, non_blank: &[&str], patterns: &HashMap<&'static str, f64>) -> &'static str {
+    if non_blank.is_empty() {
+        return "empty";
+    }
+
+    match dtype {
+        d if d.is_integer() => "integer",
+        d if d.is_float() => "float",
+        DataType::Boolean => "boolean",
+        DataType::Date => "date",
+        DataType::Datetime(_, _) => "datetime",
+        DataType::String => {
+            let share = |pattern: &str| patterns.get(pattern).copied().unwrap_or(0.0);
+            if share("numeric_as_text") >= INFERENCE_THRESHOLD {
+                "numeric_text"
+            } else if share("date") >= INFERENCE_THRESHOLD {
+                "date_text"
+            } else if share("boolean_as_text") >= INFERENCE_THRESHOLD {
+                "boolean_text"
+            } else if share("email") >= INFERENCE_THRESHOLD {
+                "email"
+            } else if share("phone") >= INFERENCE_THRESHOLD {
+                "phone"
+            } else {
+                "text"
+            }
+        }
+        _ => "other",
+    }
+}
+
+fn length_distribution(values: &[&str]) -> Value {
+    let mut lengths: Vec<usize> = values.iter().map(|v| v.chars().count()).collect();
+    if lengths.is_empty() {
+        return Value::Null;
+    }
+    lengths.sort_unstable();
+
+    let percentile = |p: f64| lengths[((lengths.len() - 1) as f64 * p).round() as usize];
+    let mean = lengths.iter().sum::<usize>() as f64 / lengths.len() as f64;
+
+    serde_json::json!({
+        "min": lengths[0],
+        "max": lengths[lengths.len() - 1],
+        "mean": number_or_null(mean),
+        "median": percentile(0.5),
+        "p95": percentile(0.95)
+    })
+}
+
+fn quality_issues(
+    row_count: usize,
+    null_count: usize,
+    blank_count: usize,
+    distinct_count: usize,
+    patterns: &HashMap<&'static str, f64>,
+) -> Vec<&'static str> {
+    let mut issues = Vec::new();
+    if row_count > 0 && null_count == row_count {
+        issues.push("all_null");
+    } else if rate(null_count + blank_count, row_count) > 0.5 {
+        issues.push("mostly_missing");
+    }
+    if distinct_count == 1 && row_count > 1 {
+        issues.push("constant");
+    }
+    if patterns.get("numeric_as_text").is_some_and(|&r| r >= INFERENCE_THRESHOLD) {
+        issues.push("numeric_stored_as_text");
+    }
+    if patterns.values().any(|r| (0.5..INFERENCE_THRESHOLD).contains(r)) {
+        issues.push("mixed_formats");
+    }
+    issues
+}
+
+fn rate(count: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { count as f64 / total as f64 }
+}
+
+fn number_or_null(value: f64) -> Value {
+    serde_json::Number::from_f64(value).map(Value::Number).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_csv(csv: &str) -> DataFrame {
+        CsvReader::new(std::io::Cursor::new(csv.to_string())).finish().unwrap()
+    }
+
+    #[test]
+    fn test_profile_detects_patterns() {
+        let df = read_csv("email,amount,joined,score\na@x.com,\"1,200\",2024-01-05,1\nb@y.org,300,2024/02/01,\n,45.5,2024-03-09,3\n");
+        let report = profile_dataframe(&df, 3).unwrap();
+        let columns = report["columns"].as_array().unwrap();
+
+        assert_eq!(report["row_count"], 3);
+        assert_eq!(columns[0]["inferred_type"], "email");
+        assert_eq!(columns[1]["inferred_type"], "numeric_text");
+        assert!(columns[1]["issues"].as_array().unwrap().contains(&Value::from("numeric_stored_as_text")));
+        assert_eq!(columns[2]["patterns"]["date"], 1.0);
+        assert_eq!(columns[3]["inferred_type"], "integer");
+        assert_eq!(columns[3]["null_count"], 1);
+        assert_eq!(columns[3]["max"], 3.0);
+    }
+
+    #[test]
+    fn test_profile_length_distribution() {
+        let df = read_csv("name\nab\nabcd\nabcdef\n");
+        let report = profile_dataframe(&df, 10).unwrap();
+        let length = &report["columns"][0]["length"];
+
+        assert_eq!(length["min"], 2);
+        assert_eq!(length["max"], 6);
+        assert_eq!(length["median"], 4);
+        assert_eq!(report["columns"][0]["distinct_count"], 3);
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+// Interpolation between the two closest ranks, following numpy's method names;
+// linear is Hyndman-Fan type 7, the default in numpy, R and Excel's PERCENTILE.INC
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QuantileMethod {
+    #[default]
+    #[serde(alias = "type7", alias = "hyndman_fan_7")]
+    Linear,
+    Lower,
+    Higher,
+    Midpoint,
+    Nearest,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NanPolicy {
+    // Drop NaN values and compute over the rest
+    #[default]
+    Omit,
+    // Any NaN makes every quantile NaN
+    Propagate,
+    Error,
+}
+
+// Quantile of data that is already sorted ascending and free of NaN; q must lie in [0, 1]
+pub fn quantile_sorted(sorted: &[f64], q: f64, method: QuantileMethod) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+
+    match method {
+        QuantileMethod::Linear => sorted[lower] + (sorted[upper] - sorted[lower]) * fraction,
+        QuantileMethod::Lower => sorted[lower],
+        QuantileMethod::Higher => sorted[upper],
+        QuantileMethod::Midpoint => (sorted[lower] + sorted[upper]) / 2.0,
+        // Ties go to the even rank, as numpy does
+        QuantileMethod::Nearest => {
+            let index = if fraction == 0.5 {
+                if lower.is_multiple_of(2) { lower } else { upper }
+            } else {
+                position.round() as usize
+            };
+            sorted[index]
+        }
+    }
+}
+
+pub fn quantiles(data: &[f64], qs: &[f64], method: QuantileMethod, nan_policy: NanPolicy) -> Result<Vec<f64>> {
+    validate_probabilities(qs)?;
+
+    let nan_count = data.iter().filter(|v| v.is_nan()).count();
+    if nan_count > 0 {
+        match nan_policy {
+            NanPolicy::Omit => {}
+            NanPolicy::Propagate => return Ok(vec![f64::NAN; qs.len()]),
+            NanPolicy::Error => return Err(anyhow!("Input contains {} NaN values", nan_count)),
+        }
+    }
+
+    let mut sorted: Vec<f64> = data.iter().copied().filter(|v| !v.is_nan()).collect();
+    if sorted.is_empty() {
+        return Err(anyhow!("Cannot compute quantiles without non-NaN values"));
+    }
+    sorted.sort_by(f64::total_cmp);
+
+    Ok(qs.iter().map(|&q| quantile_sorted(&sorted, q, method)).collect())
+}
+
+// Weighted quantiles interpolate between the centres of each value's cumulative weight,
+// which reduces to the linear method when all weights are equal
+pub fn weighted_quantiles(data: &[f64], weights: &[f64], qs: &[f64], nan_policy: NanPolicy) -> Result<Vec<f64>> {
+    validate_probabilities(qs)?;
+    if data.len() != weights.len() {
+        return Err(anyhow!("Got {} weights for {} values", weights.len(), data.len()));
+    }
+    if let Some(bad) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+        return Err(anyhow!("Weights must be finite and non-negative, got {}", bad));
+    }
+
+    let nan_count = data.iter().filter(|v| v.is_nan()).count();
+    if nan_count > 0 {
+        match nan_policy {
+            NanPolicy::Omit => {}
+            NanPolicy::Propagate => return Ok(vec![f64::NAN; qs.len()]),
+            NanPolicy::Error => return Err(anyhow!("Input contains {} NaN values", nan_count)),
+        }
+    }
+
+    let mut pairs: Vec<(f64, f64)> = data.iter().copied()
+        .zip(weights.iter().copied())
+        .filter(|(v, w)| !v.is_nan() && *w > 0.0)
+        .collect();
+    if pairs.is_empty() {
+        return Err(anyhow!("Cannot compute weighted quantiles without positive weights"));
+    }
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let total: f64 = pairs.iter().map(|(_, w)| w).sum();
+    let mut cumulative = 0.0;
+    let centres: Vec<f64> = pairs.iter()
+        .map(|(_, w)| {
+            let centre = cumulative + w / 2.0;
+            cumulative += w;
+            centre
+        })
+        .collect();
+
+    // Scale the centres so the first maps to 0 and the last to 1, matching the unweighted ranks
+    let (first, last) = (centres[0], centres[centres.len() - 1]);
+    let span = last - first;
+
+    Ok(qs.iter()
+        .map(|&q| {
+            if span <= 0.0 || total <= 0.0 {
+                return pairs[0].0;
+            }
+            let target = first + q * span;
+            let upper = centres.partition_point(|&c| c < target).min(centres.len() - 1);
+            if upper == 0 {
+                return pairs[0].0;
+            }
+            let lower = upper - 1;
+            let fraction = (target - centres[lower]) / (centres[upper] - centres[lower]);
+            pairs[lower].0 + (pairs[upper].0 - pairs[lower].0) * fraction
+        })
+        .collect())
+}
+
+fn validate_probabilities(qs: &[f64]) -> Result<()> {
+    match qs.iter().find(|q| !(0.0..=1.0).contains(*q)) {
+        Some(q) => Err(anyhow!("Quantiles must lie between 0 and 1, got {}", q)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+// Merging t-digest (Dunning & Ertl) for approximate quantiles in bounded memory; accuracy is
+// highest in the tails and the number of centroids stays around the compression parameter
+#[derive(Clone, Debug)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    total_weight: f64,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression: compression.max(10.0),
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            total_weight: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.buffer.push(value);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        if self.buffer.len() >= (self.compression as usize) * 5 {
+            self.compress();
+        }
+    }
+
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut incoming: Vec<Centroid> = self.centroids.drain(..)
+            .chain(self.buffer.drain(..).map(|mean| Centroid { mean, weight: 1.0 }))
+            .collect();
+        incoming.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+        let total: f64 = incoming.iter().map(|c| c.weight).sum();
+
+        let mut merged: Vec<Centroid> = Vec::with_capacity(self.compression as usize);
+        let mut current = incoming[0];
+        let mut weight_before = 0.0;
+        let mut limit = total * self.k_inverse(self.k(0.0) + 1.0);
+
+        for next in incoming.into_iter().skip(1) {
+            if weight_before + current.weight + next.weight <= limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_before += current.weight;
+                merged.push(current);
+                limit = total * self.k_inverse(self.k(weight_before / total) + 1.0);
+                current = next;
+            }
+        }
+        merged.push(current);
+
+        self.centroids = merged;
+        self.total_weight = total;
+    }
+
+    // Scale function k1: centroids near q = 0 and q = 1 are kept small
+    fn k(&self, q: f64) -> f64 {
+        self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin()
+    }
+
+    fn k_inverse(&self, k: f64) -> f64 {
+        let angle = (k * 2.0 * PI / self.compression).clamp(-PI / 2.0, PI / 2.0);
+        (angle.sin() + 1.0) / 2.0
+    }
+
+    pub fn quantile(&mut self, q: f64) -> f64 {
+        self.compress();
+        if self.centroids.is_empty() {
+            return f64::NAN;
+        }
+        if self.centroids.len() == 1 {
+            return self.centroids[0].mean;
+        }
+
+        let target = q.clamp(0.0, 1.0) * self.total_weight;
+        let mut cumulative = 0.0;
+        let mut previous_centre = 0.0;
+        let mut previous_mean = self.min;
+
+        for centroid in &self.centroids {
+            let centre = cumulative + centroid.weight / 2.0;
+            if target < centre {
+                let fraction = if centre > previous_centre { (target - previous_centre) / (centre - previous_centre) } else { 0.0 };
+                return previous_mean + (centroid.mean - previous_mean) * fraction;
+            }
+            cumulative += centroid.weight;
+            previous_centre = centre;
+            previous_mean = centroid.mean;
+        }
+
+        let remaining = self.total_weight - previous_centre;
+        let fraction = if remaining > 0.0 { (target - previous_centre) / remaining } else { 1.0 };
+        previous_mean + (self.max - previous_mean) * fraction.min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_methods_match_numpy() {
+        let data = [1.0, 2.0, 3.0, 4.0];
+        let q = [0.4];
+        let run = |method| quantiles(&data, &q, method, NanPolicy::Omit).unwrap()[0];
+
+        assert!((run(QuantileMethod::Linear) - 2.2).abs() < 1e-12);
+        assert_eq!(run(QuantileMethod::Lower), 2.0);
+        assert_eq!(run(QuantileMethod::Higher), 3.0);
+        assert_eq!(run(QuantileMethod::Midpoint), 2.5);
+        assert_eq!(run(QuantileMethod::Nearest), 2.0);
+        assert_eq!(quantile_sorted(&[1.0, 2.0, 3.0, 4.0, 5.0], 0.625, QuantileMethod::Nearest), 3.0);
+    }
+
+    #[test]
+    fn test_nan_policies() {
+        let data = [3.0, f64::NAN, 1.0, 2.0];
+        assert_eq!(quantiles(&data, &[0.5], QuantileMethod::Linear, NanPolicy::Omit).unwrap(), vec![2.0]);
+        assert!(quantiles(&data, &[0.5], QuantileMethod::Linear, NanPolicy::Propagate).unwrap()[0].is_nan());
+        assert!(quantiles(&data, &[0.5], QuantileMethod::Linear, NanPolicy::Error).is_err());
+        assert!(quantiles(&data, &[1.5], QuantileMethod::Linear, NanPolicy::Omit).is_err());
+    }
+
+    #[test]
+    fn test_weighted_quantiles() {
+        let equal = weighted_quantiles(&[1.0, 2.0, 3.0, 4.0], &[1.0; 4], &[0.4], NanPolicy::Omit).unwrap();
+        assert!((equal[0] - 2.2).abs() < 1e-12);
+
+        // A heavy weight pulls the median towards its value
+        let skewed = weighted_quantiles(&[1.0, 2.0, 10.0], &[1.0, 1.0, 8.0], &[0.5], NanPolicy::Omit).unwrap();
+        assert!(skewed[0] > 5.0, "{:?}", skewed);
+        assert!(weighted_quantiles(&[1.0], &[-1.0], &[0.5], NanPolicy::Omit).is_err());
+    }
+
+    #[test]
+    fn test_tdigest_approximates_uniform_data() {
+        let mut digest = TDigest::new(100.0);
+        for i in 0..100_000 {
+            digest.add(i as f64);
+        }
+
+        for q in [0.01, 0.25, 0.5, 0.99] {
+            let estimate = digest.quantile(q);
+            let exact = q * 99_999.0;
+            assert!((estimate - exact).abs() < 200.0, "q={} estimate={} exact={}", q, estimate, exact);
+        }
+        assert_eq!(digest.quantile(0.0), 0.0);
+        assert_eq!(digest.quantile(1.0), 99_999.0);
+        assert!(digest.centroids.len() < 500);
+    }
+}
@@ -0,0 +1,279 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// Parameter types as the UI sees them; `one_of` accepts a value matching any of its options
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ParamType {
+    Integer,
+    Number,
+    Boolean,
+    String,
+    Array {
+        items: Box<ParamType>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        length: Option<usize>,
+    },
+    Object,
+    OneOf { options: Vec<ParamType> },
+}
+
+impl ParamType {
+    pub fn array(items: ParamType) -> Self {
+        ParamType::Array { items: Box::new(items), length: None }
+    }
+
+    // Fixed-length integer pair such as a matrix shape [rows, cols]
+    pub fn shape() -> Self {
+        ParamType::Array { items: Box::new(ParamType::Integer), length: Some(2) }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            ParamType::Integer => "an integer".to_string(),
+            ParamType::Number => "a number".to_string(),
+            ParamType::Boolean => "a boolean".to_string(),
+            ParamType::String => "a string".to_string(),
+            ParamType::Array { items, length: Some(length) } => format!("an array of {} items, each {}", length, items.describe()),
+            ParamType::Array { items, length: None } => format!("an array of items, each {}", items.describe()),
+            ParamType::Object => "an object".to_string(),
+            ParamType::OneOf { options } => options.iter().map(|o| o.describe()).collect::<Vec<_>>().join(" or "),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ParamSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: ParamType,
+    pub description: String,
+    pub required: bool,
+    pub default: Option<Value>,
+    // Inclusive bounds, applied to every number in the value
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    // Allowed strings; aliases are accepted as well but not offered
+    pub choices: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+}
+
+impl ParamSpec {
+    pub fn new(name: &str, param_type: ParamType, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            param_type,
+            description: description.to_string(),
+            required: false,
+            default: None,
+            minimum: None,
+            maximum: None,
+            choices: None,
+            aliases: Vec::new(),
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn default(mut self, value: impl Into<Value>) -> Self {
+        self.default = Some(value.into());
+        self
+    }
+
+    pub fn range(mut self, minimum: Option<f64>, maximum: Option<f64>) -> Self {
+        self.minimum = minimum;
+        self.maximum = maximum;
+        self
+    }
+
+    pub fn choices(mut self, choices: &[&str]) -> Self {
+        self.choices = Some(choices.iter().map(|c| c.to_string()).collect());
+        self
+    }
+
+    pub fn aliases(mut self, aliases: &[&str]) -> Self {
+        self.aliases = aliases.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    fn check(&self, value: &Value) -> Result<()> {
+        if self.matches(&self.param_type, value) {
+            return Ok(());
+        }
+
+        let mut expected = self.param_type.describe();
+        if let Some(choices) = &self.choices {
+            expected.push_str(&format!(" (one of {})", choices.join(", ")));
+        }
+        match (self.minimum, self.maximum) {
+            (Some(low), Some(high)) => expected.push_str(&format!(" between {} and {}", low, high)),
+            (Some(low), None) => expected.push_str(&format!(" of at least {}", low)),
+            (None, Some(high)) => expected.push_str(&format!(" of at most {}", high)),
+            (None, None) => {}
+        }
+        Err(anyhow!("Parameter '{}' must be {}, got {}", self.name, expected, value))
+    }
+
+    fn matches(&self, param_type: &ParamType, value: &Value) -> bool {
+        let in_range = |n: f64| self.minimum.is_none_or(|low| n >= low) && self.maximum.is_none_or(|high| n <= high);
+        match param_type {
+            ParamType::Integer => value.as_i64().map(|n| n as f64).or_else(|| value.as_u64().map(|n| n as f64)).is_some_and(in_range),
+            ParamType::Number => value.as_f64().is_some_and(in_range),
+            ParamType::Boolean => value.is_boolean(),
+            ParamType::String => value.as_str().is_some_and(|s| {
+                self.choices.as_ref().is_none_or(|choices| choices.iter().chain(&self.aliases).any(|c| c == s))
+            }),
+            ParamType::Array { items, length } => value.as_array().is_some_and(|values| {
+                length.is_none_or(|length| values.len() == length) && values.iter().all(|v| self.matches(items, v))
+            }),
+            ParamType::Object => value.is_object(),
+            ParamType::OneOf { options } => options.iter().any(|option| self.matches(option, value)),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OperationSchema {
+    pub name: String,
+    pub description: String,
+    pub category: String,
+    pub parameters: Vec<ParamSpec>,
+}
+
+impl OperationSchema {
+    pub fn new(name: &str, category: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            category: category.to_string(),
+            parameters: Vec::new(),
+        }
+    }
+
+    pub fn param(mut self, spec: ParamSpec) -> Self {
+        self.parameters.push(spec);
+        self
+    }
+
+    pub fn declares(&self, name: &str) -> bool {
+        self.parameters.iter().any(|p| p.name == name)
+    }
+
+    // Checks every supplied parameter and fills in declared defaults. A null counts as not supplied.
+    // Unknown names are errors unless `ignore_unknown` is set, for parameters shared by several operations
+    pub fn validate(&self, params: Option<&Value>, ignore_unknown: bool) -> Result<Value> {
+        let supplied = match params {
+            None | Some(Value::Null) => Map::new(),
+            Some(Value::Object(map)) => map.clone(),
+            Some(other) => return Err(anyhow!("Parameters for '{}' must be an object, got {}", self.name, other)),
+        };
+
+        if !ignore_unknown {
+            if let Some(unknown) = supplied.keys().find(|key| !self.declares(key)) {
+                let known: Vec<&str> = self.parameters.iter().map(|p| p.name.as_str()).collect();
+                return Err(anyhow!(
+                    "Unknown parameter '{}' for operation '{}'; expected {}",
+                    unknown, self.name, if known.is_empty() { "none".to_string() } else { known.join(", ") }
+                ));
+            }
+        }
+
+        let mut validated = Map::new();
+        for spec in &self.parameters {
+            match supplied.get(&spec.name).filter(|v| !v.is_null()) {
+                Some(value) => {
+                    spec.check(value).map_err(|e| anyhow!("Invalid parameters for '{}': {}", self.name, e))?;
+                    validated.insert(spec.name.clone(), value.clone());
+                }
+                None if spec.required => {
+                    return Err(anyhow!("Operation '{}' requires parameter '{}'", self.name, spec.name));
+                }
+                None => {
+                    if let Some(default) = &spec.default {
+                        validated.insert(spec.name.clone(), default.clone());
+                    }
+                }
+            }
+        }
+        Ok(Value::Object(validated))
+    }
+}
+
+// A value operation: a declared parameter schema plus the computation. Parameters reach `execute`
+// only after they have passed `schema().validate`
+pub trait DataOperation: Send + Sync {
+    fn schema(&self) -> &OperationSchema;
+    fn execute(&self, data: &[f64], params: Option<&Value>) -> Result<Value>;
+}
+
+pub struct FnOperation<F> {
+    schema: OperationSchema,
+    function: F,
+}
+
+impl<F> FnOperation<F>
+where
+    F: Fn(&[f64], Option<&Value>) -> Result<Value> + Send + Sync,
+{
+    pub fn new(schema: OperationSchema, function: F) -> Self {
+        Self { schema, function }
+    }
+}
+
+impl<F> DataOperation for FnOperation<F>
+where
+    F: Fn(&[f64], Option<&Value>) -> Result<Value> + Send + Sync,
+{
+    fn schema(&self) -> &OperationSchema {
+        &self.schema
+    }
+
+    fn execute(&self, data: &[f64], params: Option<&Value>) -> Result<Value> {
+        (self.function)(data, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram_schema() -> OperationSchema {
+        OperationSchema::new("histogram", "distribution", "Bin counts")
+            .param(ParamSpec::new("bins", ParamType::OneOf { options: vec![ParamType::Integer, ParamType::String] }, "Bin count or rule")
+                .default("auto")
+                .range(Some(1.0), None)
+                .choices(&["auto", "sturges", "scott", "freedman_diaconis"])
+                .aliases(&["fd"]))
+            .param(ParamSpec::new("shape", ParamType::shape(), "Matrix shape"))
+            .param(ParamSpec::new("density", ParamType::Boolean, "Normalise to a density").default(false))
+    }
+
+    #[test]
+    fn test_validation_fills_defaults_and_rejects_bad_values() {
+        let schema = histogram_schema();
+        assert_eq!(schema.validate(None, false).unwrap(), serde_json::json!({"bins": "auto", "density": false}));
+
+        let valid = schema.validate(Some(&serde_json::json!({"bins": "fd", "shape": [2, 3], "density": null})), false).unwrap();
+        assert_eq!(valid, serde_json::json!({"bins": "fd", "shape": [2, 3], "density": false}));
+
+        for bad in [
+            serde_json::json!({"bins": "ten"}),
+            serde_json::json!({"bins": 0}),
+            serde_json::json!({"bins": 2.5}),
+            serde_json::json!({"shape": [2]}),
+            serde_json::json!({"density": "yes"}),
+            serde_json::json!({"colour": "red"}),
+        ] {
+            assert!(schema.validate(Some(&bad), false).is_err(), "{} was accepted", bad);
+        }
+
+        let error = schema.validate(Some(&serde_json::json!({"bins": "ten"})), false).unwrap_err().to_string();
+        assert!(error.contains("'bins'") && error.contains("one of auto"), "{}", error);
+        assert!(schema.validate(Some(&serde_json::json!({"colour": "red"})), true).is_ok());
+    }
+}
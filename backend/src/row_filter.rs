@@ -0,0 +1,452 @@
+use anyhow::{Result, anyhow};
+use polars::prelude::{when, BooleanChunked, Column, DataType, Expr, GetOutput, IntoColumn, NamedFrom, PolarsResult, Schema, Series, NULL, col, lit};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::advanced_formulas::{value_to_f64, value_to_text};
+use crate::data_processor::any_value_to_json;
+
+// Boolean condition tree used by the FILTER formula, e.g.
+// {"and": [{"predicate": {"column": "region", "op": "eq", "value": "North"}},
+//          {"not": {"predicate": {"column": "amount", "op": "is_null"}}}]}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterCondition {
+    And(Vec<FilterCondition>),
+    Or(Vec<FilterCondition>),
+    Not(Box<FilterCondition>),
+    Predicate(ColumnPredicate),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ColumnPredicate {
+    pub column: String,
+    pub op: PredicateOp,
+    #[serde(default)]
+    pub value: Option<Value>,
+    #[serde(default = "default_case_sensitive")]
+    pub case_sensitive: bool,
+}
+
+fn default_case_sensitive() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PredicateOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    In,
+    NotIn,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Regex,
+    IsNull,
+    IsNotNull,
+    Between,
+}
+
+// Condition tree with regexes compiled and operands checked, ready to evaluate per row
+pub enum CompiledCondition {
+    And(Vec<CompiledCondition>),
+    Or(Vec<CompiledCondition>),
+    Not(Box<CompiledCondition>),
+    Predicate(CompiledPredicate),
+}
+
+pub struct CompiledPredicate {
+    column: String,
+    op: PredicateOp,
+    operand: Operand,
+    case_sensitive: bool,
+}
+
+enum Operand {
+    None,
+    Single(Value),
+    List(Vec<Value>),
+    Range(Value, Value),
+    Pattern(Regex),
+}
+
+impl FilterCondition {
+    pub fn compile(&self) -> Result<CompiledCondition> {
+        Ok(match self {
+            FilterCondition::And(children) => CompiledCondition::And(
+                children.iter().map(|c| c.compile()).collect::<Result<_>>()?,
+            ),
+            FilterCondition::Or(children) => CompiledCondition::Or(
+                children.iter().map(|c| c.compile()).collect::<Result<_>>()?,
+            ),
+            FilterCondition::Not(child) => CompiledCondition::Not(Box::new(child.compile()?)),
+            FilterCondition::Predicate(predicate) => CompiledCondition::Predicate(predicate.compile()?),
+        })
+    }
+
+    // Polars predicate for lazy and streaming queries, built from native expressions so the scan
+    // stays vectorised and can push the filter down. Predicates follow the row matcher's rules, so
+    // both paths keep exactly the same rows; where a column's type has no native equivalent for
+    // the row matcher's coercions, that one predicate is evaluated cell by cell instead
+    pub fn to_expr(&self, schema: &Schema) -> Result<Expr> {
+        Ok(match self {
+            FilterCondition::And(children) => children.iter()
+                .map(|c| c.to_expr(schema))
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .reduce(|a, b| a.and(b))
+                .unwrap_or(lit(true)),
+            FilterCondition::Or(children) => children.iter()
+                .map(|c| c.to_expr(schema))
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .reduce(|a, b| a.or(b))
+                .unwrap_or(lit(false)),
+            FilterCondition::Not(child) => child.to_expr(schema)?.not(),
+            FilterCondition::Predicate(predicate) => {
+                let dtype = schema.get(predicate.column.as_str())
+                    .ok_or_else(|| anyhow!("Filter column '{}' not found", predicate.column))?;
+                let compiled = predicate.compile()?;
+                // A null result is a non-match, as in the row matcher, so NOT and OR see plain booleans
+                predicate.native_expr(dtype)
+                    .unwrap_or_else(|| compiled.into_row_expr())
+                    .fill_null(lit(false))
+            }
+        })
+    }
+}
+
+impl ColumnPredicate {
+    fn compile(&self) -> Result<CompiledPredicate> {
+        let missing_value = || anyhow!("Filter on '{}' with op {:?} requires a value", self.column, self.op);
+
+        let operand = match self.op {
+            PredicateOp::IsNull | PredicateOp::IsNotNull => Operand::None,
+            PredicateOp::In | PredicateOp::NotIn => {
+                let list = self.value.as_ref()
+                    .and_then(|v| v.as_array())
+                    .ok_or_else(|| anyhow!("Filter on '{}' with op {:?} requires a list value", self.column, self.op))?;
+                Operand::List(list.clone())
+            }
+            PredicateOp::Between => {
+                let bounds = self.value.as_ref()
+                    .and_then(|v| v.as_array())
+                    .filter(|a| a.len() == 2)
+                    .ok_or_else(|| anyhow!("Filter on '{}' with op between requires [low, high]", self.column))?;
+                Operand::Range(bounds[0].clone(), bounds[1].clone())
+            }
+            PredicateOp::Regex => {
+                let pattern = self.value.as_ref()
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(missing_value)?;
+                let regex = RegexBuilder::new(pattern)
+                    .case_insensitive(!self.case_sensitive)
+                    .build()
+                    .map_err(|e| anyhow!("Invalid regex for column '{}': {}", self.column, e))?;
+                Operand::Pattern(regex)
+            }
+            _ => Operand::Single(self.value.clone().ok_or_else(missing_value)?),
+        };
+
+        Ok(CompiledPredicate {
+            column: self.column.clone(),
+            op: self.op,
+            operand,
+            case_sensitive: self.case_sensitive,
+        })
+    }
+    
+    // Vectorised form for a column of the given type, or None where the row matcher coerces
+    // per cell: numbers against text columns, text against number columns, dates and the like
+    fn native_expr(&self, dtype: &DataType) -> Option<Expr> {
+        let column = col(self.column.as_str());
+        let value = self.value.as_ref();
+
+        if dtype.is_primitive_numeric() {
+            // Non-finite floats are null cells to the row matcher, as any_value_to_json reports them
+            let number = column.cast(DataType::Float64);
+            let number = when(number.clone().is_finite()).then(number).otherwise(lit(NULL));
+            let finite = |v: &Value| value_to_f64(v).filter(|n| n.is_finite());
+            return match self.op {
+                PredicateOp::IsNull => Some(number.is_null()),
+                PredicateOp::IsNotNull => Some(number.is_not_null()),
+                PredicateOp::Eq => Some(number.eq(lit(finite(value?)?))),
+                PredicateOp::Ne => Some(number.neq(lit(finite(value?)?))),
+                PredicateOp::Gt => Some(number.gt(lit(finite(value?)?))),
+                PredicateOp::Gte => Some(number.gt_eq(lit(finite(value?)?))),
+                PredicateOp::Lt => Some(number.lt(lit(finite(value?)?))),
+                PredicateOp::Lte => Some(number.lt_eq(lit(finite(value?)?))),
+                PredicateOp::In | PredicateOp::NotIn => {
+                    // Entries that are not finite numbers can never equal a number cell
+                    let values: Vec<f64> = value?.as_array()?.iter().filter_map(finite).collect();
+                    let found = number.is_in(lit(Series::new("values".into(), values)).implode(), false);
+                    Some(if self.op == PredicateOp::In { found } else { found.not() })
+                }
+                PredicateOp::Between => {
+                    let bounds = value?.as_array()?;
+                    let (low, high) = (finite(bounds.first()?)?, finite(bounds.get(1)?)?);
+                    Some(number.clone().gt_eq(lit(low)).and(number.lt_eq(lit(high))))
+                }
+                _ => None,
+            };
+        }
+
+        if *dtype == DataType::Boolean {
+            return match (self.op, value) {
+                (PredicateOp::IsNull, _) => Some(column.is_null()),
+                (PredicateOp::IsNotNull, _) => Some(column.is_not_null()),
+                (PredicateOp::Eq, Some(Value::Bool(b))) => Some(column.eq(lit(*b))),
+                (PredicateOp::Ne, Some(Value::Bool(b))) => Some(column.neq(lit(*b))),
+                _ => None,
+            };
+        }
+
+        if *dtype == DataType::String {
+            // Numeric operands compare numerically against numeric-looking text, row by row
+            let text = |v: &Value| match v {
+                Value::Number(_) | Value::Null => None,
+                v => Some(self.fold_case(value_to_text(v))),
+            };
+            let cased = if self.case_sensitive { column.clone() } else { column.clone().str().to_lowercase() };
+            return match self.op {
+                PredicateOp::IsNull => Some(column.is_null()),
+                PredicateOp::IsNotNull => Some(column.is_not_null()),
+                PredicateOp::Eq => Some(cased.eq(lit(text(value?)?))),
+                PredicateOp::Ne => Some(cased.neq(lit(text(value?)?))),
+                PredicateOp::Gt => Some(cased.gt(lit(text(value?)?))),
+                PredicateOp::Gte => Some(cased.gt_eq(lit(text(value?)?))),
+                PredicateOp::Lt => Some(cased.lt(lit(text(value?)?))),
+                PredicateOp::Lte => Some(cased.lt_eq(lit(text(value?)?))),
+                PredicateOp::Contains => Some(cased.str().contains_literal(lit(text(value?)?))),
+                PredicateOp::StartsWith => Some(cased.str().starts_with(lit(text(value?)?))),
+                PredicateOp::EndsWith => Some(cased.str().ends_with(lit(text(value?)?))),
+                PredicateOp::In | PredicateOp::NotIn => {
+                    let list = value?.as_array()?;
+                    if list.iter().any(Value::is_number) {
+                        return None;
+                    }
+                    let values: Vec<String> = list.iter().filter_map(text).collect();
+                    let found = cased.is_in(lit(Series::new("values".into(), values)).implode(), false);
+                    Some(if self.op == PredicateOp::In { found } else { found.not() })
+                }
+                PredicateOp::Between => {
+                    let bounds = value?.as_array()?;
+                    let (low, high) = (text(bounds.first()?)?, text(bounds.get(1)?)?);
+                    Some(cased.clone().gt_eq(lit(low)).and(cased.lt_eq(lit(high))))
+                }
+                // The regex sees the original text; case_sensitive becomes the regex's own flag
+                PredicateOp::Regex => {
+                    let pattern = value?.as_str()?;
+                    let pattern = if self.case_sensitive { pattern.to_string() } else { format!("(?i){}", pattern) };
+                    Some(column.str().contains(lit(pattern), true))
+                }
+            };
+        }
+
+        None
+    }
+
+    fn fold_case(&self, text: String) -> String {
+        if self.case_sensitive { text } else { text.to_lowercase() }
+    }
+}
+
+impl CompiledCondition {
+    pub fn matches(&self, row: &HashMap<String, Value>) -> bool {
+        match self {
+            CompiledCondition::And(children) => children.iter().all(|c| c.matches(row)),
+            CompiledCondition::Or(children) => children.iter().any(|c| c.matches(row)),
+            CompiledCondition::Not(child) => !child.matches(row),
+            CompiledCondition::Predicate(predicate) => predicate.matches(row),
+        }
+    }
+}
+
+impl CompiledPredicate {
+    fn matches(&self, row: &HashMap<String, Value>) -> bool {
+        self.matches_cell(row.get(&self.column))
+    }
+
+    // Comparisons against a null or missing cell never match; use is_null to select them
+    fn matches_cell(&self, cell: Option<&Value>) -> bool {
+        let cell = cell.filter(|v| !v.is_null());
+
+        let cell = match (self.op, cell) {
+            (PredicateOp::IsNull, cell) => return cell.is_none(),
+            (PredicateOp::IsNotNull, cell) => return cell.is_some(),
+            (_, None) => return false,
+            (_, Some(cell)) => cell,
+        };
+
+        match (&self.operand, self.op) {
+            (Operand::Single(target), PredicateOp::Eq) => self.equals(cell, target),
+            (Operand::Single(target), PredicateOp::Ne) => !self.equals(cell, target),
+            (Operand::Single(target), PredicateOp::Gt) => self.compare(cell, target) == Some(Ordering::Greater),
+            (Operand::Single(target), PredicateOp::Gte) => matches!(self.compare(cell, target), Some(Ordering::Greater | Ordering::Equal)),
+            (Operand::Single(target), PredicateOp::Lt) => self.compare(cell, target) == Some(Ordering::Less),
+            (Operand::Single(target), PredicateOp::Lte) => matches!(self.compare(cell, target), Some(Ordering::Less | Ordering::Equal)),
+            (Operand::Single(target), PredicateOp::Contains) => self.text(cell).contains(&self.text(target)),
+            (Operand::Single(target), PredicateOp::StartsWith) => self.text(cell).starts_with(&self.text(target)),
+            (Operand::Single(target), PredicateOp::EndsWith) => self.text(cell).ends_with(&self.text(target)),
+            (Operand::List(list), PredicateOp::In) => list.iter().any(|target| self.equals(cell, target)),
+            (Operand::List(list), PredicateOp::NotIn) => !list.iter().any(|target| self.equals(cell, target)),
+            (Operand::Range(low, high), PredicateOp::Between) => {
+                matches!(self.compare(cell, low), Some(Ordering::Greater | Ordering::Equal))
+                    && matches!(self.compare(cell, high), Some(Ordering::Less | Ordering::Equal))
+            }
+            (Operand::Pattern(regex), PredicateOp::Regex) => regex.is_match(&value_to_text(cell)),
+            _ => false,
+        }
+    }
+
+    // Row-by-row evaluation over the predicate's column, for operands with no native expression
+    fn into_row_expr(self) -> Expr {
+        let column = col(self.column.as_str());
+        column.map(
+            move |column: Column| {
+                let mask = (0..column.len())
+                    .map(|i| Ok(self.matches_cell(Some(&any_value_to_json(&column.get(i)?)))))
+                    .collect::<PolarsResult<BooleanChunked>>()?;
+                Ok(Some(mask.with_name(column.name().clone()).into_column()))
+            },
+            GetOutput::from_type(DataType::Boolean),
+        )
+    }
+
+    fn text(&self, value: &Value) -> String {
+        let text = value_to_text(value);
+        if self.case_sensitive { text } else { text.to_lowercase() }
+    }
+
+    fn equals(&self, cell: &Value, target: &Value) -> bool {
+        self.compare(cell, target) == Some(Ordering::Equal)
+    }
+
+    // Numbers compare numerically (numeric strings are coerced when the other side is a number),
+    // everything else compares as text
+    fn compare(&self, cell: &Value, target: &Value) -> Option<Ordering> {
+        if cell.is_number() || target.is_number() {
+            if let (Some(a), Some(b)) = (value_to_f64(cell), value_to_f64(target)) {
+                return a.partial_cmp(&b);
+            }
+        }
+        if let (Value::Bool(a), Value::Bool(b)) = (cell, target) {
+            return Some(a.cmp(b));
+        }
+        if target.is_null() {
+            return None;
+        }
+        Some(self.text(cell).cmp(&self.text(target)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_compound_condition() {
+        let condition: FilterCondition = serde_json::from_value(serde_json::json!({
+            "and": [
+                {"predicate": {"column": "region", "op": "in", "value": ["North", "East"]}},
+                {"or": [
+                    {"predicate": {"column": "amount", "op": "between", "value": [100, 200]}},
+                    {"predicate": {"column": "name", "op": "regex", "value": "^vip", "case_sensitive": false}}
+                ]},
+                {"not": {"predicate": {"column": "status", "op": "is_null"}}}
+            ]
+        })).unwrap();
+        let compiled = condition.compile().unwrap();
+
+        assert!(compiled.matches(&row(serde_json::json!({"region": "North", "amount": 150, "status": "ok"}))));
+        assert!(compiled.matches(&row(serde_json::json!({"region": "East", "amount": 5, "name": "VIP Store", "status": "ok"}))));
+        assert!(!compiled.matches(&row(serde_json::json!({"region": "North", "amount": 150, "status": null}))));
+        assert!(!compiled.matches(&row(serde_json::json!({"region": "South", "amount": 150, "status": "ok"}))));
+    }
+
+    #[test]
+    fn test_null_cells_do_not_match_comparisons() {
+        let condition: FilterCondition = serde_json::from_value(serde_json::json!({
+            "predicate": {"column": "amount", "op": "lt", "value": 10}
+        })).unwrap();
+        let compiled = condition.compile().unwrap();
+
+        assert!(compiled.matches(&row(serde_json::json!({"amount": "5"}))));
+        assert!(!compiled.matches(&row(serde_json::json!({"amount": null}))));
+        assert!(!compiled.matches(&row(serde_json::json!({}))));
+    }
+
+    #[test]
+    fn test_native_expressions_keep_the_same_rows_as_the_row_matcher() {
+        use crate::data_processor::dataframe_to_rows;
+        use polars::prelude::{df, IntoLazy};
+
+        let df = df!(
+            "id" => [0i64, 1, 2, 3, 4, 5],
+            "amount" => [Some(5.0), Some(150.0), None, Some(f64::NAN), Some(99.5), Some(200.0)],
+            "qty" => [Some(1i64), Some(2), Some(3), None, Some(5), Some(6)],
+            "region" => [Some("North"), Some("north"), Some("East"), None, Some("Northeast"), Some("10")],
+            "flag" => [Some(true), Some(false), None, Some(true), Some(false), Some(true)]
+        ).unwrap();
+        let schema = df.schema().clone();
+        let rows = dataframe_to_rows(&df).unwrap();
+
+        let conditions = [
+            // Native on every column type
+            serde_json::json!({"predicate": {"column": "amount", "op": "gt", "value": 99.5}}),
+            serde_json::json!({"predicate": {"column": "amount", "op": "ne", "value": "150"}}),
+            serde_json::json!({"predicate": {"column": "amount", "op": "is_null"}}),
+            serde_json::json!({"predicate": {"column": "qty", "op": "in", "value": [2, "5", "x", null]}}),
+            serde_json::json!({"predicate": {"column": "qty", "op": "between", "value": [2, 5]}}),
+            serde_json::json!({"predicate": {"column": "region", "op": "eq", "value": "NORTH", "case_sensitive": false}}),
+            serde_json::json!({"predicate": {"column": "region", "op": "starts_with", "value": "North"}}),
+            serde_json::json!({"predicate": {"column": "region", "op": "not_in", "value": ["East", null]}}),
+            serde_json::json!({"predicate": {"column": "region", "op": "regex", "value": "^n.*h$", "case_sensitive": false}}),
+            serde_json::json!({"predicate": {"column": "region", "op": "lt", "value": "O"}}),
+            serde_json::json!({"predicate": {"column": "flag", "op": "eq", "value": true}}),
+            serde_json::json!({"not": {"predicate": {"column": "amount", "op": "lt", "value": 100}}}),
+            serde_json::json!({"or": [
+                {"predicate": {"column": "flag", "op": "ne", "value": true}},
+                {"and": [{"predicate": {"column": "region", "op": "contains", "value": "th"}}, {"not": {"predicate": {"column": "qty", "op": "is_not_null"}}}]}
+            ]}),
+            serde_json::json!({"and": []}),
+            // Row-matcher fallbacks: numbers against text, text against numbers
+            serde_json::json!({"predicate": {"column": "region", "op": "gte", "value": 9}}),
+            serde_json::json!({"predicate": {"column": "amount", "op": "contains", "value": "5"}}),
+            serde_json::json!({"not": {"predicate": {"column": "region", "op": "in", "value": [10, "East"]}}}),
+        ];
+
+        for condition in conditions {
+            let filter: FilterCondition = serde_json::from_value(condition.clone()).unwrap();
+            let compiled = filter.compile().unwrap();
+            let expected: Vec<Value> = rows.iter().filter(|r| compiled.matches(r)).map(|r| r["id"].clone()).collect();
+
+            let kept = df.clone().lazy().filter(filter.to_expr(&schema).unwrap()).collect().unwrap();
+            let ids: Vec<Value> = kept.column("id").unwrap().i64().unwrap().into_iter().map(|v| Value::from(v.unwrap())).collect();
+            assert_eq!(ids, expected, "{}", condition);
+        }
+
+        let missing: FilterCondition = serde_json::from_value(serde_json::json!({"predicate": {"column": "nope", "op": "is_null"}})).unwrap();
+        assert!(missing.to_expr(&schema).is_err());
+    }
+
+    #[test]
+    fn test_invalid_regex_is_rejected() {
+        let condition: FilterCondition = serde_json::from_value(serde_json::json!({
+            "predicate": {"column": "name", "op": "regex", "value": "("}
+        })).unwrap();
+        assert!(condition.compile().is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NullOrder {
+    First,
+    #[default]
+    Last,
+}
+
+// One key of a multi-column SORT; null placement does not flip with the direction
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SortKey {
+    pub column: String,
+    #[serde(default)]
+    pub direction: SortDirection,
+    #[serde(default)]
+    pub nulls: NullOrder,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default)]
+    pub natural: bool,
+}
+
+impl SortKey {
+    pub fn ascending(column: &str) -> Self {
+        Self {
+            column: column.to_string(),
+            direction: SortDirection::Asc,
+            nulls: NullOrder::Last,
+            case_insensitive: false,
+            natural: false,
+        }
+    }
+
+    pub fn compare_rows(&self, a: &HashMap<String, Value>, b: &HashMap<String, Value>) -> Ordering {
+        let a = a.get(&self.column).filter(|v| !v.is_null());
+        let b = b.get(&self.column).filter(|v| !v.is_null());
+
+        match (a, b) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => self.null_ordering(),
+            (Some(_), None) => self.null_ordering().reverse(),
+            (Some(a), Some(b)) => {
+                let ordering = self.compare_values(a, b);
+                match self.direction {
+                    SortDirection::Asc => ordering,
+                    SortDirection::Desc => ordering.reverse(),
+                }
+            }
+        }
+    }
+
+    fn null_ordering(&self) -> Ordering {
+        match self.nulls {
+            NullOrder::First => Ordering::Less,
+            NullOrder::Last => Ordering::Greater,
+        }
+    }
+
+    // Values of different JSON types are ordered by type: bool < number < string < array < object
+    fn compare_values(&self, a: &Value, b: &Value) -> Ordering {
+        match (a, b) {
+            (Value::Number(x), Value::Number(y)) => {
+                let x = x.as_f64().unwrap_or(0.0);
+                let y = y.as_f64().unwrap_or(0.0);
+                x.total_cmp(&y)
+            }
+            (Value::String(x), Value::String(y)) => self.compare_text(x, y),
+            (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+            _ => type_rank(a).cmp(&type_rank(b))
+                .then_with(|| self.compare_text(&a.to_string(), &b.to_string())),
+        }
+    }
+
+    fn compare_text(&self, a: &str, b: &str) -> Ordering {
+        if self.case_insensitive {
+            let a = a.to_lowercase();
+            let b = b.to_lowercase();
+            if self.natural { natural_cmp(&a, &b) } else { a.cmp(&b) }
+        } else if self.natural {
+            natural_cmp(a, b)
+        } else {
+            a.cmp(b)
+        }
+    }
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+// Stable multi-key sort: rows equal on every key keep their input order
+pub fn sort_rows(rows: &mut [HashMap<String, Value>], keys: &[SortKey]) {
+    rows.sort_by(|a, b| {
+        keys.iter()
+            .map(|key| key.compare_rows(a, b))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+// Natural ordering compares digit runs by numeric value, so "item2" < "item10"
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_run = take_digits(&mut a_chars);
+                let b_run = take_digits(&mut b_chars);
+                let a_trimmed = a_run.trim_start_matches('0');
+                let b_trimmed = b_run.trim_start_matches('0');
+
+                let ordering = a_trimmed.len().cmp(&b_trimmed.len())
+                    .then_with(|| a_trimmed.cmp(b_trimmed))
+                    .then_with(|| a_run.len().cmp(&b_run.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut run = String::new();
+    while let Some(c) = chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+        run.push(c);
+        chars.next();
+    }
+    run
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        assert_eq!(natural_cmp("item2", "item10"), Ordering::Less);
+        assert_eq!(natural_cmp("item10", "item10"), Ordering::Equal);
+        assert_eq!(natural_cmp("a", "a1"), Ordering::Less);
+        assert_eq!(natural_cmp("v1.9", "v1.10"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_multi_key_sort_is_stable_with_nulls() {
+        let mut rows: Vec<HashMap<String, Value>> = serde_json::from_value(serde_json::json!([
+            {"id": 1, "group": "b", "amount": 5.0},
+            {"id": 2, "group": "A", "amount": null},
+            {"id": 3, "group": "a", "amount": 7.0},
+            {"id": 4, "group": "B", "amount": 5.0}
+        ])).unwrap();
+
+        let keys = vec![
+            SortKey { case_insensitive: true, ..SortKey::ascending("group") },
+            SortKey { direction: SortDirection::Desc, nulls: NullOrder::First, ..SortKey::ascending("amount") },
+        ];
+        sort_rows(&mut rows, &keys);
+
+        let ids: Vec<i64> = rows.iter().map(|r| r["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, vec![2, 3, 1, 4]);
+    }
+}
@@ -0,0 +1,344 @@
+use anyhow::{Result, anyhow};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// xoshiro256** seeded through SplitMix64. Implemented here rather than taken from a crate so a
+// seed keeps selecting the same rows across dependency upgrades
+pub struct SeededRng {
+    state: [u64; 4],
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        let mut splitmix = || {
+            x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Self { state: [splitmix(), splitmix(), splitmix(), splitmix()] }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    // Uniform in [0, bound) without modulo bias
+    pub fn below(&mut self, bound: usize) -> usize {
+        let bound = bound as u64;
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % bound) as usize;
+            }
+        }
+    }
+
+    // Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+// Without an explicit seed one is drawn from the clock and reported back, so any sample can be repeated
+pub fn resolve_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplingMethod {
+    #[default]
+    Random,
+    Stratified,
+    Systematic,
+    Reservoir,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SamplingOptions {
+    pub method: SamplingMethod,
+    // Exactly one of size and fraction sets how many items to draw
+    pub size: Option<usize>,
+    pub fraction: Option<f64>,
+    pub seed: Option<u64>,
+    // Column whose values define the strata for stratified sampling and splits
+    pub stratify_by: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Sample {
+    pub method: SamplingMethod,
+    pub seed: u64,
+    pub population: usize,
+    // Ascending, so sampled rows keep their original order
+    pub indices: Vec<usize>,
+}
+
+fn sample_size(population: usize, size: Option<usize>, fraction: Option<f64>) -> Result<usize> {
+    match (size, fraction) {
+        (Some(_), Some(_)) => Err(anyhow!("Give either 'size' or 'fraction', not both")),
+        (Some(size), None) if size > population => {
+            Err(anyhow!("Sample size {} exceeds the population of {}", size, population))
+        }
+        (Some(size), None) => Ok(size),
+        (None, Some(fraction)) if (0.0..=1.0).contains(&fraction) => Ok((fraction * population as f64).round() as usize),
+        (None, Some(fraction)) => Err(anyhow!("'fraction' must lie between 0 and 1, got {}", fraction)),
+        (None, None) => Err(anyhow!("Sampling requires a 'size' or 'fraction' parameter")),
+    }
+}
+
+// Draws from positions 0..population; strata labels (one per position) are required for stratified sampling
+pub fn sample_indices(population: usize, options: &SamplingOptions, strata: Option<&[String]>) -> Result<Sample> {
+    let seed = resolve_seed(options.seed);
+    let mut rng = SeededRng::new(seed);
+
+    let mut indices = match options.method {
+        SamplingMethod::Random => {
+            let size = sample_size(population, options.size, options.fraction)?;
+            random_subset(&(0..population).collect::<Vec<_>>(), size, &mut rng)
+        }
+        SamplingMethod::Reservoir => {
+            // The population is visited once in order, as it would be when streamed
+            let size = sample_size(population, options.size, options.fraction)?;
+            reservoir(0..population, size, &mut rng).into_iter().map(|(i, _)| i).collect()
+        }
+        SamplingMethod::Systematic => {
+            let size = sample_size(population, options.size, options.fraction)?;
+            if size == 0 {
+                Vec::new()
+            } else {
+                let step = population as f64 / size as f64;
+                let start = rng.next_f64() * step;
+                (0..size).map(|i| ((start + i as f64 * step) as usize).min(population - 1)).collect()
+            }
+        }
+        SamplingMethod::Stratified => {
+            let strata = strata.ok_or_else(|| anyhow!("Stratified sampling requires a 'stratify_by' column"))?;
+            let size = sample_size(population, options.size, options.fraction)?;
+            let groups = group_positions(strata);
+            let counts: Vec<f64> = groups.values().map(|g| g.len() as f64).collect();
+            let allocation = apportion(size, &counts);
+            groups.values()
+                .zip(allocation)
+                .flat_map(|(members, take)| random_subset(members, take, &mut rng))
+                .collect()
+        }
+    };
+    indices.sort_unstable();
+
+    Ok(Sample { method: options.method, seed, population, indices })
+}
+
+fn random_subset(items: &[usize], size: usize, rng: &mut SeededRng) -> Vec<usize> {
+    // Partial Fisher-Yates: only the first `size` positions need to be settled
+    let mut pool = items.to_vec();
+    for i in 0..size.min(pool.len()) {
+        let j = i + rng.below(pool.len() - i);
+        pool.swap(i, j);
+    }
+    pool.truncate(size);
+    pool
+}
+
+// Algorithm R: keeps a uniform sample of `size` items from a stream of unknown length in O(size) memory
+pub fn reservoir<T>(items: impl IntoIterator<Item = T>, size: usize, rng: &mut SeededRng) -> Vec<(usize, T)> {
+    let mut kept: Vec<(usize, T)> = Vec::with_capacity(size);
+    for (seen, item) in items.into_iter().enumerate() {
+        if kept.len() < size {
+            kept.push((seen, item));
+        } else {
+            let slot = rng.below(seen + 1);
+            if slot < size {
+                kept[slot] = (seen, item);
+            }
+        }
+    }
+    kept.sort_by_key(|(position, _)| *position);
+    kept
+}
+
+fn group_positions(labels: &[String]) -> BTreeMap<&str, Vec<usize>> {
+    let mut groups: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        groups.entry(label.as_str()).or_default().push(i);
+    }
+    groups
+}
+
+// Splits `total` in proportion to the weights by the largest-remainder method. With group sizes as
+// weights and total <= their sum no group is given more than it has
+fn apportion(total: usize, weights: &[f64]) -> Vec<usize> {
+    let sum: f64 = weights.iter().sum();
+    if sum <= 0.0 {
+        return vec![0; weights.len()];
+    }
+    let quotas: Vec<f64> = weights.iter().map(|w| total as f64 * w / sum).collect();
+    let mut allocation: Vec<usize> = quotas.iter().map(|q| q.floor() as usize).collect();
+
+    let mut order: Vec<usize> = (0..weights.len()).collect();
+    order.sort_by(|&a, &b| (quotas[b] - quotas[b].floor()).total_cmp(&(quotas[a] - quotas[a].floor())));
+    let remaining = total.saturating_sub(allocation.iter().sum());
+    for &i in order.iter().take(remaining) {
+        allocation[i] += 1;
+    }
+    allocation
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SplitOptions {
+    pub train: f64,
+    pub test: f64,
+    pub validation: f64,
+    // Without shuffling the partitions are contiguous, e.g. for time-ordered data
+    pub shuffle: bool,
+    pub seed: Option<u64>,
+    pub stratify_by: Option<String>,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            train: 0.8,
+            test: 0.2,
+            validation: 0.0,
+            shuffle: true,
+            seed: None,
+            stratify_by: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Split {
+    pub seed: u64,
+    pub train: Vec<usize>,
+    pub test: Vec<usize>,
+    pub validation: Vec<usize>,
+}
+
+// Every position lands in exactly one partition; with strata each partition keeps the strata proportions
+pub fn split_indices(population: usize, options: &SplitOptions, strata: Option<&[String]>) -> Result<Split> {
+    let ratios = [options.train, options.test, options.validation];
+    if ratios.iter().any(|r| !(0.0..=1.0).contains(r)) || (ratios.iter().sum::<f64>() - 1.0).abs() > 1e-9 {
+        return Err(anyhow!(
+            "Split ratios must lie between 0 and 1 and sum to 1, got train {}, test {}, validation {}",
+            options.train, options.test, options.validation
+        ));
+    }
+    if strata.is_some() && !options.shuffle {
+        return Err(anyhow!("Stratified splits require shuffling"));
+    }
+
+    let seed = resolve_seed(options.seed);
+    let mut rng = SeededRng::new(seed);
+    let groups: Vec<Vec<usize>> = match strata {
+        Some(strata) => group_positions(strata).into_values().collect(),
+        None => vec![(0..population).collect()],
+    };
+
+    let mut partitions = [Vec::new(), Vec::new(), Vec::new()];
+    for mut members in groups {
+        if options.shuffle {
+            rng.shuffle(&mut members);
+        }
+        let sizes = apportion(members.len(), &ratios);
+        let mut offset = 0;
+        for (partition, size) in partitions.iter_mut().zip(sizes) {
+            partition.extend_from_slice(&members[offset..offset + size]);
+            offset += size;
+        }
+    }
+    for partition in partitions.iter_mut() {
+        partition.sort_unstable();
+    }
+
+    let [train, test, validation] = partitions;
+    Ok(Split { seed, train, test, validation })
+}
+
+// Stratum label of every row; nulls form their own stratum
+pub fn strata_labels(df: &DataFrame, column: &str) -> Result<Vec<String>> {
+    let values = df.column(column)
+        .map_err(|_| anyhow!("Column '{}' not found", column))?
+        .cast(&DataType::String)?;
+    Ok(values.str()?
+        .into_iter()
+        .map(|v| v.unwrap_or("null").to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(method: SamplingMethod, size: usize, seed: u64) -> SamplingOptions {
+        SamplingOptions { method, size: Some(size), seed: Some(seed), ..Default::default() }
+    }
+
+    #[test]
+    fn test_samples_are_reproducible_and_sized() {
+        for method in [SamplingMethod::Random, SamplingMethod::Reservoir, SamplingMethod::Systematic] {
+            let first = sample_indices(1000, &options(method, 25, 42), None).unwrap();
+            let second = sample_indices(1000, &options(method, 25, 42), None).unwrap();
+            assert_eq!(first.indices, second.indices);
+            assert_eq!(first.indices.len(), 25);
+            assert!(first.indices.windows(2).all(|w| w[0] < w[1]), "{:?}", method);
+        }
+        assert_ne!(
+            sample_indices(1000, &options(SamplingMethod::Random, 25, 1), None).unwrap().indices,
+            sample_indices(1000, &options(SamplingMethod::Random, 25, 2), None).unwrap().indices
+        );
+
+        let systematic = sample_indices(100, &options(SamplingMethod::Systematic, 10, 7), None).unwrap();
+        assert!(systematic.indices.windows(2).all(|w| w[1] - w[0] == 10));
+        assert!(sample_indices(10, &options(SamplingMethod::Random, 11, 0), None).is_err());
+    }
+
+    #[test]
+    fn test_stratified_sample_and_split_keep_proportions() {
+        let strata: Vec<String> = (0..100).map(|i| if i < 80 { "a".to_string() } else { "b".to_string() }).collect();
+        let sample = sample_indices(100, &options(SamplingMethod::Stratified, 10, 3), Some(&strata)).unwrap();
+        assert_eq!(sample.indices.iter().filter(|&&i| i < 80).count(), 8);
+        assert!(sample_indices(100, &options(SamplingMethod::Stratified, 10, 3), None).is_err());
+
+        let split_options = SplitOptions { train: 0.7, test: 0.2, validation: 0.1, seed: Some(9), ..Default::default() };
+        let split = split_indices(100, &split_options, Some(&strata)).unwrap();
+        assert_eq!((split.train.len(), split.test.len(), split.validation.len()), (70, 20, 10));
+        assert_eq!(split.test.iter().filter(|&&i| i >= 80).count(), 4);
+
+        let mut all: Vec<usize> = split.train.iter().chain(&split.test).chain(&split.validation).copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..100).collect::<Vec<_>>());
+
+        let ordered = split_indices(10, &SplitOptions { shuffle: false, ..Default::default() }, None).unwrap();
+        assert_eq!(ordered.test, vec![8, 9]);
+        assert!(split_indices(10, &SplitOptions { train: 0.5, ..Default::default() }, None).is_err());
+    }
+}
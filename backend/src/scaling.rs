@@ -0,0 +1,295 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::quantiles::{quantile_sorted, QuantileMethod};
+
+// Power-transform lambdas are searched within this range, as in scipy
+const LAMBDA_RANGE: (f64, f64) = (-5.0, 5.0);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalingMethod {
+    #[default]
+    #[serde(alias = "z_score")]
+    Standard,
+    MinMax,
+    Robust,
+    MaxAbs,
+    BoxCox,
+    YeoJohnson,
+    Log1p,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ScalingOptions {
+    pub method: ScalingMethod,
+    pub feature_range: (f64, f64),
+    // Fixed power-transform lambda; estimated by maximum likelihood when absent
+    pub lambda: Option<f64>,
+    // Parameters returned by an earlier call; when given, nothing is refitted
+    pub fitted: Option<FittedScaler>,
+}
+
+impl Default for ScalingOptions {
+    fn default() -> Self {
+        Self {
+            method: ScalingMethod::Standard,
+            feature_range: (0.0, 1.0),
+            lambda: None,
+            fitted: None,
+        }
+    }
+}
+
+// Everything needed to apply the same transform to new data
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum FittedScaler {
+    Standard { mean: f64, std: f64 },
+    MinMax { min: f64, max: f64, feature_range: (f64, f64) },
+    Robust { median: f64, iqr: f64 },
+    MaxAbs { max_abs: f64 },
+    BoxCox { lambda: f64 },
+    YeoJohnson { lambda: f64 },
+    Log1p,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScalingResult {
+    // Null where the input was non-finite or outside the transform's domain
+    pub transformed_data: Vec<Option<f64>>,
+    pub fitted: FittedScaler,
+    pub count: usize,
+    pub non_finite_count: usize,
+    pub out_of_domain_count: usize,
+    pub warnings: Vec<String>,
+}
+
+pub fn scale(values: &[f64], options: &ScalingOptions) -> Result<ScalingResult> {
+    let mut warnings = Vec::new();
+    let fitted = match &options.fitted {
+        Some(fitted) => fitted.clone(),
+        None => fit(values, options, &mut warnings)?,
+    };
+
+    let non_finite_count = values.iter().filter(|v| !v.is_finite()).count();
+    let transformed_data: Vec<Option<f64>> = values.iter()
+        .map(|&v| if v.is_finite() { fitted.transform(v) } else { None })
+        .collect();
+    let out_of_domain_count = transformed_data.iter().filter(|v| v.is_none()).count() - non_finite_count;
+    if out_of_domain_count > 0 {
+        warnings.push(format!("{} values lie outside the transform's domain and were set to null", out_of_domain_count));
+    }
+
+    Ok(ScalingResult {
+        transformed_data,
+        fitted,
+        count: values.len(),
+        non_finite_count,
+        out_of_domain_count,
+        warnings,
+    })
+}
+
+// Fitting uses finite values only; a zero spread is replaced by 1 so constant data maps to a
+// constant instead of NaN
+fn fit(values: &[f64], options: &ScalingOptions, warnings: &mut Vec<String>) -> Result<FittedScaler> {
+    let mut finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if finite.is_empty() && options.method != ScalingMethod::Log1p {
+        return Err(anyhow!("Cannot fit a scaler without finite values"));
+    }
+    finite.sort_by(f64::total_cmp);
+
+    let mut nonzero = |spread: f64, name: &str| {
+        if spread > 0.0 {
+            spread
+        } else {
+            warnings.push(format!("The {} is zero; using 1 so constant data maps to a constant", name));
+            1.0
+        }
+    };
+
+    Ok(match options.method {
+        ScalingMethod::Standard => {
+            let n = finite.len() as f64;
+            let mean = finite.iter().sum::<f64>() / n;
+            let std = (finite.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+            FittedScaler::Standard { mean, std: nonzero(std, "standard deviation") }
+        }
+        ScalingMethod::MinMax => {
+            let (low, high) = options.feature_range;
+            if !low.is_finite() || !high.is_finite() || low >= high {
+                return Err(anyhow!("feature_range must be increasing, got ({}, {})", low, high));
+            }
+            let (min, max) = (finite[0], finite[finite.len() - 1]);
+            if min == max {
+                warnings.push("The range is zero; every value maps to the lower end of feature_range".to_string());
+            }
+            FittedScaler::MinMax { min, max, feature_range: options.feature_range }
+        }
+        ScalingMethod::Robust => {
+            let median = quantile_sorted(&finite, 0.5, QuantileMethod::Linear);
+            let iqr = quantile_sorted(&finite, 0.75, QuantileMethod::Linear) - quantile_sorted(&finite, 0.25, QuantileMethod::Linear);
+            FittedScaler::Robust { median, iqr: nonzero(iqr, "interquartile range") }
+        }
+        ScalingMethod::MaxAbs => {
+            let max_abs = finite.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+            FittedScaler::MaxAbs { max_abs: nonzero(max_abs, "maximum absolute value") }
+        }
+        ScalingMethod::BoxCox => {
+            if finite[0] <= 0.0 {
+                return Err(anyhow!("Box-Cox requires strictly positive values (minimum is {}); use yeo_johnson instead", finite[0]));
+            }
+            let lambda = match options.lambda {
+                Some(lambda) => lambda,
+                None => maximise(|lambda| power_log_likelihood(&finite, lambda, box_cox, |x| x.ln())),
+            };
+            FittedScaler::BoxCox { lambda }
+        }
+        ScalingMethod::YeoJohnson => {
+            let lambda = match options.lambda {
+                Some(lambda) => lambda,
+                None => maximise(|lambda| power_log_likelihood(&finite, lambda, yeo_johnson, |x| x.signum() * x.abs().ln_1p())),
+            };
+            FittedScaler::YeoJohnson { lambda }
+        }
+        ScalingMethod::Log1p => FittedScaler::Log1p,
+    })
+}
+
+impl FittedScaler {
+    pub fn transform(&self, value: f64) -> Option<f64> {
+        let result = match *self {
+            FittedScaler::Standard { mean, std } => (value - mean) / std,
+            FittedScaler::MinMax { min, max, feature_range: (low, high) } => {
+                let range = if max > min { max - min } else { 1.0 };
+                low + (value - min) / range * (high - low)
+            }
+            FittedScaler::Robust { median, iqr } => (value - median) / iqr,
+            FittedScaler::MaxAbs { max_abs } => value / max_abs,
+            FittedScaler::BoxCox { lambda } => {
+                if value <= 0.0 {
+                    return None;
+                }
+                box_cox(value, lambda)
+            }
+            FittedScaler::YeoJohnson { lambda } => yeo_johnson(value, lambda),
+            FittedScaler::Log1p => {
+                if value <= -1.0 {
+                    return None;
+                }
+                value.ln_1p()
+            }
+        };
+        result.is_finite().then_some(result)
+    }
+}
+
+fn box_cox(x: f64, lambda: f64) -> f64 {
+    if lambda.abs() < 1e-12 {
+        x.ln()
+    } else {
+        (x.powf(lambda) - 1.0) / lambda
+    }
+}
+
+fn yeo_johnson(x: f64, lambda: f64) -> f64 {
+    if x >= 0.0 {
+        if lambda.abs() < 1e-12 {
+            x.ln_1p()
+        } else {
+            ((x + 1.0).powf(lambda) - 1.0) / lambda
+        }
+    } else if (lambda - 2.0).abs() < 1e-12 {
+        -(-x).ln_1p()
+    } else {
+        -((1.0 - x).powf(2.0 - lambda) - 1.0) / (2.0 - lambda)
+    }
+}
+
+// Profile log-likelihood of a normal fit to the transformed data, including the Jacobian term
+fn power_log_likelihood(values: &[f64], lambda: f64, transform: fn(f64, f64) -> f64, log_jacobian: impl Fn(f64) -> f64) -> f64 {
+    let n = values.len() as f64;
+    let transformed: Vec<f64> = values.iter().map(|&x| transform(x, lambda)).collect();
+    let mean = transformed.iter().sum::<f64>() / n;
+    let variance = transformed.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    if !variance.is_finite() || variance <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    -n / 2.0 * variance.ln() + (lambda - 1.0) * values.iter().map(|&x| log_jacobian(x)).sum::<f64>()
+}
+
+// Golden-section search; the profile likelihood is unimodal in lambda for these transforms
+fn maximise(objective: impl Fn(f64) -> f64) -> f64 {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = LAMBDA_RANGE;
+    let mut left = high - ratio * (high - low);
+    let mut right = low + ratio * (high - low);
+    let (mut f_left, mut f_right) = (objective(left), objective(right));
+
+    while high - low > 1e-8 {
+        if f_left >= f_right {
+            high = right;
+            right = left;
+            f_right = f_left;
+            left = high - ratio * (high - low);
+            f_left = objective(left);
+        } else {
+            low = left;
+            left = right;
+            f_left = f_right;
+            right = low + ratio * (high - low);
+            f_right = objective(right);
+        }
+    }
+    (low + high) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(values: &[f64], method: ScalingMethod) -> ScalingResult {
+        scale(values, &ScalingOptions { method, ..Default::default() }).unwrap()
+    }
+
+    #[test]
+    fn test_linear_scalers_and_reuse() {
+        let values = [1.0, 2.0, 3.0, 4.0, f64::NAN, 5.0];
+
+        let min_max = run(&values, ScalingMethod::MinMax);
+        assert_eq!(min_max.transformed_data, vec![Some(0.0), Some(0.25), Some(0.5), Some(0.75), None, Some(1.0)]);
+        assert_eq!(min_max.non_finite_count, 1);
+
+        let robust = run(&values, ScalingMethod::Robust);
+        assert_eq!(robust.fitted, FittedScaler::Robust { median: 3.0, iqr: 2.0 });
+        assert_eq!(run(&[-4.0, 2.0], ScalingMethod::MaxAbs).transformed_data, vec![Some(-1.0), Some(0.5)]);
+
+        // Parameters fitted on one batch are applied unchanged to the next
+        let reused = scale(&[6.0, 0.0], &ScalingOptions { fitted: Some(min_max.fitted.clone()), ..Default::default() }).unwrap();
+        assert_eq!(reused.transformed_data, vec![Some(1.25), Some(-0.25)]);
+
+        let constant = run(&[7.0, 7.0, 7.0], ScalingMethod::Standard);
+        assert_eq!(constant.transformed_data, vec![Some(0.0); 3]);
+        assert_eq!(constant.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_power_transforms_and_log1p() {
+        // Exponential growth is made linear by a log, so Box-Cox should pick lambda near zero
+        let values: Vec<f64> = (0..50).map(|i| (i as f64 / 10.0).exp()).collect();
+        let box_cox = run(&values, ScalingMethod::BoxCox);
+        let FittedScaler::BoxCox { lambda } = box_cox.fitted else { panic!("unexpected {:?}", box_cox.fitted) };
+        assert!(lambda.abs() < 0.3, "lambda {}", lambda);
+        assert!(scale(&[0.0, 1.0], &ScalingOptions { method: ScalingMethod::BoxCox, ..Default::default() }).is_err());
+
+        let yeo_johnson = scale(&[-2.0, 0.0, 3.0], &ScalingOptions { method: ScalingMethod::YeoJohnson, lambda: Some(1.0), ..Default::default() }).unwrap();
+        assert_eq!(yeo_johnson.transformed_data, vec![Some(-2.0), Some(0.0), Some(3.0)]);
+
+        let log = run(&[0.0, -1.0, f64::INFINITY, 3.0], ScalingMethod::Log1p);
+        assert_eq!(log.transformed_data[0], Some(0.0));
+        assert_eq!((log.transformed_data[1], log.transformed_data[2]), (None, None));
+        assert_eq!((log.out_of_domain_count, log.non_finite_count), (1, 1));
+    }
+}
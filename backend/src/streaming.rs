@@ -0,0 +1,287 @@
+use anyhow::{Result, anyhow};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tracing::info;
+
+use crate::data_processor::dataframe_to_records;
+use crate::dataset::{parse_dtype, DatasetFormat, LoadOptions, TextEncoding};
+use crate::export::{self, ExportSummary, ExportTarget};
+use crate::row_filter::FilterCondition;
+
+// Rows returned in the response when a query is not exported
+const DEFAULT_RESULT_LIMIT: usize = 1000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamAggregation {
+    Count,
+    NullCount,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Std,
+    Var,
+}
+
+impl StreamAggregation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            StreamAggregation::Count => "count",
+            StreamAggregation::NullCount => "null_count",
+            StreamAggregation::Sum => "sum",
+            StreamAggregation::Mean => "mean",
+            StreamAggregation::Min => "min",
+            StreamAggregation::Max => "max",
+            StreamAggregation::Std => "std",
+            StreamAggregation::Var => "var",
+        }
+    }
+}
+
+// A count without a column counts rows; every other function needs a column
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AggregationSpec {
+    pub function: StreamAggregation,
+    #[serde(default)]
+    pub column: Option<String>,
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+impl AggregationSpec {
+    fn to_expr(&self) -> Result<Expr> {
+        let Some(column) = self.column.as_deref() else {
+            return match self.function {
+                StreamAggregation::Count => Ok(len().alias(self.alias.as_deref().unwrap_or("count"))),
+                other => Err(anyhow!("Aggregation '{}' requires a column", other.as_str())),
+            };
+        };
+
+        let expr = col(column);
+        let aggregated = match self.function {
+            StreamAggregation::Count => expr.count(),
+            StreamAggregation::NullCount => expr.null_count(),
+            StreamAggregation::Sum => expr.sum(),
+            StreamAggregation::Mean => expr.mean(),
+            StreamAggregation::Min => expr.min(),
+            StreamAggregation::Max => expr.max(),
+            StreamAggregation::Std => expr.std(1),
+            StreamAggregation::Var => expr.var(1),
+        };
+        let alias = self.alias.clone().unwrap_or_else(|| format!("{}_{}", column, self.function.as_str()));
+        Ok(aggregated.alias(alias))
+    }
+}
+
+// Query over a local file executed batch by batch: filter, then project or aggregate, then either
+// return the (limited) result or write all of it to disk
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct StreamQuery {
+    pub filter: Option<FilterCondition>,
+    pub columns: Option<Vec<String>>,
+    pub group_by: Vec<String>,
+    pub aggregations: Vec<AggregationSpec>,
+    pub limit: Option<usize>,
+    pub export: Option<ExportTarget>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StreamResult {
+    pub format: String,
+    pub columns: Vec<String>,
+    pub rows: usize,
+    // Present when the result is returned inline rather than exported
+    pub data: Option<Vec<Value>>,
+    pub truncated: bool,
+    pub export: Option<ExportSummary>,
+}
+
+// Only formats that can be read in batches; JSON documents and workbooks must be parsed whole
+pub fn scan_file(path: &Path, options: &LoadOptions) -> Result<LazyFrame> {
+    let format = options.format
+        .or_else(|| DatasetFormat::from_path(path))
+        .ok_or_else(|| anyhow!("Cannot determine file format of '{}'; pass an explicit format", path.display()))?;
+    if !path.is_file() {
+        return Err(anyhow!("File '{}' does not exist", path.display()));
+    }
+    let location = PlPath::new(&path.to_string_lossy());
+
+    let frame = match format {
+        DatasetFormat::Csv | DatasetFormat::Tsv => {
+            let default_separator = if format == DatasetFormat::Tsv { '\t' } else { ',' };
+            let separator = ascii_byte(options.delimiter.unwrap_or(default_separator), "delimiter")?;
+            let quote = options.quote_char.map(|q| ascii_byte(q, "quote_char")).transpose()?;
+            let encoding = match options.encoding {
+                TextEncoding::Utf8 => CsvEncoding::Utf8,
+                TextEncoding::Utf8Lossy => CsvEncoding::LossyUtf8,
+                TextEncoding::Latin1 => return Err(anyhow!("Latin-1 files cannot be streamed; load the dataset instead or re-encode it as UTF-8")),
+            };
+            let overrides: Schema = options.schema_overrides.iter()
+                .map(|(name, dtype)| Ok(Field::new(name.as_str().into(), parse_dtype(dtype)?)))
+                .collect::<Result<_>>()?;
+            let null_values = (!options.null_values.is_empty()).then(|| {
+                NullValues::AllColumns(options.null_values.iter().map(|v| v.as_str().into()).collect())
+            });
+
+            LazyCsvReader::new(location)
+                .with_has_header(options.has_header)
+                .with_skip_rows(options.skip_rows)
+                .with_infer_schema_length(options.infer_schema_length)
+                .with_dtype_overwrite((!overrides.is_empty()).then(|| Arc::new(overrides)))
+                .with_separator(separator)
+                .with_quote_char(quote)
+                .with_encoding(encoding)
+                .with_null_values(null_values)
+                .finish()?
+        }
+        DatasetFormat::Parquet => LazyFrame::scan_parquet(location, ScanArgsParquet::default())?,
+        other => return Err(anyhow!("Streaming supports CSV, TSV and Parquet files, not {}", other.as_str())),
+    };
+
+    info!("Scanning {} file {} in streaming mode", format.as_str(), path.display());
+    Ok(frame)
+}
+
+fn ascii_byte(c: char, option: &str) -> Result<u8> {
+    if c.is_ascii() {
+        Ok(c as u8)
+    } else {
+        Err(anyhow!("{} must be a single ASCII character, got '{}'", option, c))
+    }
+}
+
+pub fn build_query(frame: LazyFrame, query: &StreamQuery) -> Result<LazyFrame> {
+    let mut frame = frame;
+    if let Some(filter) = &query.filter {
+        let schema = frame.collect_schema()?;
+        frame = frame.filter(filter.to_expr(&schema)?);
+    }
+
+    if query.aggregations.is_empty() {
+        if !query.group_by.is_empty() {
+            return Err(anyhow!("group_by requires at least one aggregation"));
+        }
+        if let Some(columns) = &query.columns {
+            frame = frame.select(columns.iter().map(|c| col(c.as_str())).collect::<Vec<_>>());
+        }
+        return Ok(frame);
+    }
+
+    let aggregations = query.aggregations.iter().map(|a| a.to_expr()).collect::<Result<Vec<_>>>()?;
+    Ok(if query.group_by.is_empty() {
+        frame.select(aggregations)
+    } else {
+        let keys: Vec<Expr> = query.group_by.iter().map(|c| col(c.as_str())).collect();
+        // Group order is otherwise nondeterministic under the streaming engine
+        frame.group_by(keys).agg(aggregations).sort(query.group_by.clone(), SortMultipleOptions::default())
+    })
+}
+
+pub fn run(path: &Path, options: &LoadOptions, query: &StreamQuery) -> Result<StreamResult> {
+    let format = options.format.or_else(|| DatasetFormat::from_path(path)).map(|f| f.as_str()).unwrap_or("unknown");
+    let mut frame = build_query(scan_file(path, options)?, query)?;
+    let columns: Vec<String> = frame.collect_schema()?.iter_names().map(|n| n.to_string()).collect();
+
+    if let Some(target) = &query.export {
+        let summary = export::export_lazy(frame, Path::new(&target.path), &target.options)?;
+        return Ok(StreamResult {
+            format: format.to_string(),
+            columns,
+            rows: summary.rows,
+            data: None,
+            truncated: false,
+            export: Some(summary),
+        });
+    }
+
+    // One extra row tells whether the inline result was cut short
+    let limit = query.limit.unwrap_or(DEFAULT_RESULT_LIMIT);
+    let result = frame.limit(limit as IdxSize + 1).collect_with_engine(Engine::Streaming)?;
+    let truncated = result.height() > limit;
+    let result = result.head(Some(limit));
+
+    Ok(StreamResult {
+        format: format.to_string(),
+        columns,
+        rows: result.height(),
+        data: Some(dataframe_to_records(&result)?),
+        truncated,
+        export: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::ExportOptions;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn write_csv(rows: usize) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("stream-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut csv = String::from("id,region,amount\n");
+        for i in 0..rows {
+            let region = ["North", "South", "East"][i % 3];
+            let amount = if i % 10 == 0 { String::new() } else { (i % 7).to_string() };
+            csv.push_str(&format!("{},{},{}\n", i, region, amount));
+        }
+        let path = dir.join("sales.csv");
+        std::fs::write(&path, csv).unwrap();
+        (dir, path)
+    }
+
+    fn query(value: Value) -> StreamQuery {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_filtered_group_aggregation() {
+        let (dir, path) = write_csv(3000);
+        let result = run(&path, &LoadOptions::default(), &query(serde_json::json!({
+            "filter": {"predicate": {"column": "region", "op": "in", "value": ["North", "East"]}},
+            "group_by": ["region"],
+            "aggregations": [
+                {"function": "count"},
+                {"function": "null_count", "column": "amount"},
+                {"function": "max", "column": "amount", "alias": "largest"}
+            ]
+        }))).unwrap();
+
+        assert_eq!(result.columns, vec!["region", "count", "amount_null_count", "largest"]);
+        let data = result.data.unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!((data[0]["region"].clone(), data[0]["count"].clone()), (Value::from("East"), Value::from(1000)));
+        assert_eq!(data[1]["amount_null_count"], 100);
+        assert_eq!(data[1]["largest"], 6);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_limit_and_streamed_export() {
+        let (dir, path) = write_csv(500);
+        let limited = run(&path, &LoadOptions::default(), &query(serde_json::json!({"columns": ["id"], "limit": 10}))).unwrap();
+        assert_eq!((limited.rows, limited.truncated), (10, true));
+
+        let target = ExportTarget { path: dir.join("south.parquet").display().to_string(), options: ExportOptions::default() };
+        let exported = run(&path, &LoadOptions::default(), &StreamQuery {
+            filter: Some(serde_json::from_value(serde_json::json!({"predicate": {"column": "region", "op": "eq", "value": "South"}})).unwrap()),
+            export: Some(target),
+            ..Default::default()
+        }).unwrap();
+        let summary = exported.export.unwrap();
+        assert_eq!((summary.rows, summary.columns, exported.rows), (167, 3, 167));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        let options = ExportOptions { compression: Some(export::ExportCompression::Gzip), ..Default::default() };
+        let gzip = ExportTarget { path: dir.join("out.csv").display().to_string(), options };
+        assert!(run(&path, &LoadOptions::default(), &StreamQuery { export: Some(gzip), ..Default::default() }).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
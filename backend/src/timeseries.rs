@@ -0,0 +1,610 @@
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::data_processor::finite_or_null;
+use crate::dataset::{parse_date_text, parse_datetime_text};
+use crate::quantiles::{quantile_sorted, QuantileMethod};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    #[default]
+    #[serde(alias = "D", alias = "daily")]
+    Day,
+    #[serde(alias = "W", alias = "weekly")]
+    Week,
+    #[serde(alias = "M", alias = "monthly")]
+    Month,
+    #[serde(alias = "Q", alias = "quarterly")]
+    Quarter,
+}
+
+impl Frequency {
+    // Weeks start on Monday (ISO 8601); months and quarters on their first day
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Frequency::Day => date,
+            Frequency::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Frequency::Month => date.with_day(1).unwrap_or(date),
+            Frequency::Quarter => date.with_day(1)
+                .and_then(|d| d.with_month(date.month0() / 3 * 3 + 1))
+                .unwrap_or(date),
+        }
+    }
+
+    pub fn next_period(&self, start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Frequency::Day => start.checked_add_signed(Duration::days(1)),
+            Frequency::Week => start.checked_add_signed(Duration::days(7)),
+            Frequency::Month => start.checked_add_months(Months::new(1)),
+            Frequency::Quarter => start.checked_add_months(Months::new(3)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    Sum,
+    Mean,
+    Median,
+    Min,
+    Max,
+    Count,
+    First,
+    Last,
+    Std,
+}
+
+impl Aggregation {
+    // Values are the non-null observations in order; empty input gives 0 for sum and count, null otherwise
+    pub fn apply(&self, values: &[f64]) -> Option<f64> {
+        if values.is_empty() {
+            return matches!(self, Aggregation::Sum | Aggregation::Count).then_some(0.0);
+        }
+        let n = values.len() as f64;
+        match self {
+            Aggregation::Sum => Some(values.iter().sum()),
+            Aggregation::Mean => Some(values.iter().sum::<f64>() / n),
+            Aggregation::Median => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                Some(quantile_sorted(&sorted, 0.5, QuantileMethod::Linear))
+            }
+            Aggregation::Min => values.iter().copied().reduce(f64::min),
+            Aggregation::Max => values.iter().copied().reduce(f64::max),
+            Aggregation::Count => Some(n),
+            Aggregation::First => values.first().copied(),
+            Aggregation::Last => values.last().copied(),
+            Aggregation::Std => {
+                if values.len() < 2 {
+                    return None;
+                }
+                let mean = values.iter().sum::<f64>() / n;
+                Some((values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FillMethod {
+    // Insert the missing periods with null values
+    #[default]
+    Null,
+    Zero,
+    #[serde(alias = "ffill")]
+    Forward,
+    #[serde(alias = "bfill")]
+    Backward,
+    Linear,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DecompositionModel {
+    #[default]
+    Additive,
+    Multiplicative,
+}
+
+// Parameters shared by the time-series table operations; unrelated keys are ignored
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TimeSeriesOptions {
+    pub time_column: Option<String>,
+    pub value_column: Option<String>,
+    pub frequency: Frequency,
+    // Defaults to sum when resampling and mean for rolling windows
+    pub aggregation: Option<Aggregation>,
+    pub include_empty: bool,
+    pub window: usize,
+    pub min_periods: Option<usize>,
+    pub center: bool,
+    pub periods: usize,
+    pub fill_method: FillMethod,
+    pub period: Option<usize>,
+    pub model: DecompositionModel,
+}
+
+impl Default for TimeSeriesOptions {
+    fn default() -> Self {
+        Self {
+            time_column: None,
+            value_column: None,
+            frequency: Frequency::Day,
+            aggregation: None,
+            include_empty: true,
+            window: 7,
+            min_periods: None,
+            center: false,
+            periods: 1,
+            fill_method: FillMethod::Null,
+            period: None,
+            model: DecompositionModel::Additive,
+        }
+    }
+}
+
+impl TimeSeriesOptions {
+    pub fn columns(&self) -> Result<(&str, &str)> {
+        let time_column = self.time_column.as_deref()
+            .ok_or_else(|| anyhow!("Time-series operations require a 'time_column' parameter"))?;
+        let value_column = self.value_column.as_deref()
+            .ok_or_else(|| anyhow!("Time-series operations require a 'value_column' parameter"))?;
+        Ok((time_column, value_column))
+    }
+}
+
+// Observations sorted by time; rows without a usable timestamp are dropped
+pub struct TimeSeries {
+    pub timestamps: Vec<NaiveDateTime>,
+    pub values: Vec<Option<f64>>,
+    pub date_only: bool,
+    pub dropped_rows: usize,
+}
+
+impl TimeSeries {
+    // Values for methods that need every observation present
+    pub fn complete_values(&self, purpose: &str) -> Result<Vec<f64>> {
+        let missing = self.values.iter().filter(|v| v.is_none()).count();
+        if missing > 0 {
+            return Err(anyhow!("Series has {} missing values; fill gaps before {}", missing, purpose));
+        }
+        Ok(self.values.iter().flatten().copied().collect())
+    }
+
+    pub fn format_timestamp(&self, timestamp: &NaiveDateTime) -> String {
+        if self.date_only {
+            timestamp.format("%Y-%m-%d").to_string()
+        } else {
+            timestamp.format("%Y-%m-%dT%H:%M:%S").to_string()
+        }
+    }
+}
+
+pub fn series_from_dataframe(df: &DataFrame, time_column: &str, value_column: &str) -> Result<TimeSeries> {
+    let time = df.column(time_column).map_err(|_| anyhow!("Column '{}' not found", time_column))?;
+    let midnight = |date: NaiveDate| date.and_time(NaiveTime::MIN);
+    let timestamps: Vec<Option<NaiveDateTime>> = match time.dtype() {
+        DataType::Date => time.date()?.as_date_iter().map(|d| d.map(midnight)).collect(),
+        DataType::Datetime(_, _) => time.datetime()?.as_datetime_iter().collect(),
+        DataType::String => time.str()?.into_iter()
+            .map(|text| text.map(str::trim).and_then(|text| parse_datetime_text(text).or_else(|| parse_date_text(text).map(midnight))))
+            .collect(),
+        other => return Err(anyhow!("Column '{}' has type {} and cannot be read as timestamps", time_column, other)),
+    };
+
+    let value = df.column(value_column).map_err(|_| anyhow!("Column '{}' not found", value_column))?;
+    if !value.dtype().is_primitive_numeric() {
+        return Err(anyhow!("Column '{}' has type {} and is not numeric", value_column, value.dtype()));
+    }
+    let values: Vec<Option<f64>> = value.cast(&DataType::Float64)?.f64()?
+        .into_iter()
+        .map(|v| v.filter(|v| !v.is_nan()))
+        .collect();
+
+    let mut rows: Vec<(NaiveDateTime, Option<f64>)> = timestamps.into_iter()
+        .zip(values)
+        .filter_map(|(timestamp, value)| timestamp.map(|t| (t, value)))
+        .collect();
+    let dropped_rows = df.height() - rows.len();
+    if rows.is_empty() {
+        return Err(anyhow!("Column '{}' has no parseable timestamps", time_column));
+    }
+    rows.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let date_only = *time.dtype() == DataType::Date || rows.iter().all(|(t, _)| t.time() == NaiveTime::MIN);
+    let (timestamps, values) = rows.into_iter().unzip();
+    Ok(TimeSeries { timestamps, values, date_only, dropped_rows })
+}
+
+pub struct Bucket {
+    pub start: NaiveDate,
+    pub value: Option<f64>,
+    pub count: usize,
+}
+
+// Aggregates observations into calendar periods; empty periods between the first and last are kept when asked
+pub fn resample(series: &TimeSeries, frequency: Frequency, aggregation: Aggregation, include_empty: bool) -> Vec<Bucket> {
+    let mut buckets: Vec<(NaiveDate, Vec<f64>, usize)> = Vec::new();
+    for (timestamp, value) in series.timestamps.iter().zip(&series.values) {
+        let start = frequency.period_start(timestamp.date());
+        if buckets.last().is_none_or(|(last, _, _)| *last != start) {
+            if include_empty {
+                if let Some(mut next) = buckets.last().and_then(|(last, _, _)| frequency.next_period(*last)) {
+                    while next < start {
+                        buckets.push((next, Vec::new(), 0));
+                        match frequency.next_period(next) {
+                            Some(following) => next = following,
+                            None => break,
+                        }
+                    }
+                }
+            }
+            buckets.push((start, Vec::new(), 0));
+        }
+        if let Some((_, values, count)) = buckets.last_mut() {
+            *count += 1;
+            values.extend(value);
+        }
+    }
+
+    buckets.into_iter()
+        .map(|(start, values, count)| Bucket { start, value: aggregation.apply(&values), count })
+        .collect()
+}
+
+// Windows count observations, not calendar time; nulls inside a window are skipped and
+// a window needs at least min_periods non-null values to produce a result
+pub fn rolling(values: &[Option<f64>], window: usize, min_periods: usize, center: bool, aggregation: Aggregation) -> Result<Vec<Option<f64>>> {
+    if window == 0 {
+        return Err(anyhow!("Rolling window must be at least 1"));
+    }
+    if min_periods == 0 || min_periods > window {
+        return Err(anyhow!("min_periods must be between 1 and the window size ({}), got {}", window, min_periods));
+    }
+
+    Ok((0..values.len())
+        .map(|i| {
+            let start = if center { i.saturating_sub(window / 2) } else { (i + 1).saturating_sub(window) };
+            let end = if center { (i + window - window / 2).min(values.len()) } else { i + 1 };
+            let present: Vec<f64> = values[start..end].iter().flatten().copied().collect();
+            if present.len() < min_periods {
+                None
+            } else {
+                aggregation.apply(&present)
+            }
+        })
+        .collect())
+}
+
+pub fn difference(values: &[Option<f64>], periods: usize) -> Vec<Option<f64>> {
+    lagged(values, periods, |current, previous| Some(current - previous))
+}
+
+// Relative change against the value `periods` observations earlier; undefined when that value is zero
+pub fn pct_change(values: &[Option<f64>], periods: usize) -> Vec<Option<f64>> {
+    lagged(values, periods, |current, previous| (previous != 0.0).then(|| (current - previous) / previous))
+}
+
+fn lagged(values: &[Option<f64>], periods: usize, compare: impl Fn(f64, f64) -> Option<f64>) -> Vec<Option<f64>> {
+    (0..values.len())
+        .map(|i| {
+            let previous = i.checked_sub(periods).and_then(|j| values[j])?;
+            compare(values[i]?, previous)
+        })
+        .collect()
+}
+
+pub struct FilledPoint {
+    pub start: NaiveDate,
+    pub value: Option<f64>,
+    pub filled: bool,
+}
+
+// Completes a series with one observation per period: missing periods are inserted and
+// they, together with existing nulls, are filled by the chosen method
+pub fn fill_gaps(series: &TimeSeries, frequency: Frequency, method: FillMethod) -> Result<Vec<FilledPoint>> {
+    let buckets = resample(series, frequency, Aggregation::Last, true);
+    if let Some(duplicate) = buckets.iter().find(|b| b.count > 1) {
+        return Err(anyhow!(
+            "Period starting {} has {} observations; resample the series before filling gaps",
+            duplicate.start, duplicate.count
+        ));
+    }
+
+    let mut points: Vec<FilledPoint> = buckets.into_iter()
+        .map(|b| FilledPoint { start: b.start, filled: b.value.is_none(), value: b.value })
+        .collect();
+
+    match method {
+        FillMethod::Null => {}
+        FillMethod::Zero => {
+            for point in points.iter_mut().filter(|p| p.filled) {
+                point.value = Some(0.0);
+            }
+        }
+        FillMethod::Forward => {
+            let mut last = None;
+            for point in points.iter_mut() {
+                if point.filled {
+                    point.value = last;
+                }
+                last = point.value;
+            }
+        }
+        FillMethod::Backward => {
+            let mut next = None;
+            for point in points.iter_mut().rev() {
+                if point.filled {
+                    point.value = next;
+                }
+                next = point.value;
+            }
+        }
+        // Interpolates by period position; gaps before the first or after the last value stay null
+        FillMethod::Linear => {
+            let known: Vec<(usize, f64)> = points.iter()
+                .enumerate()
+                .filter_map(|(i, p)| p.value.map(|v| (i, v)))
+                .collect();
+            for pair in known.windows(2) {
+                let ((left, left_value), (right, right_value)) = (pair[0], pair[1]);
+                for (i, point) in points.iter_mut().enumerate().take(right).skip(left + 1) {
+                    let fraction = (i - left) as f64 / (right - left) as f64;
+                    point.value = Some(left_value + (right_value - left_value) * fraction);
+                }
+            }
+        }
+    }
+    Ok(points)
+}
+
+pub struct Decomposition {
+    pub trend: Vec<Option<f64>>,
+    pub seasonal: Vec<f64>,
+    pub residual: Vec<Option<f64>>,
+    pub seasonal_indices: Vec<f64>,
+}
+
+// Classical decomposition: a centred moving average of one season (2 x period for even periods)
+// gives the trend, and the average detrended value at each position in the season gives the
+// seasonal index. Assumes evenly spaced observations; resample or fill gaps first
+pub fn seasonal_decompose(values: &[f64], period: usize, model: DecompositionModel) -> Result<Decomposition> {
+    let n = values.len();
+    if period < 2 {
+        return Err(anyhow!("Seasonal period must be at least 2, got {}", period));
+    }
+    if n < 2 * period {
+        return Err(anyhow!("Decomposition with period {} needs at least {} observations, got {}", period, 2 * period, n));
+    }
+    if model == DecompositionModel::Multiplicative && values.iter().any(|&v| v <= 0.0) {
+        return Err(anyhow!("Multiplicative decomposition requires strictly positive values"));
+    }
+
+    let half = period / 2;
+    let mut trend = vec![None; n];
+    for (i, slot) in trend.iter_mut().enumerate().take(n - half).skip(half) {
+        let window = &values[i - half..=i + half];
+        *slot = Some(if period % 2 == 1 {
+            window.iter().sum::<f64>() / period as f64
+        } else {
+            (window[1..period].iter().sum::<f64>() + 0.5 * (window[0] + window[period])) / period as f64
+        });
+    }
+
+    let detrend = |value: f64, trend: f64| match model {
+        DecompositionModel::Additive => value - trend,
+        DecompositionModel::Multiplicative => value / trend,
+    };
+
+    let mut sums = vec![0.0; period];
+    let mut counts = vec![0usize; period];
+    for (i, t) in trend.iter().enumerate() {
+        if let Some(t) = t {
+            sums[i % period] += detrend(values[i], *t);
+            counts[i % period] += 1;
+        }
+    }
+    let raw: Vec<f64> = sums.iter().zip(&counts).map(|(s, &c)| s / c as f64).collect();
+
+    // Normalise so the seasonal effect cancels over one full season
+    let mean = raw.iter().sum::<f64>() / period as f64;
+    let seasonal_indices: Vec<f64> = raw.iter()
+        .map(|&s| match model {
+            DecompositionModel::Additive => s - mean,
+            DecompositionModel::Multiplicative => s / mean,
+        })
+        .collect();
+
+    let seasonal: Vec<f64> = (0..n).map(|i| seasonal_indices[i % period]).collect();
+    let residual = trend.iter()
+        .enumerate()
+        .map(|(i, t)| t.map(|t| detrend(detrend(values[i], t), seasonal[i])))
+        .collect();
+
+    Ok(Decomposition { trend, seasonal, residual, seasonal_indices })
+}
+
+// Runs one of the time-series table operations and shapes its output for the API
+pub fn run_operation(df: &DataFrame, operation: &str, options: &TimeSeriesOptions) -> Result<Value> {
+    let (time_column, value_column) = options.columns()?;
+    let series = series_from_dataframe(df, time_column, value_column)?;
+    let format_date = |date: &NaiveDate| date.format("%Y-%m-%d").to_string();
+
+    // One output row per observation: timestamp, original value and the computed column
+    let per_observation = |name: &str, results: &[Option<f64>]| -> Vec<Value> {
+        series.timestamps.iter()
+            .zip(&series.values)
+            .zip(results)
+            .map(|((timestamp, value), result)| {
+                let mut row = serde_json::Map::new();
+                row.insert(time_column.to_string(), Value::from(series.format_timestamp(timestamp)));
+                row.insert(value_column.to_string(), value.map(finite_or_null).unwrap_or(Value::Null));
+                row.insert(name.to_string(), result.map(finite_or_null).unwrap_or(Value::Null));
+                Value::Object(row)
+            })
+            .collect()
+    };
+
+    let mut result = match operation {
+        "resample" => {
+            let aggregation = options.aggregation.unwrap_or(Aggregation::Sum);
+            let buckets = resample(&series, options.frequency, aggregation, options.include_empty);
+            let data: Vec<Value> = buckets.iter()
+                .map(|b| serde_json::json!({
+                    time_column: format_date(&b.start),
+                    value_column: b.value.map(finite_or_null).unwrap_or(Value::Null),
+                    "count": b.count
+                }))
+                .collect();
+            serde_json::json!({
+                "frequency": options.frequency,
+                "aggregation": aggregation,
+                "periods": data.len(),
+                "data": data
+            })
+        }
+        "rolling" => {
+            let aggregation = options.aggregation.unwrap_or(Aggregation::Mean);
+            let min_periods = options.min_periods.unwrap_or(options.window);
+            let values = rolling(&series.values, options.window, min_periods, options.center, aggregation)?;
+            serde_json::json!({
+                "window": options.window,
+                "min_periods": min_periods,
+                "center": options.center,
+                "aggregation": aggregation,
+                "data": per_observation("rolling", &values)
+            })
+        }
+        "difference" | "pct_change" => {
+            if options.periods == 0 {
+                return Err(anyhow!("'periods' must be at least 1"));
+            }
+            let values = if operation == "difference" {
+                difference(&series.values, options.periods)
+            } else {
+                pct_change(&series.values, options.periods)
+            };
+            serde_json::json!({
+                "periods": options.periods,
+                "data": per_observation(operation, &values)
+            })
+        }
+        "fill_gaps" => {
+            let points = fill_gaps(&series, options.frequency, options.fill_method)?;
+            let data: Vec<Value> = points.iter()
+                .map(|p| serde_json::json!({
+                    time_column: format_date(&p.start),
+                    value_column: p.value.map(finite_or_null).unwrap_or(Value::Null),
+                    "filled": p.filled
+                }))
+                .collect();
+            serde_json::json!({
+                "frequency": options.frequency,
+                "fill_method": options.fill_method,
+                "filled_count": points.iter().filter(|p| p.filled).count(),
+                "data": data
+            })
+        }
+        "seasonal_decompose" => {
+            let period = options.period
+                .ok_or_else(|| anyhow!("seasonal_decompose requires a 'period' parameter (observations per season)"))?;
+            let values = series.complete_values("decomposing")?;
+            let decomposition = seasonal_decompose(&values, period, options.model)?;
+
+            let data: Vec<Value> = series.timestamps.iter()
+                .enumerate()
+                .map(|(i, timestamp)| serde_json::json!({
+                    time_column: series.format_timestamp(timestamp),
+                    value_column: finite_or_null(values[i]),
+                    "trend": decomposition.trend[i].map(finite_or_null).unwrap_or(Value::Null),
+                    "seasonal": finite_or_null(decomposition.seasonal[i]),
+                    "residual": decomposition.residual[i].map(finite_or_null).unwrap_or(Value::Null)
+                }))
+                .collect();
+            serde_json::json!({
+                "model": options.model,
+                "period": period,
+                "seasonal_indices": decomposition.seasonal_indices,
+                "data": data
+            })
+        }
+        _ => return Err(anyhow!("Unknown time-series operation: {}", operation)),
+    };
+
+    result["operation"] = Value::from(operation);
+    result["time_column"] = Value::from(time_column);
+    result["value_column"] = Value::from(value_column);
+    result["rows_dropped"] = Value::from(series.dropped_rows);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn daily_frame() -> DataFrame {
+        df!(
+            "date" => ["2024-01-03", "2024-01-01", "2024-01-01", "2024-01-08", "bad", "2024-02-15"],
+            "amount" => [Some(5.0), Some(10.0), Some(2.0), None, Some(1.0), Some(7.0)]
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_resample_and_fill_gaps() {
+        let series = series_from_dataframe(&daily_frame(), "date", "amount").unwrap();
+        assert_eq!(series.dropped_rows, 1);
+
+        let weekly = resample(&series, Frequency::Week, Aggregation::Sum, true);
+        assert_eq!(weekly[0].start, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!((weekly[0].value, weekly[0].count), (Some(17.0), 3));
+        assert_eq!((weekly[1].value, weekly[1].count), (Some(0.0), 1));
+        assert_eq!(weekly.len(), 7);
+
+        let monthly = resample(&series, Frequency::Month, Aggregation::Mean, true);
+        assert_eq!(monthly.iter().map(|b| b.value).collect::<Vec<_>>(), vec![Some(17.0 / 3.0), Some(7.0)]);
+        assert_eq!(Frequency::Quarter.period_start(NaiveDate::from_ymd_opt(2024, 8, 20).unwrap()), NaiveDate::from_ymd_opt(2024, 7, 1).unwrap());
+
+        assert!(fill_gaps(&series, Frequency::Day, FillMethod::Linear).is_err());
+        let monthly_series = TimeSeries {
+            timestamps: ["2024-01-01", "2024-02-01", "2024-04-01"].iter().map(|d| parse_date_text(d).unwrap().and_time(NaiveTime::MIN)).collect(),
+            values: vec![Some(1.0), None, Some(7.0)],
+            date_only: true,
+            dropped_rows: 0,
+        };
+        let filled = fill_gaps(&monthly_series, Frequency::Month, FillMethod::Linear).unwrap();
+        assert_eq!(filled.iter().map(|p| p.value).collect::<Vec<_>>(), vec![Some(1.0), Some(3.0), Some(5.0), Some(7.0)]);
+        assert_eq!(filled.iter().filter(|p| p.filled).count(), 2);
+    }
+
+    #[test]
+    fn test_rolling_difference_and_pct_change() {
+        let values = vec![Some(1.0), Some(2.0), None, Some(4.0), Some(8.0)];
+        assert_eq!(rolling(&values, 2, 2, false, Aggregation::Sum).unwrap(), vec![None, Some(3.0), None, None, Some(12.0)]);
+        assert_eq!(rolling(&values, 3, 1, true, Aggregation::Max).unwrap(), vec![Some(2.0), Some(2.0), Some(4.0), Some(8.0), Some(8.0)]);
+        assert_eq!(difference(&values, 1), vec![None, Some(1.0), None, None, Some(4.0)]);
+        assert_eq!(pct_change(&values, 1), vec![None, Some(1.0), None, None, Some(1.0)]);
+        assert!(rolling(&values, 2, 3, false, Aggregation::Mean).is_err());
+    }
+
+    #[test]
+    fn test_seasonal_decomposition_recovers_pattern() {
+        let pattern = [3.0, -1.0, -2.0, 0.0];
+        let values: Vec<f64> = (0..16).map(|i| 10.0 + 0.5 * i as f64 + pattern[i % 4]).collect();
+        let decomposition = seasonal_decompose(&values, 4, DecompositionModel::Additive).unwrap();
+
+        for (index, expected) in decomposition.seasonal_indices.iter().zip(pattern) {
+            assert!((index - expected).abs() < 1e-12, "{:?}", decomposition.seasonal_indices);
+        }
+        assert_eq!(decomposition.trend[0], None);
+        assert!((decomposition.trend[2].unwrap() - 11.0).abs() < 1e-12);
+        assert!(decomposition.residual.iter().flatten().all(|r| r.abs() < 1e-12));
+        assert!(seasonal_decompose(&values[..6], 4, DecompositionModel::Additive).is_err());
+    }
+}
@@ -2,10 +2,17 @@ use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{info, warn, error};
 use uuid::Uuid;
 
+use crate::compute::run_blocking;
+use crate::data_processor::{dataframe_to_records, rows_to_dataframe};
+use crate::dataset::{self, DatasetFormat, LoadOptions};
+use crate::export::{self, ExportOptions};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowStep {
     pub id: String,
@@ -52,9 +59,11 @@ pub struct WorkflowResult {
     pub failed_steps: usize,
 }
 
+type StepProcessor = Arc<dyn Fn(&Value, Option<&Value>) -> Result<Value> + Send + Sync>;
+
 pub struct WorkflowEngine {
     workflows: Mutex<HashMap<String, WorkflowExecution>>,
-    step_processors: HashMap<String, Box<dyn Fn(&Value, Option<&Value>) -> Result<Value> + Send + Sync>>,
+    step_processors: HashMap<String, StepProcessor>,
 }
 
 impl WorkflowEngine {
@@ -73,7 +82,7 @@ impl WorkflowEngine {
     
     fn register_step_processors(&mut self) {
         // Data processing steps
-        self.step_processors.insert("data_transform".to_string(), Box::new(|data, params| {
+        self.step_processors.insert("data_transform".to_string(), Arc::new(|data, params| {
             let operation = params.and_then(|p| p.get("operation"))
                 .and_then(|p| p.as_str())
                 .ok_or_else(|| anyhow!("Data transform requires 'operation' parameter"))?;
@@ -160,7 +169,7 @@ impl WorkflowEngine {
         }));
         
         // File operations
-        self.step_processors.insert("file_operation".to_string(), Box::new(|_data, params| {
+        self.step_processors.insert("file_operation".to_string(), Arc::new(|data, params| {
             let operation = params.and_then(|p| p.get("operation"))
                 .and_then(|p| p.as_str())
                 .ok_or_else(|| anyhow!("File operation requires 'operation' parameter"))?;
@@ -171,24 +180,53 @@ impl WorkflowEngine {
                         .and_then(|p| p.as_str())
                         .ok_or_else(|| anyhow!("File operation requires 'file_path' parameter"))?;
                     
-                    // Simulate CSV reading
+                    let mut options: LoadOptions = params.and_then(|p| p.get("options"))
+                        .map(|o| serde_json::from_value(o.clone()))
+                        .transpose()
+                        .map_err(|e| anyhow!("Invalid read_csv options: {}", e))?
+                        .unwrap_or_default();
+                    
+                    // Unrecognised extensions are read as CSV
+                    let path = Path::new(file_path);
+                    if options.format.is_none() && DatasetFormat::from_path(path).is_none() {
+                        options.format = Some(DatasetFormat::Csv);
+                    }
+                    
+                    let df = dataset::load_from_path(path, &options)?;
+                    let columns: Vec<String> = df.get_column_names().iter().map(|s| s.to_string()).collect();
+                    
                     Ok(serde_json::json!({
                         "operation": "read_csv",
                         "file_path": file_path,
                         "status": "success",
-                        "message": "CSV file read successfully (simulated)"
+                        "rows": df.height(),
+                        "columns": columns,
+                        "data": dataframe_to_records(&df)?
                     }))
                 }
-                "write_json" => {
+                "write_json" | "write_file" => {
                     let file_path = params.and_then(|p| p.get("file_path"))
                         .and_then(|p| p.as_str())
                         .ok_or_else(|| anyhow!("File operation requires 'file_path' parameter"))?;
                     
+                    let mut options: ExportOptions = params.and_then(|p| p.get("options"))
+                        .map(|o| serde_json::from_value(o.clone()))
+                        .transpose()
+                        .map_err(|e| anyhow!("Invalid {} options: {}", operation, e))?
+                        .unwrap_or_default();
+                    if operation == "write_json" && options.format.is_none() {
+                        options.format = Some(DatasetFormat::Json);
+                    }
+                    
+                    let rows = records_from_step_data(data)?;
+                    let mut df = rows_to_dataframe(&rows, None)?;
+                    let summary = export::export_dataframe(&mut df, Path::new(file_path), &options)?;
+                    
                     Ok(serde_json::json!({
-                        "operation": "write_json",
+                        "operation": operation,
                         "file_path": file_path,
                         "status": "success",
-                        "message": "JSON file written successfully (simulated)"
+                        "export": summary
                     }))
                 }
                 _ => Err(anyhow!("Unknown file operation: {}", operation))
@@ -196,7 +234,7 @@ impl WorkflowEngine {
         }));
         
         // Conditional steps
-        self.step_processors.insert("conditional".to_string(), Box::new(|data, params| {
+        self.step_processors.insert("conditional".to_string(), Arc::new(|data, params| {
             let condition = params.and_then(|p| p.get("condition"))
                 .and_then(|p| p.as_str())
                 .ok_or_else(|| anyhow!("Conditional requires 'condition' parameter"))?;
@@ -223,7 +261,7 @@ impl WorkflowEngine {
         }));
         
         // Delay steps
-        self.step_processors.insert("delay".to_string(), Box::new(|_data, params| {
+        self.step_processors.insert("delay".to_string(), Arc::new(|_data, params| {
             let duration_ms = params.and_then(|p| p.get("duration_ms"))
                 .and_then(|p| p.as_u64())
                 .unwrap_or(1000);
@@ -470,12 +508,12 @@ impl WorkflowEngine {
         
         // Get step processor
         let processor = self.step_processors.get(&step.operation)
-            .ok_or_else(|| anyhow!("Unknown step operation: {}", step.operation))?;
-        
-        // Execute step
-        let result = processor(&input_data, step.parameters.as_ref())?;
+            .ok_or_else(|| anyhow!("Unknown step operation: {}", step.operation))?
+            .clone();
         
-        Ok(result)
+        // Execute step on the blocking pool; file steps read and write whole files
+        let parameters = step.parameters.clone();
+        run_blocking(move || processor(&input_data, parameters.as_ref())).await
     }
     
     pub async fn get_workflow_status(&self, workflow_id: &str) -> Option<WorkflowExecution> {
@@ -500,6 +538,26 @@ impl WorkflowEngine {
     }
 }
 
+// Step input is either a list of records, a step result carrying a "data" list (e.g. read_csv),
+// or the list of dependency results, whose records are concatenated
+fn records_from_step_data(data: &Value) -> Result<Vec<HashMap<String, Value>>> {
+    let records = match data {
+        Value::Object(map) if map.get("data").is_some_and(Value::is_array) => map["data"].as_array().cloned().unwrap_or_default(),
+        Value::Array(items) if !items.is_empty() && items.iter().all(|item| item.get("data").is_some_and(Value::is_array)) => {
+            items.iter().flat_map(|item| item["data"].as_array().cloned().unwrap_or_default()).collect()
+        }
+        Value::Array(items) => items.clone(),
+        _ => return Err(anyhow!("File write requires a list of records as input")),
+    };
+    
+    records.into_iter()
+        .map(|record| match record {
+            Value::Object(map) => Ok(map.into_iter().collect()),
+            other => Err(anyhow!("Expected a record object, got {}", other)),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -533,4 +591,32 @@ mod tests {
         assert!(!workflow_id.is_empty());
         assert_eq!(result["status"], "completed");
     }
+    
+    #[tokio::test]
+    async fn test_write_json_step_writes_file() {
+        let engine = WorkflowEngine::new().await;
+        let path = std::env::temp_dir().join(format!("workflow-{}.json", Uuid::new_v4()));
+        
+        let steps = vec![
+            WorkflowStep {
+                id: "write".to_string(),
+                operation: "file_operation".to_string(),
+                dependencies: vec![],
+                data: serde_json::json!([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]),
+                parameters: Some(serde_json::json!({
+                    "operation": "write_json",
+                    "file_path": path.to_string_lossy()
+                })),
+                timeout_ms: None,
+                retry_count: None,
+            }
+        ];
+        
+        let (_, result) = engine.execute_workflow("export_workflow", &steps, None).await.unwrap();
+        assert_eq!(result["results"]["write"]["export"]["rows"], 2);
+        
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written[1]["name"], "b");
+        std::fs::remove_file(path).unwrap();
+    }
 }